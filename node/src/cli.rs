@@ -1,7 +1,13 @@
 use node_template_runtime::pallet_zk_snarks::{
-	common::{prepare_proof, prepare_verification_key},
-	deserialization::{deserialize_public_inputs, Proof, VKey},
-	verify::{prepare_public_inputs, verify},
+	bn254::Bn254,
+	common::{
+		prepare_fflonk_proof, prepare_fflonk_verification_key, prepare_plonk_proof,
+		prepare_plonk_verification_key, prepare_proof, prepare_verification_key,
+	},
+	deserialization::{deserialize_public_inputs, ProtocolProof, ProtocolVKey, PublicInput},
+	fflonk::verify_fflonk,
+	plonk::verify_plonk,
+	verify::{prepare_public_inputs, verify, Bls12_381, Curve, SupportedCurve, VerificationError},
 };
 use sc_cli::RunCmd;
 use std::{fs::File, io::Read};
@@ -85,15 +91,20 @@ impl ZkSnarksVerifyCmd {
 		let mut inputs_contents = String::new();
 		inputs_file.read_to_string(&mut inputs_contents)?;
 
-		let vk = VKey::from_json_u8_slice(vk_contents.as_bytes()).unwrap();
-		let proof = Proof::from_json_u8_slice(proof_contents.as_bytes()).unwrap();
+		let vk = ProtocolVKey::from_json_u8_slice(vk_contents.as_bytes()).unwrap();
+		let proof = ProtocolProof::from_json_u8_slice(proof_contents.as_bytes()).unwrap();
 		let inputs = deserialize_public_inputs(inputs_contents.as_bytes()).unwrap();
 
-		match verify(
-			prepare_verification_key(vk).unwrap(),
-			prepare_proof(proof).unwrap(),
-			prepare_public_inputs(inputs),
-		) {
+		let result = match SupportedCurve::from_name(vk.curve()) {
+			Some(SupportedCurve::Bls12381) => verify_with::<Bls12_381>(vk, proof, inputs),
+			Some(SupportedCurve::Bn254) => verify_with::<Bn254>(vk, proof, inputs),
+			None => {
+				println!("Curve not supported");
+				return Ok(())
+			},
+		};
+
+		match result {
 			Ok(true) => println!("Proof OK"),
 			Ok(false) => println!("Proof NOK"),
 			Err(_) => println!("Verification error"),
@@ -101,3 +112,29 @@ impl ZkSnarksVerifyCmd {
 		Ok(())
 	}
 }
+
+fn verify_with<C: Curve>(
+	vk: ProtocolVKey,
+	proof: ProtocolProof,
+	inputs: Vec<PublicInput>,
+) -> Result<bool, VerificationError> {
+	let inputs = prepare_public_inputs::<C>(inputs).unwrap();
+	match (vk, proof) {
+		(ProtocolVKey::Groth16(vk), ProtocolProof::Groth16(proof)) => verify(
+			prepare_verification_key::<C>(vk).unwrap(),
+			prepare_proof::<C>(proof).unwrap(),
+			inputs,
+		),
+		(ProtocolVKey::Plonk(vk), ProtocolProof::Plonk(proof)) => verify_plonk(
+			prepare_plonk_verification_key::<C>(vk).unwrap(),
+			prepare_plonk_proof::<C>(proof).unwrap(),
+			inputs,
+		),
+		(ProtocolVKey::Fflonk(vk), ProtocolProof::Fflonk(proof)) => verify_fflonk(
+			prepare_fflonk_verification_key::<C>(vk).unwrap(),
+			prepare_fflonk_proof::<C>(proof).unwrap(),
+			inputs,
+		),
+		_ => Err(VerificationError::InvalidVerificationKey),
+	}
+}
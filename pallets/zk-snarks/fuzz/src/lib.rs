@@ -0,0 +1,79 @@
+// MIT License
+
+// Copyright (c) 2022 Bright Inventions
+
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Entry points of the fuzz targets, shared with the regression test of the checked in corpus
+
+use pallet_zk_snarks::{
+	deserialization::{deserialize_public_inputs, Proof, VKey},
+	mock::{new_test_ext, RuntimeOrigin, ZKSnarks, VERIFIED},
+	CircuitId,
+};
+
+const CIRCUIT_ID: CircuitId = 1;
+const ALICE_ACCOUNT_ID: u64 = 1;
+const VERIFICATION_KEY: &[u8] = include_bytes!("../../../../blog/data/verification_key.json");
+const PUBLIC_INPUTS: &[u8] = include_bytes!("../../../../blog/data/input.json");
+
+pub fn vkey_json(data: &[u8]) {
+	let _ = VKey::from_json_u8_slice(data);
+}
+
+pub fn proof_json(data: &[u8]) {
+	let _ = Proof::from_json_u8_slice(data);
+}
+
+pub fn public_inputs(data: &[u8]) {
+	let _ = deserialize_public_inputs(data);
+}
+
+/// Submits `data` as the proof of the circuit registered with `blog/data/verification_key.json`.
+/// A zero byte, which never occurs in json, separates the proof from the public inputs, otherwise
+/// `blog/data/input.json` is used.
+pub fn verify(data: &[u8]) {
+	let (proof, public_inputs) = match data.iter().position(|byte| *byte == 0) {
+		Some(separator) => (&data[..separator], &data[separator + 1..]),
+		None => (data, PUBLIC_INPUTS),
+	};
+	new_test_ext().execute_with(|| {
+		ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			VERIFICATION_KEY.to_vec(),
+			None,
+		)
+		.expect("verification key of the blog is valid");
+		let _ = ZKSnarks::verify(
+			RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+			CIRCUIT_ID,
+			proof.to_vec(),
+			public_inputs.to_vec(),
+		);
+	});
+	// Statements recorded by the mock `OnProofVerified` hook would pile up between the runs
+	VERIFIED.with(|verified| verified.borrow_mut().clear());
+}
@@ -0,0 +1,67 @@
+// MIT License
+
+// Copyright (c) 2022 Bright Inventions
+
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Runs the seed corpus and the inputs which used to crash the pallet through the fuzz targets
+
+use std::{fs, path::Path};
+
+fn run_corpus(target: &str, entry_point: fn(&[u8])) {
+	let fuzz_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+	for corpus in ["corpus", "regressions"] {
+		let dir = fuzz_dir.join(corpus).join(target);
+		for entry in fs::read_dir(&dir).unwrap_or_else(|_| panic!("{} is missing", dir.display())) {
+			let path = entry.unwrap().path();
+			let name = path.file_name().unwrap().to_string_lossy();
+			// Only the seeds of the fuzzing corpus are checked in
+			if corpus == "corpus" && !name.starts_with("seed_") {
+				continue
+			}
+			entry_point(&fs::read(&path).unwrap());
+		}
+	}
+}
+
+#[test]
+fn vkey_json_corpus() {
+	run_corpus("vkey_json", pallet_zk_snarks_fuzz::vkey_json);
+}
+
+#[test]
+fn proof_json_corpus() {
+	run_corpus("proof_json", pallet_zk_snarks_fuzz::proof_json);
+}
+
+#[test]
+fn public_inputs_corpus() {
+	run_corpus("public_inputs", pallet_zk_snarks_fuzz::public_inputs);
+}
+
+#[test]
+fn verify_corpus() {
+	run_corpus("verify", pallet_zk_snarks_fuzz::verify);
+}
@@ -0,0 +1,146 @@
+// MIT License
+
+// Copyright (c) 2022 Bright Inventions
+
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! RPC interface of the ZK-Snarks pallet.
+
+use std::sync::Arc;
+
+use jsonrpsee::{
+	core::{Error as JsonRpseeError, RpcResult},
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+use pallet_zk_snarks_runtime_api::CircuitId;
+pub use pallet_zk_snarks_runtime_api::ZkSnarksApi as ZkSnarksRuntimeApi;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+#[rpc(client, server)]
+pub trait ZkSnarksApi<BlockHash> {
+	/// Verify a proof against the circuit registered under `circuit_id`, like the `verify`
+	/// extrinsic does, but without submitting and paying for it.
+	#[method(name = "zkSnarks_verify")]
+	fn verify(
+		&self,
+		circuit_id: CircuitId,
+		proof: Bytes,
+		public_inputs: Bytes,
+		at: Option<BlockHash>,
+	) -> RpcResult<bool>;
+
+	/// The verification key registered under `circuit_id`, `None` if there is no such circuit.
+	#[method(name = "zkSnarks_getVerificationKey")]
+	fn get_verification_key(
+		&self,
+		circuit_id: CircuitId,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<Bytes>>;
+}
+
+/// Provides RPC methods to query the ZK-Snarks pallet.
+pub struct ZkSnarks<C, Block> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<Block>,
+}
+
+impl<C, Block> ZkSnarks<C, Block> {
+	/// Creates a new instance of the ZK-Snarks RPC handler.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+	/// The proof could not be verified, e.g. it is malformed or the circuit is not registered.
+	VerificationError,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::RuntimeError => 1,
+			Error::VerificationError => 2,
+		}
+	}
+}
+
+impl<C, Block> ZkSnarksApiServer<<Block as BlockT>::Hash> for ZkSnarks<C, Block>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: ZkSnarksRuntimeApi<Block>,
+{
+	fn verify(
+		&self,
+		circuit_id: CircuitId,
+		proof: Bytes,
+		public_inputs: Bytes,
+		at: Option<Block::Hash>,
+	) -> RpcResult<bool> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.verify_proof(&at, circuit_id, proof.to_vec(), public_inputs.to_vec())
+			.map_err(|e| runtime_error("Unable to verify the proof.", e))?
+			.map_err(|e| {
+				CallError::Custom(ErrorObject::owned(
+					Error::VerificationError.into(),
+					"Proof verification error.",
+					Some(format!("{:?}", e)),
+				))
+				.into()
+			})
+	}
+
+	fn get_verification_key(
+		&self,
+		circuit_id: CircuitId,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Option<Bytes>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.get_verification_key(&at, circuit_id)
+			.map(|vk| vk.map(Into::into))
+			.map_err(|e| runtime_error("Unable to query the verification key.", e))
+	}
+}
+
+fn runtime_error(message: &str, e: impl std::fmt::Debug) -> JsonRpseeError {
+	CallError::Custom(ErrorObject::owned(
+		Error::RuntimeError.into(),
+		message,
+		Some(format!("{:?}", e)),
+	))
+	.into()
+}
@@ -0,0 +1,50 @@
+// MIT License
+
+// Copyright (c) 2022 Bright Inventions
+
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Runtime API definition of the ZK-Snarks pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use pallet_zk_snarks::CircuitId;
+use sp_runtime::DispatchError;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	pub trait ZkSnarksApi {
+		/// Verify a proof against the circuit registered under `circuit_id` without submitting
+		/// an extrinsic, nothing is stored.
+		fn verify_proof(
+			circuit_id: CircuitId,
+			proof: Vec<u8>,
+			public_inputs: Vec<u8>,
+		) -> Result<bool, DispatchError>;
+
+		/// The verification key registered under `circuit_id`.
+		fn get_verification_key(circuit_id: CircuitId) -> Option<Vec<u8>>;
+	}
+}
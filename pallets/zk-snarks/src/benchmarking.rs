@@ -27,158 +27,552 @@
 // DEALINGS IN THE SOFTWARE.
 
 use super::*;
-use frame_benchmarking::benchmarks;
+use crate::{
+	bn254::Bn254,
+	compact::CompactProof,
+	deserialization::{Proof, U256},
+	jacobian::U768,
+	verify::{Bls12_381, Curve, SupportedCurve},
+};
+use codec::Encode;
+use frame_benchmarking::{benchmarks, whitelisted_caller};
+use frame_support::traits::{EnsureOrigin, Get};
 use frame_system::RawOrigin;
+use sp_io::hashing::blake2_256;
+use sp_std::{
+	string::{String, ToString},
+	vec::Vec,
+};
 
 use crate::Pallet as ZKSnarks;
 
+const CIRCUIT_ID: CircuitId = 1;
+
+/// Length of a public input in json, a full size scalar with quotes and a comma.
+const PUBLIC_INPUT_JSON_LEN: u32 = 80;
+/// Upper bound of the length of a verification key json without its `IC` points.
+const VK_JSON_BASE_LEN: u32 = 2_500;
+/// Upper bound of the length of a point of `IC` in json, with all three coordinates full size.
+const IC_POINT_JSON_LEN: u32 = 360;
+
 benchmarks! {
-	setup_verification_benchmark {
-		let key: Vec<u8> = prepare_vk_json().as_bytes().into();
-		let public_inputs: Vec<u8> = prepare_public_inputs_json().as_bytes().into();
-	}: setup_verification(RawOrigin::None, public_inputs, key)
-
-	verify_benchmark {
-		let key: Vec<u8> = prepare_vk_json().as_bytes().into();
-		let proof: Vec<u8> = prepare_proof_json().as_bytes().into();
-		let public_inputs: Vec<u8> = prepare_public_inputs_json().as_bytes().into();
-		ZKSnarks::<T>::setup_verification(RawOrigin::None.into(), public_inputs, key).expect("This should work...");
-	}: verify(RawOrigin::None, proof)
+	register_circuit {
+		let vk_ic_len in 1 .. max_ic_len::<T>();
+		let circuit = Circuit::<Bls12_381>::new(vk_ic_len - 1);
+		let key = circuit.vk_json();
+		let origin = T::SetupOrigin::successful_origin();
+	}: _(origin, CIRCUIT_ID, key, None)
+	verify {
+		assert!(DecodedVerificationKeyStorage::<T>::contains_key(CIRCUIT_ID));
+	}
+
+	verify {
+		let n_public in 1 .. max_public_inputs::<T>();
+		let circuit = Circuit::<Bls12_381>::new(n_public);
+		let inputs = public_inputs::<Bls12_381>(n_public, 0);
+		let proof = circuit.proof_json(&inputs, 0);
+		let proof_hash = blake2_256(&proof);
+		register::<T>(circuit.vk_json());
+		let caller: T::AccountId = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller), CIRCUIT_ID, proof, public_inputs_json::<Bls12_381>(&inputs))
+	verify {
+		assert_eq!(recorded_result::<T>(proof_hash), Some(true));
+	}
+
+	verify_bn254 {
+		let n_public in 1 .. max_public_inputs::<T>();
+		let circuit = Circuit::<Bn254>::new(n_public);
+		let inputs = public_inputs::<Bn254>(n_public, 0);
+		let proof = circuit.proof_json(&inputs, 0);
+		let proof_hash = blake2_256(&proof);
+		let pub_input = public_inputs_json::<Bn254>(&inputs);
+		register::<T>(circuit.vk_json());
+		let caller: T::AccountId = whitelisted_caller();
+	}: {
+		ZKSnarks::<T>::verify(RawOrigin::Signed(caller).into(), CIRCUIT_ID, proof, pub_input)
+			.expect("The proof should be verified");
+	}
+	verify {
+		assert_eq!(recorded_result::<T>(proof_hash), Some(true));
+	}
+
+	verify_compact {
+		let n_public in 1 .. max_public_inputs::<T>();
+		let circuit = Circuit::<Bls12_381>::new(n_public);
+		let inputs = public_inputs::<Bls12_381>(n_public, 0);
+		let proof = circuit.compact_proof(&inputs, 0);
+		let proof_hash = blake2_256(&proof);
+		let pub_input = public_inputs_json::<Bls12_381>(&inputs);
+		register::<T>(circuit.vk_json());
+		let caller: T::AccountId = whitelisted_caller();
+	}: {
+		ZKSnarks::<T>::verify(RawOrigin::Signed(caller).into(), CIRCUIT_ID, proof, pub_input)
+			.expect("The proof should be verified");
+	}
+	verify {
+		assert_eq!(recorded_result::<T>(proof_hash), Some(true));
+	}
+
+	verify_compact_bn254 {
+		let n_public in 1 .. max_public_inputs::<T>();
+		let circuit = Circuit::<Bn254>::new(n_public);
+		let inputs = public_inputs::<Bn254>(n_public, 0);
+		let proof = circuit.compact_proof(&inputs, 0);
+		let proof_hash = blake2_256(&proof);
+		let pub_input = public_inputs_json::<Bn254>(&inputs);
+		register::<T>(circuit.vk_json());
+		let caller: T::AccountId = whitelisted_caller();
+	}: {
+		ZKSnarks::<T>::verify(RawOrigin::Signed(caller).into(), CIRCUIT_ID, proof, pub_input)
+			.expect("The proof should be verified");
+	}
+	verify {
+		assert_eq!(recorded_result::<T>(proof_hash), Some(true));
+	}
+
+	verify_plonk {
+		let n_public in 1 .. max_public_inputs::<T>();
+		let proof = plonk_proof_json::<Bls12_381>();
+		let proof_hash = blake2_256(&proof);
+		let pub_input = public_inputs_json::<Bls12_381>(&public_inputs::<Bls12_381>(n_public, 0));
+		register::<T>(plonk_vk_json::<Bls12_381>(n_public));
+		let caller: T::AccountId = whitelisted_caller();
+	}: {
+		ZKSnarks::<T>::verify(RawOrigin::Signed(caller).into(), CIRCUIT_ID, proof, pub_input)
+			.expect("The proof should be verified");
+	}
+	verify {
+		assert_eq!(recorded_result::<T>(proof_hash), Some(false));
+	}
+
+	verify_plonk_bn254 {
+		let n_public in 1 .. max_public_inputs::<T>();
+		let proof = plonk_proof_json::<Bn254>();
+		let proof_hash = blake2_256(&proof);
+		let pub_input = public_inputs_json::<Bn254>(&public_inputs::<Bn254>(n_public, 0));
+		register::<T>(plonk_vk_json::<Bn254>(n_public));
+		let caller: T::AccountId = whitelisted_caller();
+	}: {
+		ZKSnarks::<T>::verify(RawOrigin::Signed(caller).into(), CIRCUIT_ID, proof, pub_input)
+			.expect("The proof should be verified");
+	}
+	verify {
+		assert_eq!(recorded_result::<T>(proof_hash), Some(false));
+	}
+
+	verify_fflonk {
+		let n_public in 1 .. max_public_inputs::<T>();
+		let proof = fflonk_proof_json::<Bls12_381>();
+		let proof_hash = blake2_256(&proof);
+		let pub_input = public_inputs_json::<Bls12_381>(&public_inputs::<Bls12_381>(n_public, 0));
+		register::<T>(fflonk_vk_json::<Bls12_381>(n_public));
+		let caller: T::AccountId = whitelisted_caller();
+	}: {
+		ZKSnarks::<T>::verify(RawOrigin::Signed(caller).into(), CIRCUIT_ID, proof, pub_input)
+			.expect("The proof should be verified");
+	}
+	verify {
+		assert_eq!(recorded_result::<T>(proof_hash), Some(false));
+	}
+
+	verify_fflonk_bn254 {
+		let n_public in 1 .. max_public_inputs::<T>();
+		let proof = fflonk_proof_json::<Bn254>();
+		let proof_hash = blake2_256(&proof);
+		let pub_input = public_inputs_json::<Bn254>(&public_inputs::<Bn254>(n_public, 0));
+		register::<T>(fflonk_vk_json::<Bn254>(n_public));
+		let caller: T::AccountId = whitelisted_caller();
+	}: {
+		ZKSnarks::<T>::verify(RawOrigin::Signed(caller).into(), CIRCUIT_ID, proof, pub_input)
+			.expect("The proof should be verified");
+	}
+	verify {
+		assert_eq!(recorded_result::<T>(proof_hash), Some(false));
+	}
+
+	// A proof shorter than its json is cut short, so it is rejected before the public inputs are
+	// parsed, a longer one is padded with whitespace. The public inputs are cut short the same way.
+	verify_parse_failure {
+		let p in 1 .. T::MaxProofLength::get();
+		let i in 1 .. T::MaxPublicInputsLength::get();
+		let circuit = Circuit::<Bls12_381>::new(1);
+		let mut proof = circuit.proof_json(&public_inputs::<Bls12_381>(1, 0), 0);
+		proof.resize(p as usize, b' ');
+		let mut pub_input = overflowing_public_inputs_json(i / PUBLIC_INPUT_JSON_LEN + 1);
+		pub_input.truncate(i as usize);
+		let caller: T::AccountId = whitelisted_caller();
+	}: {
+		let result =
+			ZKSnarks::<T>::verify(RawOrigin::Signed(caller).into(), CIRCUIT_ID, proof, pub_input);
+		assert!(result.is_err());
+	}
+
+	verify_decode_failure {
+		let circuit = Circuit::<Bls12_381>::new(1);
+		let inputs = public_inputs::<Bls12_381>(1, 0);
+		// `z` of `pi_c` set to 2 without scaling `x` and `y`, so `pi_c` is not on the curve
+		let z = ["\"", &minus_one::<Bls12_381>(), r#""],"protocol""#].concat();
+		let proof = String::from_utf8(circuit.proof_json(&inputs, 0))
+			.expect("The json is ascii")
+			.replacen(&z, r#""2"],"protocol""#, 1)
+			.into_bytes();
+		let pub_input = public_inputs_json::<Bls12_381>(&inputs);
+		register::<T>(circuit.vk_json());
+		let caller: T::AccountId = whitelisted_caller();
+	}: {
+		let result =
+			ZKSnarks::<T>::verify(RawOrigin::Signed(caller).into(), CIRCUIT_ID, proof, pub_input);
+		assert_eq!(result.map_err(|e| e.error), Err(Error::<T>::PointNotOnCurve.into()));
+	}
+
+	verify_batch {
+		let b in 1 .. T::MaxBatchSize::get();
+		let n_public in 1 .. max_public_inputs::<T>();
+		let circuit = Circuit::<Bls12_381>::new(n_public);
+		let proofs: Vec<(Vec<u8>, Vec<u8>)> = (0..b as u64)
+			.map(|seed| {
+				let inputs = public_inputs::<Bls12_381>(n_public, seed);
+				(circuit.proof_json(&inputs, seed), public_inputs_json::<Bls12_381>(&inputs))
+			})
+			.collect();
+		let proof_hashes: Vec<ProofHash> =
+			proofs.iter().map(|(proof, _)| blake2_256(proof)).collect();
+		register::<T>(circuit.vk_json());
+		let caller: T::AccountId = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller), CIRCUIT_ID, proofs)
+	verify {
+		for proof_hash in proof_hashes {
+			assert_eq!(recorded_result::<T>(proof_hash), Some(true));
+		}
+	}
+
+	// The last proof is invalid, so the batch check fails and every proof is verified on its own
+	verify_batch_invalid {
+		let b in 1 .. T::MaxBatchSize::get();
+		let n_public in 1 .. max_public_inputs::<T>();
+		let circuit = Circuit::<Bls12_381>::new(n_public);
+		let proofs: Vec<(Vec<u8>, Vec<u8>)> = (0..b as u64)
+			.map(|seed| {
+				let inputs = public_inputs::<Bls12_381>(n_public, seed);
+				let proof = if seed + 1 == b as u64 {
+					circuit.proof_json(&public_inputs::<Bls12_381>(n_public, seed + 1), seed)
+				} else {
+					circuit.proof_json(&inputs, seed)
+				};
+				(proof, public_inputs_json::<Bls12_381>(&inputs))
+			})
+			.collect();
+		let proof_hashes: Vec<ProofHash> =
+			proofs.iter().map(|(proof, _)| blake2_256(proof)).collect();
+		register::<T>(circuit.vk_json());
+		let caller: T::AccountId = whitelisted_caller();
+	}: {
+		ZKSnarks::<T>::verify_batch(RawOrigin::Signed(caller).into(), CIRCUIT_ID, proofs)
+			.expect("The batch should be verified");
+	}
+	verify {
+		for (index, proof_hash) in proof_hashes.into_iter().enumerate() {
+			let valid = index + 1 != b as usize;
+			assert_eq!(recorded_result::<T>(proof_hash), Some(valid));
+		}
+	}
+
+	set_unsigned_submission {
+		let circuit = Circuit::<Bls12_381>::new(1);
+		let origin = T::SetupOrigin::successful_origin();
+		ZKSnarks::<T>::register_circuit(origin.clone(), CIRCUIT_ID, circuit.vk_json(), Some(0))
+			.expect("This should work...");
+		let submission = Some(UnsignedSubmission { priority: 1, longevity: 64 });
+	}: _(origin, CIRCUIT_ID, submission)
+	verify {
+		assert_eq!(UnsignedSubmissionStorage::<T>::get(CIRCUIT_ID), submission);
+	}
 
 	impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test)
 }
 
-fn prepare_public_inputs_json() -> String {
-	r#"[
- "33"
-]"#
-	.to_owned()
-}
-
-fn prepare_vk_json() -> String {
-	r#"{
- "protocol": "groth16",
- "curve": "bls12381",
- "nPublic": 1,
- "vk_alpha_1": [
-  "2635983656263320256511463995836413167331869092392943593306076905516259749312747842295447349507189592731785901862558",
-  "743892996456702519498029594549937288641619275055957975879157306988929970626325326222697609972550552691064908651931",
-  "1"
- ],
- "vk_beta_2": [
-  [
-   "1296094501238138520689116246487755613076576267512760150298482409401507546730337296489670510433482825207790998397346",
-   "3467549840163329914429787393326495235851806074050417925094845001935796859739058829480949031354270816778136382040361"
-  ],
-  [
-   "3403410200913851046378881164751590587066009874691619938225021193334979700147466129997648606538377099567064346931273",
-   "3804847074485539411700684267722735363688167108429634491643293100788171321105199556340902873511607444652008144844173"
-  ],
-  [
-   "1",
-   "0"
-  ]
- ],
- "vk_gamma_2": [
-  [
-   "352701069587466618187139116011060144890029952792775240219908644239793785735715026873347600343865175952761926303160",
-   "3059144344244213709971259814753781636986470325476647558659373206291635324768958432433509563104347017837885763365758"
-  ],
-  [
-   "1985150602287291935568054521177171638300868978215655730859378665066344726373823718423869104263333984641494340347905",
-   "927553665492332455747201965776037880757740193453592970025027978793976877002675564980949289727957565575433344219582"
-  ],
-  [
-   "1",
-   "0"
-  ]
- ],
- "vk_delta_2": [
-  [
-   "3284950120787447527021651154232749836311526699432807747366843303661566424019671125328694916562846460813145647040459",
-   "3218306808275776807419693666072599084905639169987324420818366627509865827220976650759812930713956208246000627242485"
-  ],
-  [
-   "3945290144137392347873751586031392152201459997902585432454016489727689337013866944382877542451368688652560743518350",
-   "3505020872425170466568261366418107787485649574216477007429328593907934456720034754142706045279478244784882964969099"
-  ],
-  [
-   "1",
-   "0"
-  ]
- ],
- "vk_alphabeta_12": [
-  [
-   [
-    "2875682627859788046787727046323207700818211438271057184016590533515641699292997693457599620936708894947724425715231",
-    "1238832727101571020174962081840437018121939792461931445079462232794726384752259447129206595688503509959353794284793"
-   ],
-   [
-    "1142295393527745936520465586775444768688364741373237930118445421796520414741916849824256960449892474465014692624756",
-    "2180077006016464788050801868062734927906767334913187269719534809313436039282935753136702491423916116028147695108113"
-   ],
-   [
-    "581912189975592585217934845255593126879157415518933223520266217690258707840190591176174259119561150786976644927862",
-    "1496521185256234033198775390415811847166244093737149241712223242576005202124661827966889067451826629794876020037891"
-   ]
-  ],
-  [
-   [
-    "968778761326544533347894440852946317832878172436078056438728764792716948106777133186592741979864246862480026990714",
-    "3286237875677076419439678035167386721716851772116127087476697302808027553397980022598381369816552966804476744614726"
-   ],
-   [
-    "703046133019192877150497098682775062870944581080811653558417167836034365682308629278579084636589495681129838804552",
-    "3120651492951743750811126470515331662411558962596191689455151216422711804034698152168980665082907679235009776566592"
-   ],
-   [
-    "3093035865177537484265129293484086930964325066660842965056946750881983192007730606218463861804151316907199193750598",
-    "2217088332657331378025998358211322741524769834682072728928845130805944349335376146743275401044544953792401446016391"
-   ]
-  ]
- ],
- "IC": [
-  [
-   "3759794041598018594287463133849401670165044879836734797942436987012929463856866218164906521458646350224910548839839",
-   "3238512100593065266229132824040292706800754984648723917955334599968665051423411534393542324672325614522917210582797",
-   "1"
-  ],
-  [
-   "3305881491744710205856868316456114914540772066725994230747514104922282269209779243587827394909802115252764372519712",
-   "2462443929524735084767395208674598757462820081953985438437610428598624587728712969052746628125821805697605346885091",
-   "1"
-  ]
- ]
-}"#.to_owned()
-}
-
-fn prepare_proof_json() -> String {
-	r#"{
- "pi_a": [
-  "2820173869801000183955769496344276101575675010174203082588560105436284422640780128231242184109173767085197647834267",
-  "1152541093585973172499551859168528642628429504007613830168996825879806250289422935864437193085184388469171892221011",
-  "1"
- ],
- "pi_b": [
-  [
-   "54413090665354594353317256815335793052197307111690011609872716599840507808706991989403605000342095250665180513594",
-   "3343285764332210309442703216841128605678475246673133285314301861643378387001264758819444434632415207857557469906035"
-  ],
-  [
-   "262180403851765105493218367619507205740764669171746348153545090105487398261554724750259283942935411519021270362742",
-   "3777303780170739988308854254585940898119682705621212814969008224084499326863117961704608873229314936725151172212883"
-  ],
-  [
-   "1",
-   "0"
-  ]
- ],
- "pi_c": [
-  "3006923877346016048391409264528383002939756547318806158402407618139299715778986391175418348881376388499383266389442",
-  "1307513151230758506579817970515482216448699470263630520204374492458260823057506418477833081567163581258564509876945",
-  "1"
- ],
- "protocol": "groth16",
- "curve": "bls12381"
-}"#.to_owned()
+/// The largest `IC` of a verification key json which fits in `MaxVerificationKeyLength`.
+fn max_ic_len<T: Config>() -> u32 {
+	let len = T::MaxVerificationKeyLength::get().saturating_sub(VK_JSON_BASE_LEN);
+	(len / IC_POINT_JSON_LEN).min(u8::MAX as u32 + 1)
+}
+
+/// The largest number of full size public inputs whose json, brackets included, fits in
+/// `MaxPublicInputsLength` and which fit in the largest verification key.
+fn max_public_inputs<T: Config>() -> u32 {
+	let len = T::MaxPublicInputsLength::get().saturating_sub(2);
+	(len / PUBLIC_INPUT_JSON_LEN).min(max_ic_len::<T>().saturating_sub(1))
+}
+
+fn register<T: Config>(key: Vec<u8>) {
+	let origin = T::SetupOrigin::successful_origin();
+	ZKSnarks::<T>::register_circuit(origin, CIRCUIT_ID, key, None).expect("This should work...");
+}
+
+/// Whether the proof was recorded as valid, `None` if it was not recorded.
+fn recorded_result<T: Config>(proof_hash: ProofHash) -> Option<bool> {
+	VerificationRecords::<T>::get(CIRCUIT_ID, proof_hash).map(|record| record.result)
+}
+
+/// Curve of the generated keys and proofs.
+trait BenchmarkCurve: Curve {
+	const CURVE: SupportedCurve;
+
+	fn name() -> &'static str {
+		core::str::from_utf8(Self::CURVE.name()).expect("The name is ascii")
+	}
+}
+
+impl BenchmarkCurve for Bls12_381 {
+	const CURVE: SupportedCurve = SupportedCurve::Bls12381;
+}
+
+impl BenchmarkCurve for Bn254 {
+	const CURVE: SupportedCurve = SupportedCurve::Bn254;
+}
+
+/// Groth16 circuit with the toxic waste known, so a valid proof of any statement can be computed
+/// without a prover. All G2 points are the generator, which costs the same to decode and pair as
+/// any other point.
+struct Circuit<C: BenchmarkCurve> {
+	alpha: C::Scalar,
+	ic: Vec<C::Scalar>,
+}
+
+impl<C: BenchmarkCurve> Circuit<C> {
+	fn new(n_public: u32) -> Self {
+		Circuit {
+			alpha: C::scalar_from_u64(3),
+			ic: (0..=n_public as u64).map(|i| C::scalar_from_u64(13 + i)).collect(),
+		}
+	}
+
+	fn vk_json(&self) -> Vec<u8> {
+		let ic: Vec<String> = self.ic.iter().map(|ic| g1_json::<C>(ic)).collect();
+		let g2 = g2_json::<C>();
+		json_object(&[
+			("protocol", "\"groth16\"".into()),
+			("curve", curve_json::<C>()),
+			("nPublic", (self.ic.len() - 1).to_string()),
+			("vk_alpha_1", g1_json::<C>(&self.alpha)),
+			("vk_beta_2", g2.clone()),
+			("vk_gamma_2", g2.clone()),
+			("vk_delta_2", g2),
+			("IC", ["[", &ic.join(","), "]"].concat()),
+		])
+	}
+
+	/// A = a * G1, B = G2 and C = c * G1, where c is chosen so that a = α + Σ icᵢ * wᵢ + c.
+	fn proof_json(&self, inputs: &[C::Scalar], seed: u64) -> Vec<u8> {
+		let a = C::scalar_from_u64(17 + seed);
+		let acc = inputs.iter().zip(&self.ic[1..]).fold(self.ic[0], |acc, (w, ic)| acc + *w * *ic);
+		let c = a - self.alpha - acc;
+		json_object(&[
+			("pi_a", g1_json::<C>(&a)),
+			("pi_b", g2_json::<C>()),
+			("pi_c", g1_json::<C>(&c)),
+			("protocol", "\"groth16\"".into()),
+			("curve", curve_json::<C>()),
+		])
+	}
+
+	/// The proof of `proof_json` in the compact format.
+	fn compact_proof(&self, inputs: &[C::Scalar], seed: u64) -> Vec<u8> {
+		let proof =
+			Proof::from_json_u8_slice(&self.proof_json(inputs, seed)).expect("The json is a proof");
+		CompactProof::from_snarkjs(proof).expect("The points are valid").encode()
+	}
+}
+
+/// PLONK key of a circuit with 8 constraints. Its points are multiples of the generator, not
+/// derived from a circuit, so no proof is valid for it - which costs the same to verify as a valid
+/// one, only the outcome of the final pairing check differs.
+fn plonk_vk_json<C: BenchmarkCurve>(n_public: u32) -> Vec<u8> {
+	let mut fields = [
+		("protocol", "\"plonk\"".to_string()),
+		("curve", curve_json::<C>()),
+		("nPublic", n_public.to_string()),
+		("power", "3".into()),
+		("k1", scalar_json::<C>(&C::scalar_from_u64(2))),
+		("k2", scalar_json::<C>(&C::scalar_from_u64(3))),
+	]
+	.to_vec();
+	for (index, name) in ["Qm", "Ql", "Qr", "Qo", "Qc", "S1", "S2", "S3"].into_iter().enumerate() {
+		fields.push((name, g1_json::<C>(&C::scalar_from_u64(3 + index as u64))));
+	}
+	fields.push(("X_2", g2_json::<C>()));
+	fields.push(("w", scalar_json::<C>(&root_of_unity::<C>(8))));
+	json_object(&fields)
+}
+
+/// PLONK proof which passes every check but the final pairing check.
+fn plonk_proof_json<C: BenchmarkCurve>() -> Vec<u8> {
+	let mut fields = Vec::new();
+	for (index, name) in
+		["A", "B", "C", "Z", "T1", "T2", "T3", "Wxi", "Wxiw"].into_iter().enumerate()
+	{
+		fields.push((name, g1_json::<C>(&C::scalar_from_u64(17 + index as u64))));
+	}
+	for (index, name) in ["eval_a", "eval_b", "eval_c", "eval_s1", "eval_s2", "eval_zw"]
+		.into_iter()
+		.enumerate()
+	{
+		fields.push((name, scalar_json::<C>(&-C::scalar_from_u64(1 + index as u64))));
+	}
+	fields.push(("protocol", "\"plonk\"".into()));
+	fields.push(("curve", curve_json::<C>()));
+	json_object(&fields)
+}
+
+/// fflonk key of a circuit with 8 constraints, made up like `plonk_vk_json`. Its roots of unity
+/// are valid, `ω = ω₈` and its cube root `ω₈³`.
+fn fflonk_vk_json<C: BenchmarkCurve>(n_public: u32) -> Vec<u8> {
+	let w8 = root_of_unity::<C>(8);
+	json_object(&[
+		("protocol", "\"fflonk\"".into()),
+		("curve", curve_json::<C>()),
+		("nPublic", n_public.to_string()),
+		("power", "3".into()),
+		("k1", scalar_json::<C>(&C::scalar_from_u64(2))),
+		("k2", scalar_json::<C>(&C::scalar_from_u64(3))),
+		("w", scalar_json::<C>(&w8)),
+		("w3", scalar_json::<C>(&root_of_unity::<C>(3))),
+		("w4", scalar_json::<C>(&(w8 * w8))),
+		("w8", scalar_json::<C>(&w8)),
+		("wr", scalar_json::<C>(&(w8 * w8 * w8))),
+		("X_2", g2_json::<C>()),
+		("C0", g1_json::<C>(&C::scalar_from_u64(3))),
+	])
+}
+
+/// fflonk proof which passes every check but the final pairing check.
+fn fflonk_proof_json<C: BenchmarkCurve>() -> Vec<u8> {
+	let mut polynomials = Vec::new();
+	for (index, name) in ["C1", "C2", "W1", "W2"].into_iter().enumerate() {
+		polynomials.push((name, g1_json::<C>(&C::scalar_from_u64(17 + index as u64))));
+	}
+	let mut evaluations = Vec::new();
+	for (index, name) in
+		["ql", "qr", "qm", "qo", "qc", "s1", "s2", "s3", "a", "b", "c", "z", "zw", "t1w", "t2w"]
+			.into_iter()
+			.enumerate()
+	{
+		evaluations.push((name, scalar_json::<C>(&-C::scalar_from_u64(1 + index as u64))));
+	}
+	json_object(&[
+		("polynomials", json_string(json_object(&polynomials))),
+		("evaluations", json_string(json_object(&evaluations))),
+		("protocol", "\"fflonk\"".into()),
+		("curve", curve_json::<C>()),
+	])
+}
+
+/// A primitive root of unity of the given order, which has to divide `r - 1`: `g^((r - 1) / order)`
+/// for the first `g` which gives a primitive one.
+fn root_of_unity<C: Curve>(order: u64) -> C::Scalar {
+	let one = C::scalar_from_u64(1);
+	let exponent = U256::from_big_endian(&C::scalar_to_bytes(&-one)) / order;
+	(2..)
+		.map(|g| pow::<C>(C::scalar_from_u64(g), exponent))
+		.find(|root| (1..order).all(|k| pow::<C>(*root, U256::from(k)) != one))
+		.expect("The order divides r - 1")
+}
+
+fn pow<C: Curve>(base: C::Scalar, exponent: U256) -> C::Scalar {
+	let mut result = C::scalar_from_u64(1);
+	for bit in (0..exponent.bits()).rev() {
+		result = result * result;
+		if exponent.bit(bit) {
+			result = result * base;
+		}
+	}
+	result
+}
+
+/// Full size public inputs, so their json is as long as it gets.
+fn public_inputs<C: Curve>(n_public: u32, seed: u64) -> Vec<C::Scalar> {
+	(0..n_public as u64).map(|i| -C::scalar_from_u64(seed * 256 + i + 1)).collect()
+}
+
+fn public_inputs_json<C: Curve>(inputs: &[C::Scalar]) -> Vec<u8> {
+	numbers_json(inputs.iter().map(scalar_to_dec::<C>).collect())
+}
+
+/// Full size public inputs, the last of which does not fit in 256 bits, so all of them are parsed
+/// before they are rejected.
+fn overflowing_public_inputs_json(n_public: u32) -> Vec<u8> {
+	let mut numbers: Vec<String> = public_inputs::<Bls12_381>(n_public - 1, 0)
+		.iter()
+		.map(scalar_to_dec::<Bls12_381>)
+		.collect();
+	numbers.push((U256::one() << 256).to_string());
+	numbers_json(numbers)
+}
+
+fn numbers_json(numbers: Vec<String>) -> Vec<u8> {
+	let numbers: Vec<String> = numbers.iter().map(|number| ["\"", number, "\""].concat()).collect();
+	["[", &numbers.join(","), "]"].concat().into_bytes()
+}
+
+/// Json object of the fields, whose values are json already.
+fn json_object(fields: &[(&str, String)]) -> Vec<u8> {
+	let fields: Vec<String> =
+		fields.iter().map(|(name, value)| ["\"", name, "\":", value].concat()).collect();
+	["{", &fields.join(","), "}"].concat().into_bytes()
+}
+
+fn json_string(json: Vec<u8>) -> String {
+	String::from_utf8(json).expect("The json is ascii")
+}
+
+fn curve_json<C: BenchmarkCurve>() -> String {
+	["\"", C::name(), "\""].concat()
+}
+
+fn scalar_json<C: Curve>(scalar: &C::Scalar) -> String {
+	["\"", &scalar_to_dec::<C>(scalar), "\""].concat()
+}
+
+/// The point `scalar * G1`, written with `z = -1`, as `(x, -y, -1)`, so it has to be brought to
+/// the affine coordinates, which is as costly as for any `z` other than 0 and 1.
+fn g1_json<C: Curve>(scalar: &C::Scalar) -> String {
+	let bytes = C::g1_to_bytes(&C::g1_mul(&C::g1_generator(), scalar));
+	let (x, y) = bytes.split_at(bytes.len() / 2);
+	let (x, y) = (be_to_dec(x), negated_be_to_dec::<C>(y));
+	["[\"", &x, "\",\"", &y, "\",\"", &minus_one::<C>(), "\"]"].concat()
+}
+
+/// The G2 generator, written with `z = -1` as in `g1_json`. Coordinates of an uncompressed G2
+/// point are ordered `x.c1, x.c0, y.c1, y.c0`.
+fn g2_json<C: Curve>() -> String {
+	let bytes = C::g2_to_bytes(&C::g2_generator());
+	let mut coordinates = bytes.chunks(bytes.len() / 4);
+	let mut next = || coordinates.next().expect("There are four coordinates");
+	let (x_c1, x_c0) = (be_to_dec(next()), be_to_dec(next()));
+	let (y_c1, y_c0) = (negated_be_to_dec::<C>(next()), negated_be_to_dec::<C>(next()));
+	let z = ["[\"", &minus_one::<C>(), "\",\"0\"]"].concat();
+	["[[\"", &x_c0, "\",\"", &x_c1, "\"],[\"", &y_c0, "\",\"", &y_c1, "\"],", &z, "]"].concat()
+}
+
+fn be_to_dec(bytes: &[u8]) -> String {
+	U256::from_big_endian(bytes).to_string()
+}
+
+/// `-a` modulo the base field modulus, `a` is a big-endian coordinate
+fn negated_be_to_dec<C: Curve>(bytes: &[u8]) -> String {
+	let modulus = U768::from_big_endian(&C::BASE_FIELD_MODULUS);
+	let coordinate = U768::from_big_endian(bytes);
+	if coordinate.is_zero() {
+		return coordinate.to_string()
+	}
+	(modulus - coordinate).to_string()
+}
+
+/// `-1` modulo the base field modulus
+fn minus_one<C: Curve>() -> String {
+	negated_be_to_dec::<C>(&[1])
+}
+
+fn scalar_to_dec<C: Curve>(scalar: &C::Scalar) -> String {
+	U256::from_big_endian(&C::scalar_to_bytes(scalar)).to_string()
 }
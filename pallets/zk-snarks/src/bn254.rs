@@ -0,0 +1,619 @@
+// MIT License
+
+// Copyright (c) 2022 Bright Inventions
+
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+use crate::{
+	deserialization::{Number, PublicInput},
+	verify::{Curve, PointError},
+};
+use sp_std::prelude::*;
+use substrate_bn::{
+	arith::U256, pairing, pairing_batch, AffineG1, AffineG2, Fq, Fq2, Fr, Group, GroupError, Gt,
+	G1, G2,
+};
+
+/// BN254 (alt_bn128) curve, called `bn128` by snarkjs
+pub struct Bn254;
+
+// Flags kept in the two most significant bits of a compressed point, the way gnark encodes them
+const COMPRESSION_FLAGS: u8 = 0b11 << 6;
+const COMPRESSED_SMALLEST: u8 = 0b10 << 6;
+const COMPRESSED_LARGEST: u8 = 0b11 << 6;
+const COMPRESSED_INFINITY: u8 = 0b01 << 6;
+
+/// BN254 base field modulus, padded to the width of `Number`
+const BN254_BASE_FIELD_MODULUS: Number = [
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+	0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81, 0x58, 0x5d,
+	0x97, 0x81, 0x6a, 0x91, 0x68, 0x71, 0xca, 0x8d, 0x3c, 0x20, 0x8c, 0x16, 0xd8, 0x7c, 0xfd, 0x47,
+];
+
+/// Turns 48 bytes big-endian integer into base field element
+fn fq_from_number(number: Number) -> Result<Fq, PointError> {
+	if number[..16].iter().any(|byte| *byte != 0) {
+		return Err(PointError::NonCanonicalCoordinate)
+	}
+	fq_from_slice(&number[16..])
+}
+
+/// Turns 32 bytes big-endian integer into base field element
+fn fq_from_slice(bytes: &[u8]) -> Result<Fq, PointError> {
+	Fq::from_slice(bytes).map_err(|_| PointError::NonCanonicalCoordinate)
+}
+
+fn point_error(error: GroupError) -> PointError {
+	match error {
+		GroupError::NotOnCurve => PointError::NotOnCurve,
+		GroupError::NotInSubgroup => PointError::NotInSubgroup,
+	}
+}
+
+/// Tells whether `value` is greater than `-value`
+fn is_lexicographically_largest(value: Fq) -> bool {
+	value.into_u256() > (-value).into_u256()
+}
+
+/// Compares the imaginary parts first, the real ones only when the imaginary part is zero
+fn fq2_is_lexicographically_largest(value: Fq2) -> bool {
+	if value.imaginary().is_zero() {
+		is_lexicographically_largest(value.real())
+	} else {
+		is_lexicographically_largest(value.imaginary())
+	}
+}
+
+/// Splits compressed point into the flags and big-endian `x` without them
+fn split_flags<const N: usize>(bytes: &[u8]) -> Result<(u8, [u8; N]), PointError> {
+	let mut x: [u8; N] = bytes.try_into().map_err(|_| PointError::NonCanonicalCoordinate)?;
+	let flags = x[0] & COMPRESSION_FLAGS;
+	x[0] &= !COMPRESSION_FLAGS;
+	Ok((flags, x))
+}
+
+/// Picks `y` or `-y` according to the flags, which have to describe a finite point
+fn select_y<F: Copy + core::ops::Neg<Output = F>>(
+	flags: u8,
+	y: F,
+	is_largest: impl Fn(F) -> bool,
+) -> Result<F, PointError> {
+	match flags {
+		COMPRESSED_SMALLEST => Ok(if is_largest(y) { -y } else { y }),
+		COMPRESSED_LARGEST => Ok(if is_largest(y) { y } else { -y }),
+		_ => Err(PointError::NonCanonicalCoordinate),
+	}
+}
+
+/// Decodes compressed point at infinity, all of its bits but the flags have to be zero
+fn infinity<P>(x: &[u8], identity: P) -> Result<P, PointError> {
+	if x.iter().any(|byte| *byte != 0) {
+		return Err(PointError::NonCanonicalCoordinate)
+	}
+	Ok(identity)
+}
+
+impl Curve for Bn254 {
+	type G1 = G1;
+	type G2 = G2;
+	type Gt = Gt;
+	type Scalar = Fr;
+
+	const BASE_FIELD_MODULUS: Number = BN254_BASE_FIELD_MODULUS;
+
+	fn g1_from_coordinates(x: Number, y: Number) -> Result<Self::G1, PointError> {
+		let point = AffineG1::new(fq_from_number(x)?, fq_from_number(y)?).map_err(point_error)?;
+		Ok(point.into())
+	}
+
+	fn g2_from_coordinates(
+		x_c0: Number,
+		x_c1: Number,
+		y_c0: Number,
+		y_c1: Number,
+	) -> Result<Self::G2, PointError> {
+		let x = Fq2::new(fq_from_number(x_c0)?, fq_from_number(x_c1)?);
+		let y = Fq2::new(fq_from_number(y_c0)?, fq_from_number(y_c1)?);
+		let point = AffineG2::new(x, y).map_err(point_error)?;
+		Ok(point.into())
+	}
+
+	fn scalar_from_public_input(input: PublicInput) -> Option<Self::Scalar> {
+		// `Fr::from_slice` silently reduces values which are not smaller than the modulus
+		Fr::new(U256::from_slice(&input).ok()?)
+	}
+
+	fn scalar_from_u64(value: u64) -> Self::Scalar {
+		Fr::new(U256::from(value)).expect("u64 is smaller than the modulus")
+	}
+
+	fn scalar_from_hash(hash: [u8; 32]) -> Self::Scalar {
+		let mut wide = [0u8; 64];
+		wide[32..].copy_from_slice(&hash);
+		Fr::interpret(&wide)
+	}
+
+	fn scalar_to_bytes(scalar: &Self::Scalar) -> PublicInput {
+		// `Fr::to_big_endian` writes the Montgomery form, `into_u256` leaves it
+		let mut bytes = [0u8; 32];
+		scalar.into_u256().to_big_endian(&mut bytes).expect("slice has 32 bytes");
+		bytes
+	}
+
+	fn scalar_inverse(scalar: &Self::Scalar) -> Option<Self::Scalar> {
+		scalar.inverse()
+	}
+
+	fn g1_generator() -> Self::G1 {
+		G1::one()
+	}
+
+	fn g2_generator() -> Self::G2 {
+		G2::one()
+	}
+
+	fn g1_identity() -> Self::G1 {
+		G1::zero()
+	}
+
+	fn g2_identity() -> Self::G2 {
+		G2::zero()
+	}
+
+	fn g1_is_identity(point: &Self::G1) -> bool {
+		point.is_zero()
+	}
+
+	fn g2_is_identity(point: &Self::G2) -> bool {
+		point.is_zero()
+	}
+
+	fn g1_to_bytes(point: &Self::G1) -> Vec<u8> {
+		let mut bytes = vec![0u8; 64];
+		match AffineG1::from_jacobian(*point) {
+			Some(point) => {
+				point.x().to_big_endian(&mut bytes[..32]).expect("slice has 32 bytes");
+				point.y().to_big_endian(&mut bytes[32..]).expect("slice has 32 bytes");
+			},
+			// snarkjs encodes the point at infinity as zero coordinates with `0x40` flag
+			None => bytes[0] = 0x40,
+		}
+		bytes
+	}
+
+	fn g2_to_bytes(point: &Self::G2) -> Vec<u8> {
+		let mut bytes = vec![0u8; 128];
+		if let Some(point) = AffineG2::from_jacobian(*point) {
+			let coordinates =
+				[point.x().imaginary(), point.x().real(), point.y().imaginary(), point.y().real()];
+			for (chunk, coordinate) in bytes.chunks_mut(32).zip(coordinates) {
+				coordinate.to_big_endian(chunk).expect("slice has 32 bytes");
+			}
+		}
+		bytes
+	}
+
+	fn g1_from_trusted_bytes(bytes: &[u8]) -> Option<Self::G1> {
+		if bytes.len() != 64 {
+			return None
+		}
+		if bytes[0] == 0x40 && bytes[1..].iter().all(|byte| *byte == 0) {
+			return Some(G1::zero())
+		}
+		let x = Fq::from_slice(&bytes[..32]).ok()?;
+		let y = Fq::from_slice(&bytes[32..]).ok()?;
+		Some(G1::new(x, y, Fq::one()))
+	}
+
+	fn g2_from_trusted_bytes(bytes: &[u8]) -> Option<Self::G2> {
+		if bytes.len() != 128 {
+			return None
+		}
+		if bytes.iter().all(|byte| *byte == 0) {
+			return Some(G2::zero())
+		}
+		let fq = |chunk: usize| Fq::from_slice(&bytes[chunk * 32..(chunk + 1) * 32]).ok();
+		let x = Fq2::new(fq(1)?, fq(0)?);
+		let y = Fq2::new(fq(3)?, fq(2)?);
+		Some(G2::new(x, y, Fq2::one()))
+	}
+
+	fn g1_from_compressed(bytes: &[u8]) -> Result<Self::G1, PointError> {
+		let (flags, x) = split_flags::<32>(bytes)?;
+		if flags == COMPRESSED_INFINITY {
+			return infinity(&x, G1::zero())
+		}
+		let x = fq_from_slice(&x)?;
+		let y = (x * x * x + G1::b()).sqrt().ok_or(PointError::NotOnCurve)?;
+		let y = select_y(flags, y, is_lexicographically_largest)?;
+		Ok(AffineG1::new(x, y).map_err(point_error)?.into())
+	}
+
+	fn g2_from_compressed(bytes: &[u8]) -> Result<Self::G2, PointError> {
+		let (flags, x) = split_flags::<64>(bytes)?;
+		if flags == COMPRESSED_INFINITY {
+			return infinity(&x, G2::zero())
+		}
+		let x = Fq2::new(fq_from_slice(&x[32..])?, fq_from_slice(&x[..32])?);
+		let y = (x * x * x + G2::b()).sqrt().ok_or(PointError::NotOnCurve)?;
+		let y = select_y(flags, y, fq2_is_lexicographically_largest)?;
+		Ok(AffineG2::new(x, y).map_err(point_error)?.into())
+	}
+
+	fn g1_to_compressed(point: &Self::G1) -> Vec<u8> {
+		let mut bytes = vec![0u8; 32];
+		match AffineG1::from_jacobian(*point) {
+			Some(point) => {
+				point.x().to_big_endian(&mut bytes).expect("slice has 32 bytes");
+				bytes[0] |= if is_lexicographically_largest(point.y()) {
+					COMPRESSED_LARGEST
+				} else {
+					COMPRESSED_SMALLEST
+				};
+			},
+			None => bytes[0] = COMPRESSED_INFINITY,
+		}
+		bytes
+	}
+
+	fn g2_to_compressed(point: &Self::G2) -> Vec<u8> {
+		let mut bytes = vec![0u8; 64];
+		match AffineG2::from_jacobian(*point) {
+			Some(point) => {
+				let x = point.x();
+				x.imaginary().to_big_endian(&mut bytes[..32]).expect("slice has 32 bytes");
+				x.real().to_big_endian(&mut bytes[32..]).expect("slice has 32 bytes");
+				bytes[0] |= if fq2_is_lexicographically_largest(point.y()) {
+					COMPRESSED_LARGEST
+				} else {
+					COMPRESSED_SMALLEST
+				};
+			},
+			None => bytes[0] = COMPRESSED_INFINITY,
+		}
+		bytes
+	}
+
+	fn g1_mul(point: &Self::G1, scalar: &Self::Scalar) -> Self::G1 {
+		*point * *scalar
+	}
+
+	fn g1_multi_exp(base: &Self::G1, points: &[Self::G1], scalars: &[Self::Scalar]) -> Self::G1 {
+		let mut acc = *base;
+		for (point, scalar) in points.iter().zip(scalars) {
+			acc = acc + *point * *scalar;
+		}
+		acc
+	}
+
+	fn g1_neg(point: &Self::G1) -> Self::G1 {
+		-*point
+	}
+
+	fn pairing_product_is_identity(pairs: &[(Self::G1, Self::G2)]) -> bool {
+		pairing_batch(pairs) == Gt::one()
+	}
+
+	fn pairing_product_with_is_identity(pairs: &[(Self::G1, Self::G2)], factor: &Self::Gt) -> bool {
+		pairing_batch(pairs) * *factor == Gt::one()
+	}
+
+	fn pairing(g1: &Self::G1, g2: &Self::G2) -> Self::Gt {
+		pairing(*g1, *g2)
+	}
+
+	fn gt_to_bytes(value: &Self::Gt) -> Vec<u8> {
+		// SAFETY: `Gt` and the field elements it consists of are `repr(C)`, twelve `U256` of two
+		// `u128` limbs each, so it has no padding and `transmute` checks its size
+		unsafe { core::mem::transmute::<Gt, [u8; GT_LEN]>(*value) }.to_vec()
+	}
+
+	fn gt_from_trusted_bytes(bytes: &[u8]) -> Option<Self::Gt> {
+		let bytes: [u8; GT_LEN] = bytes.try_into().ok()?;
+		// SAFETY: every bit pattern is a valid `u128` limb
+		Some(unsafe { core::mem::transmute::<[u8; GT_LEN], Gt>(bytes) })
+	}
+}
+
+/// Size of `Gt` element: twelve base field elements
+const GT_LEN: usize = 384;
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		bn254::{Bn254, COMPRESSED_INFINITY, COMPRESSED_SMALLEST},
+		common::{prepare_proof, prepare_verification_key},
+		deserialization::{Proof, VKey},
+		verify::{
+			prepare_public_inputs, verify, verify_batch, Curve, DecodedVerificationKey, GProof,
+			GProofCreationError, PointError, PublicInputs, SupportedCurve, VerificationKey,
+		},
+	};
+	use substrate_bn::{Fq, Fq2, Group, G1, G2};
+
+	const VK_JSON: &str = r#"{
+"protocol": "groth16",
+"curve": "bn128",
+"nPublic": 1,
+"vk_alpha_1": [
+    "866095345221421711514180413743448151905772182708004637573355935301020034566",
+    "7221391810315363065250504614451527595399904277538141734100112624585369102786",
+    "1"
+],
+"vk_beta_2": [
+    [
+    "129782923071222172219222274433184956792132339028859579067463064678661755618",
+    "5799183513420936057741456559375310019513849279052488076883082562800333859274"
+    ],
+    [
+    "3036510262051021480576773093037947606842011407315450398741349831397258672651",
+    "7788190940393224787731613326796768244471912930413017978895603378360484911578"
+    ],
+    [
+    "1",
+    "0"
+    ]
+],
+"vk_gamma_2": [
+    [
+    "5990830084538208363307314339680834085561604791168915514548635466897816344270",
+    "8029705769976122274798785083342388760186164194754081135701136915002223344424"
+    ],
+    [
+    "16928854157997045097912724200193400372079759262604262014535591690839693907711",
+    "10062016626673020347994911112711835999441816093079367807412606575688452361020"
+    ],
+    [
+    "1",
+    "0"
+    ]
+],
+"vk_delta_2": [
+    [
+    "18182221911411233032090955183083484750381318839113869191762455872265023953053",
+    "4532716008912247041498858886168290022787263312119842298261689495345379468329"
+    ],
+    [
+    "2817430011812539585715424844376396694204510210323711625789139129715730343075",
+    "13159489736730190134669183937699368816206619946735782132507646973360959453541"
+    ],
+    [
+    "1",
+    "0"
+    ]
+],
+"IC": [
+    [
+        "19457571953900102113261406416722199904229182968440313615011891701937375523407",
+        "15616285690599967882747247340893844255466115442843311725523493735929078649876",
+        "1"
+    ],
+    [
+        "5070236491689709851553582362151187783154533049050495132105505049846834680730",
+        "12128836162002864285533101021881151758502217213754536414245453986296060464546",
+        "1"
+    ]
+]
+}"#;
+
+	const PROOF_JSON: &str = r#"{
+"pi_a": [
+    "8407441580725980188856084962231235924707878671344545471241261411953209254851",
+    "8220749938458590961628004998375077645203738486400012973135184170547661229743",
+    "1"
+],
+"pi_b": [
+    [
+    "5817848408043394063999077998697460959884877750414136281598380853549698825081",
+    "8056041305764246727599908171879358720762163217000709315622453338868724401720"
+    ],
+    [
+    "2623946943410936151790385943274651226235732134018858389255343943011310926352",
+    "11996717810309561989026900382509107972554623931395104420925641685342210056291"
+    ],
+    [
+    "1",
+    "0"
+    ]
+],
+"pi_c": [
+    "13566245701818283649578978140042569312397974576184185491376415048557578470362",
+    "19110775143408751771206277603025975640399741366272013798382679012092160449187",
+    "1"
+],
+"protocol": "groth16",
+"curve": "bn128"
+}"#;
+
+	fn prepare_inputs(input: u8) -> PublicInputs<Bn254> {
+		let mut public_input = [0u8; 32];
+		public_input[31] = input;
+		prepare_public_inputs::<Bn254>(vec![public_input]).unwrap()
+	}
+
+	fn prepare_vk() -> VerificationKey<Bn254> {
+		prepare_verification_key(VKey::from_json_u8_slice(VK_JSON.as_bytes()).unwrap()).unwrap()
+	}
+
+	fn prepare_bn254_proof() -> GProof<Bn254> {
+		prepare_proof(Proof::from_json_u8_slice(PROOF_JSON.as_bytes()).unwrap()).unwrap()
+	}
+
+	#[test]
+	fn verify_correct_proof() {
+		let result = verify(prepare_vk(), prepare_bn254_proof(), prepare_inputs(33));
+
+		assert!(result.unwrap())
+	}
+
+	#[test]
+	fn verify_incorrect_proof() {
+		let result = verify(prepare_vk(), prepare_bn254_proof(), prepare_inputs(34));
+
+		assert!(!result.unwrap())
+	}
+
+	#[test]
+	fn verify_batch_with_incorrect_proof() {
+		let proofs = vec![
+			(prepare_bn254_proof(), prepare_inputs(33)),
+			(prepare_bn254_proof(), prepare_inputs(34)),
+			(prepare_bn254_proof(), prepare_inputs(33)),
+		];
+
+		assert_eq!(verify_batch(prepare_vk(), proofs).unwrap(), vec![true, false, true])
+	}
+
+	#[test]
+	fn verify_batch_of_correct_proofs() {
+		let proofs = vec![
+			(prepare_bn254_proof(), prepare_inputs(33)),
+			(prepare_bn254_proof(), prepare_inputs(33)),
+		];
+
+		assert_eq!(verify_batch(prepare_vk(), proofs).unwrap(), vec![true, true])
+	}
+
+	#[test]
+	fn verify_with_decoded_verification_key() {
+		let decoded = DecodedVerificationKey::new(SupportedCurve::Bn254, prepare_vk());
+		let vk = decoded.restore::<Bn254>().unwrap();
+
+		assert_eq!(decoded.curve(), SupportedCurve::Bn254);
+		assert_eq!(decoded.public_inputs_len(), 1);
+		assert!(verify(vk, prepare_bn254_proof(), prepare_inputs(33)).unwrap())
+	}
+
+	#[test]
+	fn trusted_bytes_of_gt() {
+		let vk = prepare_vk();
+		let alpha_beta = Bn254::pairing(&vk.alpha, &vk.beta);
+
+		assert!(Bn254::gt_from_trusted_bytes(&Bn254::gt_to_bytes(&alpha_beta)) == Some(alpha_beta));
+		assert!(Bn254::gt_from_trusted_bytes(&[0u8; 383]).is_none());
+	}
+
+	#[test]
+	fn trusted_bytes_of_points_at_infinity() {
+		let g1 = Bn254::g1_from_trusted_bytes(&Bn254::g1_to_bytes(&G1::zero())).unwrap();
+		let g2 = Bn254::g2_from_trusted_bytes(&Bn254::g2_to_bytes(&G2::zero())).unwrap();
+
+		assert!(g1.is_zero());
+		assert!(g2.is_zero());
+	}
+
+	#[test]
+	fn public_input_out_of_scalar_field() {
+		let modulus = [
+			0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81,
+			0x58, 0x5d, 0x28, 0x33, 0xe8, 0x48, 0x79, 0xb9, 0x70, 0x91, 0x43, 0xe1, 0xf5, 0x93,
+			0xf0, 0x00, 0x00, 0x01,
+		];
+
+		assert!(prepare_public_inputs::<Bn254>(vec![modulus]).is_err())
+	}
+
+	#[test]
+	fn scalar_from_hash_is_reduced() {
+		// r + 1
+		let hash = [
+			0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81,
+			0x58, 0x5d, 0x28, 0x33, 0xe8, 0x48, 0x79, 0xb9, 0x70, 0x91, 0x43, 0xe1, 0xf5, 0x93,
+			0xf0, 0x00, 0x00, 0x02,
+		];
+
+		assert!(Bn254::scalar_from_hash(hash) == Bn254::scalar_from_u64(1));
+	}
+
+	#[test]
+	fn scalar_to_bytes_is_big_endian() {
+		let mut bytes = [0; 32];
+		bytes[30] = 1;
+		bytes[31] = 2;
+		let scalar = Bn254::scalar_from_public_input(bytes).unwrap();
+
+		assert!(scalar == Bn254::scalar_from_u64(258));
+		assert_eq!(Bn254::scalar_to_bytes(&scalar), bytes);
+	}
+
+	#[test]
+	fn point_not_on_curve() {
+		let mut proof = Proof::from_json_u8_slice(PROOF_JSON.as_bytes()).unwrap();
+		proof.a[1][47] ^= 1;
+
+		assert_eq!(
+			prepare_proof::<Bn254>(proof).err(),
+			Some(GProofCreationError::PointCreationError(PointError::NotOnCurve))
+		)
+	}
+
+	#[test]
+	fn coordinate_out_of_base_field() {
+		let mut proof = Proof::from_json_u8_slice(PROOF_JSON.as_bytes()).unwrap();
+		proof.a[0][15] = 1;
+
+		assert_eq!(
+			prepare_proof::<Bn254>(proof).err(),
+			Some(GProofCreationError::PointCreationError(PointError::NonCanonicalCoordinate))
+		)
+	}
+
+	#[test]
+	fn g2_point_not_in_subgroup() {
+		// The twist has a large cofactor, so its points are hardly ever in the subgroup of order r
+		let (x, y) = (1u64..)
+			.map(|i| Fq2::new(Fq::from_str(&i.to_string()).unwrap(), Fq::one()))
+			.find_map(|x| Some((x, (x * x * x + G2::b()).sqrt()?)))
+			.unwrap();
+		let number = |fq: Fq| {
+			let mut number = [0u8; 48];
+			fq.to_big_endian(&mut number[16..]).unwrap();
+			number
+		};
+		let point = Bn254::g2_from_coordinates(
+			number(x.real()),
+			number(x.imaginary()),
+			number(y.real()),
+			number(y.imaginary()),
+		);
+
+		assert_eq!(point.err(), Some(PointError::NotInSubgroup))
+	}
+
+	#[test]
+	fn compressed_point_without_y() {
+		// x³ + 3 is not a square for x = 4
+		let mut bytes = [0u8; 32];
+		bytes[31] = 4;
+		bytes[0] |= COMPRESSED_SMALLEST;
+
+		assert_eq!(Bn254::g1_from_compressed(&bytes).err(), Some(PointError::NotOnCurve));
+		bytes[0] = COMPRESSED_INFINITY;
+		assert_eq!(
+			Bn254::g1_from_compressed(&bytes).err(),
+			Some(PointError::NonCanonicalCoordinate)
+		);
+	}
+}
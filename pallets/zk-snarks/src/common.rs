@@ -1,48 +1,177 @@
 use crate::{
-	deserialization::{Proof, VKey},
+	deserialization::{FflonkProof, FflonkVKey, Number, PlonkProof, PlonkVKey, Proof, VKey},
+	fflonk::{FProof, FflonkVerificationKey},
+	jacobian::{g1_to_affine, g2_to_affine},
+	plonk::{PProof, PlonkVerificationKey},
 	verify::{
-		G1UncompressedBytes, G2UncompressedBytes, GProof, GProofCreationError, VerificationKey,
+		finite_g1, finite_g2, Curve, GProof, GProofCreationError, PointError, VerificationKey,
 		VerificationKeyCreationError,
 	},
 };
 use sp_std::vec::Vec;
 
-pub fn prepare_verification_key(
+/// Creates G1 point from snarkjs Jacobian coordinates, `z = 0` stands for the point at infinity
+fn g1_from_json<C: Curve>(point: &[Number; 3]) -> Result<C::G1, PointError> {
+	match g1_to_affine(point, &C::BASE_FIELD_MODULUS)? {
+		Some((x, y)) => C::g1_from_coordinates(x, y),
+		None => Ok(C::g1_identity()),
+	}
+}
+
+/// Creates G2 point from snarkjs Jacobian coordinates, `z = (0, 0)` stands for the point at
+/// infinity
+fn g2_from_json<C: Curve>(point: &[[Number; 2]; 3]) -> Result<C::G2, PointError> {
+	match g2_to_affine(point, &C::BASE_FIELD_MODULUS)? {
+		Some(([x_c0, x_c1], [y_c0, y_c1])) => C::g2_from_coordinates(x_c0, x_c1, y_c0, y_c1),
+		None => Ok(C::g2_identity()),
+	}
+}
+
+fn finite_g1_from_json<C: Curve>(point: &[Number; 3]) -> Result<C::G1, PointError> {
+	g1_from_json::<C>(point).and_then(finite_g1::<C>)
+}
+
+fn finite_g2_from_json<C: Curve>(point: &[[Number; 2]; 3]) -> Result<C::G2, PointError> {
+	g2_from_json::<C>(point).and_then(finite_g2::<C>)
+}
+
+/// Only `ic` points may be at infinity, any other one makes the key degenerate
+pub fn prepare_verification_key<C: Curve>(
 	deserialized_vk: VKey,
-) -> Result<VerificationKey, VerificationKeyCreationError> {
-	let mut ic: Vec<G1UncompressedBytes> = Vec::with_capacity(deserialized_vk.ic.len());
-	for i in 0..deserialized_vk.ic.len() {
-		let g1_bytes = G1UncompressedBytes::new(deserialized_vk.ic[i][0], deserialized_vk.ic[i][1]);
-		ic.push(g1_bytes)
+) -> Result<VerificationKey<C>, VerificationKeyCreationError> {
+	let mut ic: Vec<C::G1> = Vec::with_capacity(deserialized_vk.ic.len());
+	for point in &deserialized_vk.ic {
+		let g1 =
+			g1_from_json::<C>(point).map_err(VerificationKeyCreationError::PointCreationError)?;
+		ic.push(g1)
 	}
-	VerificationKey::from_uncompressed(
-		&G1UncompressedBytes::new(deserialized_vk.alpha[0], deserialized_vk.alpha[1]),
-		&G2UncompressedBytes::new(
-			deserialized_vk.beta[0][0],
-			deserialized_vk.beta[0][1],
-			deserialized_vk.beta[1][0],
-			deserialized_vk.beta[1][1],
-		),
-		&G2UncompressedBytes::new(
-			deserialized_vk.gamma[0][0],
-			deserialized_vk.gamma[0][1],
-			deserialized_vk.gamma[1][0],
-			deserialized_vk.gamma[1][1],
-		),
-		&G2UncompressedBytes::new(
-			deserialized_vk.delta[0][0],
-			deserialized_vk.delta[0][1],
-			deserialized_vk.delta[1][0],
-			deserialized_vk.delta[1][1],
-		),
-		&ic,
-	)
-}
-
-pub fn prepare_proof(proof: Proof) -> Result<GProof, GProofCreationError> {
-	GProof::from_uncompressed(
-		&G1UncompressedBytes::new(proof.a[0], proof.a[1]),
-		&G2UncompressedBytes::new(proof.b[0][0], proof.b[0][1], proof.b[1][0], proof.b[1][1]),
-		&G1UncompressedBytes::new(proof.c[0], proof.c[1]),
-	)
+	let g2 = |point| {
+		finite_g2_from_json::<C>(point).map_err(VerificationKeyCreationError::PointCreationError)
+	};
+	Ok(VerificationKey {
+		alpha: finite_g1_from_json::<C>(&deserialized_vk.alpha)
+			.map_err(VerificationKeyCreationError::PointCreationError)?,
+		beta: g2(&deserialized_vk.beta)?,
+		gamma: g2(&deserialized_vk.gamma)?,
+		delta: g2(&deserialized_vk.delta)?,
+		ic,
+	})
+}
+
+pub fn prepare_proof<C: Curve>(proof: Proof) -> Result<GProof<C>, GProofCreationError> {
+	Ok(GProof {
+		a: finite_g1_from_json::<C>(&proof.a).map_err(GProofCreationError::PointCreationError)?,
+		b: finite_g2_from_json::<C>(&proof.b).map_err(GProofCreationError::PointCreationError)?,
+		c: finite_g1_from_json::<C>(&proof.c).map_err(GProofCreationError::PointCreationError)?,
+	})
+}
+
+pub fn prepare_plonk_verification_key<C: Curve>(
+	vk: PlonkVKey,
+) -> Result<PlonkVerificationKey<C>, VerificationKeyCreationError> {
+	// Selector commitments are at infinity when the circuit does not use the selector
+	let g1 = |point: &[Number; 3]| {
+		g1_from_json::<C>(point).map_err(VerificationKeyCreationError::PointCreationError)
+	};
+	let scalar = |number| {
+		C::scalar_from_public_input(number).ok_or(VerificationKeyCreationError::ScalarCreationError)
+	};
+	Ok(PlonkVerificationKey {
+		public_inputs_len: vk.public_inputs_len,
+		power: vk.power,
+		k1: scalar(vk.k1)?,
+		k2: scalar(vk.k2)?,
+		w: scalar(vk.w)?,
+		qm: g1(&vk.qm)?,
+		ql: g1(&vk.ql)?,
+		qr: g1(&vk.qr)?,
+		qo: g1(&vk.qo)?,
+		qc: g1(&vk.qc)?,
+		s1: g1(&vk.s1)?,
+		s2: g1(&vk.s2)?,
+		s3: g1(&vk.s3)?,
+		x_2: finite_g2_from_json::<C>(&vk.x_2)
+			.map_err(VerificationKeyCreationError::PointCreationError)?,
+	})
+}
+
+pub fn prepare_plonk_proof<C: Curve>(proof: PlonkProof) -> Result<PProof<C>, GProofCreationError> {
+	let g1 = |point: &[Number; 3]| {
+		finite_g1_from_json::<C>(point).map_err(GProofCreationError::PointCreationError)
+	};
+	let scalar = |number| {
+		C::scalar_from_public_input(number).ok_or(GProofCreationError::ScalarCreationError)
+	};
+	Ok(PProof {
+		a: g1(&proof.a)?,
+		b: g1(&proof.b)?,
+		c: g1(&proof.c)?,
+		z: g1(&proof.z)?,
+		t1: g1(&proof.t1)?,
+		t2: g1(&proof.t2)?,
+		t3: g1(&proof.t3)?,
+		wxi: g1(&proof.wxi)?,
+		wxiw: g1(&proof.wxiw)?,
+		eval_a: scalar(proof.eval_a)?,
+		eval_b: scalar(proof.eval_b)?,
+		eval_c: scalar(proof.eval_c)?,
+		eval_s1: scalar(proof.eval_s1)?,
+		eval_s2: scalar(proof.eval_s2)?,
+		eval_zw: scalar(proof.eval_zw)?,
+	})
+}
+
+pub fn prepare_fflonk_verification_key<C: Curve>(
+	vk: FflonkVKey,
+) -> Result<FflonkVerificationKey<C>, VerificationKeyCreationError> {
+	let scalar = |number| {
+		C::scalar_from_public_input(number).ok_or(VerificationKeyCreationError::ScalarCreationError)
+	};
+	Ok(FflonkVerificationKey {
+		public_inputs_len: vk.public_inputs_len,
+		power: vk.power,
+		k1: scalar(vk.k1)?,
+		k2: scalar(vk.k2)?,
+		w: scalar(vk.w)?,
+		w3: scalar(vk.w3)?,
+		w4: scalar(vk.w4)?,
+		w8: scalar(vk.w8)?,
+		wr: scalar(vk.wr)?,
+		x_2: finite_g2_from_json::<C>(&vk.x_2)
+			.map_err(VerificationKeyCreationError::PointCreationError)?,
+		c0: g1_from_json::<C>(&vk.c0).map_err(VerificationKeyCreationError::PointCreationError)?,
+	})
+}
+
+pub fn prepare_fflonk_proof<C: Curve>(
+	proof: FflonkProof,
+) -> Result<FProof<C>, GProofCreationError> {
+	let g1 = |point: &[Number; 3]| {
+		finite_g1_from_json::<C>(point).map_err(GProofCreationError::PointCreationError)
+	};
+	let scalar = |number| {
+		C::scalar_from_public_input(number).ok_or(GProofCreationError::ScalarCreationError)
+	};
+	let evaluations = proof.evaluations;
+	Ok(FProof {
+		c1: g1(&proof.polynomials.c1)?,
+		c2: g1(&proof.polynomials.c2)?,
+		w1: g1(&proof.polynomials.w1)?,
+		w2: g1(&proof.polynomials.w2)?,
+		eval_ql: scalar(evaluations.ql)?,
+		eval_qr: scalar(evaluations.qr)?,
+		eval_qm: scalar(evaluations.qm)?,
+		eval_qo: scalar(evaluations.qo)?,
+		eval_qc: scalar(evaluations.qc)?,
+		eval_s1: scalar(evaluations.s1)?,
+		eval_s2: scalar(evaluations.s2)?,
+		eval_s3: scalar(evaluations.s3)?,
+		eval_a: scalar(evaluations.a)?,
+		eval_b: scalar(evaluations.b)?,
+		eval_c: scalar(evaluations.c)?,
+		eval_z: scalar(evaluations.z)?,
+		eval_zw: scalar(evaluations.zw)?,
+		eval_t1w: scalar(evaluations.t1w)?,
+		eval_t2w: scalar(evaluations.t2w)?,
+	})
 }
@@ -0,0 +1,213 @@
+// MIT License
+
+// Copyright (c) 2022 Bright Inventions
+
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+use crate::{
+	bn254::Bn254,
+	common::{prepare_proof, prepare_verification_key},
+	deserialization::{Proof, VKey},
+	verify::{
+		finite_g1, finite_g2, Bls12_381, Curve, GProof, GProofCreationError, SupportedCurve,
+		VerificationKey, VerificationKeyCreationError,
+	},
+};
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use sp_std::prelude::*;
+
+pub type Bls12381G1 = [u8; 48];
+pub type Bls12381G2 = [u8; 96];
+pub type Bn254G1 = [u8; 32];
+pub type Bn254G2 = [u8; 64];
+
+/// Groth16 proof with compressed points
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct CompressedProof<G1, G2> {
+	pub a: G1,
+	pub b: G2,
+	pub c: G1,
+}
+
+/// Groth16 verification key with compressed points
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct CompressedVerificationKey<G1, G2> {
+	pub alpha: G1,
+	pub beta: G2,
+	pub gamma: G2,
+	pub delta: G2,
+	pub ic: Vec<G1>,
+}
+
+/// Groth16 proof in the compact format: SCALE encoded compressed points.
+///
+/// BLS12-381 points use the zcash encoding (48 bytes G1, 96 bytes G2), BN254 points the gnark one
+/// (32 bytes G1, 64 bytes G2), so a proof takes the curve index and 192 or 128 bytes.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum CompactProof {
+	Bls12381(CompressedProof<Bls12381G1, Bls12381G2>),
+	Bn254(CompressedProof<Bn254G1, Bn254G2>),
+}
+
+/// Groth16 verification key in the compact format, encoded like `CompactProof`
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum CompactVKey {
+	Bls12381(CompressedVerificationKey<Bls12381G1, Bls12381G2>),
+	Bn254(CompressedVerificationKey<Bn254G1, Bn254G2>),
+}
+
+/// Turns compressed point into an array, its size is fixed by the curve
+fn fixed<const N: usize>(bytes: Vec<u8>) -> [u8; N] {
+	bytes.try_into().expect("compressed point has the size of the curve")
+}
+
+impl<const G1: usize, const G2: usize> CompressedProof<[u8; G1], [u8; G2]> {
+	fn compress<C: Curve>(proof: &GProof<C>) -> Self {
+		CompressedProof {
+			a: fixed(C::g1_to_compressed(&proof.a)),
+			b: fixed(C::g2_to_compressed(&proof.b)),
+			c: fixed(C::g1_to_compressed(&proof.c)),
+		}
+	}
+
+	fn decompress<C: Curve>(&self) -> Result<GProof<C>, GProofCreationError> {
+		let g1 = |bytes: &[u8; G1]| {
+			C::g1_from_compressed(bytes)
+				.and_then(finite_g1::<C>)
+				.map_err(GProofCreationError::PointCreationError)
+		};
+		Ok(GProof {
+			a: g1(&self.a)?,
+			b: C::g2_from_compressed(&self.b)
+				.and_then(finite_g2::<C>)
+				.map_err(GProofCreationError::PointCreationError)?,
+			c: g1(&self.c)?,
+		})
+	}
+}
+
+impl<const G1: usize, const G2: usize> CompressedVerificationKey<[u8; G1], [u8; G2]> {
+	fn compress<C: Curve>(vk: &VerificationKey<C>) -> Self {
+		CompressedVerificationKey {
+			alpha: fixed(C::g1_to_compressed(&vk.alpha)),
+			beta: fixed(C::g2_to_compressed(&vk.beta)),
+			gamma: fixed(C::g2_to_compressed(&vk.gamma)),
+			delta: fixed(C::g2_to_compressed(&vk.delta)),
+			ic: vk.ic.iter().map(|point| fixed(C::g1_to_compressed(point))).collect(),
+		}
+	}
+
+	fn decompress<C: Curve>(&self) -> Result<VerificationKey<C>, VerificationKeyCreationError> {
+		let g1 = |bytes: &[u8; G1]| {
+			C::g1_from_compressed(bytes).map_err(VerificationKeyCreationError::PointCreationError)
+		};
+		// Only `ic` points may be at infinity, any other one makes the key degenerate
+		let finite_g1_point = |bytes: &[u8; G1]| {
+			C::g1_from_compressed(bytes)
+				.and_then(finite_g1::<C>)
+				.map_err(VerificationKeyCreationError::PointCreationError)
+		};
+		let finite_g2_point = |bytes: &[u8; G2]| {
+			C::g2_from_compressed(bytes)
+				.and_then(finite_g2::<C>)
+				.map_err(VerificationKeyCreationError::PointCreationError)
+		};
+		Ok(VerificationKey {
+			alpha: finite_g1_point(&self.alpha)?,
+			beta: finite_g2_point(&self.beta)?,
+			gamma: finite_g2_point(&self.gamma)?,
+			delta: finite_g2_point(&self.delta)?,
+			ic: self.ic.iter().map(g1).collect::<Result<_, _>>()?,
+		})
+	}
+}
+
+impl CompactProof {
+	/// Converts snarkjs proof, its points are validated on the way
+	pub fn from_snarkjs(proof: Proof) -> Result<Self, GProofCreationError> {
+		match SupportedCurve::from_name(&proof.curve) {
+			Some(SupportedCurve::Bls12381) => Ok(CompactProof::Bls12381(
+				CompressedProof::compress(&prepare_proof::<Bls12_381>(proof)?),
+			)),
+			Some(SupportedCurve::Bn254) =>
+				Ok(CompactProof::Bn254(CompressedProof::compress(&prepare_proof::<Bn254>(proof)?))),
+			None => Err(GProofCreationError::NotSupportedCurve),
+		}
+	}
+
+	pub fn curve(&self) -> SupportedCurve {
+		match self {
+			CompactProof::Bls12381(_) => SupportedCurve::Bls12381,
+			CompactProof::Bn254(_) => SupportedCurve::Bn254,
+		}
+	}
+
+	/// Decompresses the points, `C` has to be the curve of the proof
+	pub fn decompress<C: Curve>(&self) -> Result<GProof<C>, GProofCreationError> {
+		match self {
+			CompactProof::Bls12381(proof) => proof.decompress(),
+			CompactProof::Bn254(proof) => proof.decompress(),
+		}
+	}
+}
+
+impl CompactVKey {
+	/// Converts snarkjs verification key, its points are validated on the way
+	pub fn from_snarkjs(vk: VKey) -> Result<Self, VerificationKeyCreationError> {
+		match SupportedCurve::from_name(&vk.curve) {
+			Some(SupportedCurve::Bls12381) => Ok(CompactVKey::Bls12381(
+				CompressedVerificationKey::compress(&prepare_verification_key::<Bls12_381>(vk)?),
+			)),
+			Some(SupportedCurve::Bn254) => Ok(CompactVKey::Bn254(
+				CompressedVerificationKey::compress(&prepare_verification_key::<Bn254>(vk)?),
+			)),
+			None => Err(VerificationKeyCreationError::NotSupportedCurve),
+		}
+	}
+
+	pub fn curve(&self) -> SupportedCurve {
+		match self {
+			CompactVKey::Bls12381(_) => SupportedCurve::Bls12381,
+			CompactVKey::Bn254(_) => SupportedCurve::Bn254,
+		}
+	}
+
+	/// Number of `ic` points, one more than the number of public inputs
+	pub fn ic_len(&self) -> usize {
+		match self {
+			CompactVKey::Bls12381(vk) => vk.ic.len(),
+			CompactVKey::Bn254(vk) => vk.ic.len(),
+		}
+	}
+
+	/// Decompresses the points, `C` has to be the curve of the key
+	pub fn decompress<C: Curve>(&self) -> Result<VerificationKey<C>, VerificationKeyCreationError> {
+		match self {
+			CompactVKey::Bls12381(vk) => vk.decompress(),
+			CompactVKey::Bn254(vk) => vk.decompress(),
+		}
+	}
+}
@@ -26,175 +26,773 @@
 // IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
 // DEALINGS IN THE SOFTWARE.
 
-use serde::{Deserialize, Deserializer};
+use crate::{
+	compact::{CompactProof, CompactVKey},
+	verify::SupportedProtocol,
+};
+use codec::DecodeAll;
+use serde::Deserialize;
 use sp_std::vec::Vec;
+use uint::FromDecStrErr;
 
 construct_uint! {
 	pub struct U256(6);
 }
 
-type Number = [u8; 48];
+/// Big-endian integer wide enough to hold a coordinate of any supported curve
+pub type Number = [u8; 48];
 
 type G1 = [Number; 3];
 type G2 = [[Number; 2]; 3];
 
+/// BLS12-381 base field modulus, the largest one of the supported curves
+const MAX_BASE_FIELD_MODULUS: Number = [
+	0x1a, 0x01, 0x11, 0xea, 0x39, 0x7f, 0xe6, 0x9a, 0x4b, 0x1b, 0xa7, 0xb6, 0x43, 0x4b, 0xac, 0xd7,
+	0x64, 0x77, 0x4b, 0x84, 0xf3, 0x85, 0x12, 0xbf, 0x67, 0x30, 0xd2, 0xa0, 0xf6, 0xb0, 0xf6, 0x24,
+	0x1e, 0xab, 0xff, 0xfe, 0xb1, 0x53, 0xff, 0xff, 0xb9, 0xfe, 0xff, 0xff, 0xff, 0xff, 0xaa, 0xab,
+];
+
+/// BLS12-381 scalar field modulus, the largest one of the supported curves
+const MAX_SCALAR_FIELD_MODULUS: ScalarNumber = [
+	0x73, 0xed, 0xa7, 0x53, 0x29, 0x9d, 0x7d, 0x48, 0x33, 0x39, 0xd8, 0x08, 0x09, 0xa1, 0xd8, 0x05,
+	0x53, 0xbd, 0xa4, 0x02, 0xff, 0xfe, 0x5b, 0xfe, 0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x01,
+];
+
+/// Reason for rejecting a number of the json representation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NumberError {
+	/// Not a decimal, or `0x` prefixed hexadecimal where allowed, integer
+	BadNumber,
+	/// Too big for the integer it is stored in
+	Overflow,
+	/// Point with a wrong number of coordinates
+	WrongArity,
+	/// Not smaller than the field modulus of any supported curve
+	NonCanonical,
+}
+
+/// G1 point as written in json, decimal coordinates
+type JsonG1<'a> = Vec<&'a str>;
+
+/// G2 point as written in json, pairs of decimal coordinates
+type JsonG2<'a> = Vec<Vec<&'a str>>;
+
 /// Struct representing snarkjs generated verification key
-#[derive(Deserialize)]
 pub struct VKey {
-	#[serde(deserialize_with = "str_to_u8_vec_deserializer")]
 	pub protocol: Vec<u8>,
-	#[serde(deserialize_with = "str_to_u8_vec_deserializer")]
 	pub curve: Vec<u8>,
-	#[serde(alias = "nPublic")]
 	pub public_inputs_len: u8,
-	#[serde(alias = "vk_alpha_1")]
-	#[serde(deserialize_with = "g1_deserializer")]
 	pub alpha: G1,
-	#[serde(alias = "vk_beta_2")]
-	#[serde(deserialize_with = "g2_deserializer")]
 	pub beta: G2,
-	#[serde(alias = "vk_gamma_2")]
-	#[serde(deserialize_with = "g2_deserializer")]
 	pub gamma: G2,
-	#[serde(alias = "vk_delta_2")]
-	#[serde(deserialize_with = "g2_deserializer")]
 	pub delta: G2,
-	#[serde(alias = "IC")]
-	#[serde(deserialize_with = "vec_g1_deserializer")]
 	pub ic: Vec<G1>,
 }
 
-#[derive(Debug)]
+/// `VKey` as written by snarkjs. Its numbers are parsed only once the json is, so their errors
+/// are returned as they are rather than through serde
+#[derive(Deserialize)]
+struct JsonVKey<'a> {
+	protocol: &'a str,
+	curve: &'a str,
+	#[serde(alias = "nPublic")]
+	public_inputs_len: u8,
+	#[serde(alias = "vk_alpha_1", borrow)]
+	alpha: JsonG1<'a>,
+	#[serde(alias = "vk_beta_2", borrow)]
+	beta: JsonG2<'a>,
+	#[serde(alias = "vk_gamma_2", borrow)]
+	gamma: JsonG2<'a>,
+	#[serde(alias = "vk_delta_2", borrow)]
+	delta: JsonG2<'a>,
+	#[serde(alias = "IC", borrow)]
+	ic: Vec<JsonG1<'a>>,
+}
+
+#[derive(Debug, PartialEq)]
 pub enum VKeyDeserializationError {
 	SerdeError,
+	CodecError,
+	NotSupportedProtocol,
+	InvalidNumber(NumberError),
+}
+
+impl From<serde_json::Error> for VKeyDeserializationError {
+	fn from(_: serde_json::Error) -> Self {
+		VKeyDeserializationError::SerdeError
+	}
+}
+
+impl From<NumberError> for VKeyDeserializationError {
+	fn from(error: NumberError) -> Self {
+		VKeyDeserializationError::InvalidNumber(error)
+	}
 }
 
 impl VKey {
 	/// Creates `VKey` from json representation
 	pub fn from_json_u8_slice(slice: &[u8]) -> Result<Self, VKeyDeserializationError> {
-		serde_json::from_slice(slice).map_err(|_| VKeyDeserializationError::SerdeError)
+		let vk: JsonVKey = serde_json::from_slice(slice)?;
+		Ok(VKey {
+			protocol: vk.protocol.as_bytes().into(),
+			curve: vk.curve.as_bytes().into(),
+			public_inputs_len: vk.public_inputs_len,
+			alpha: parse_g1(&vk.alpha)?,
+			beta: parse_g2(&vk.beta)?,
+			gamma: parse_g2(&vk.gamma)?,
+			delta: parse_g2(&vk.delta)?,
+			ic: parse_vec_g1(&vk.ic)?,
+		})
 	}
 }
 
 /// Struct representing snarkjs generated proof
-#[derive(Deserialize)]
 pub struct Proof {
-	#[serde(deserialize_with = "str_to_u8_vec_deserializer")]
 	pub protocol: Vec<u8>,
-	#[serde(deserialize_with = "str_to_u8_vec_deserializer")]
 	pub curve: Vec<u8>,
-	#[serde(alias = "pi_a")]
-	#[serde(deserialize_with = "g1_deserializer")]
 	pub a: G1,
-	#[serde(alias = "pi_b")]
-	#[serde(deserialize_with = "g2_deserializer")]
 	pub b: G2,
-	#[serde(alias = "pi_c")]
-	#[serde(deserialize_with = "g1_deserializer")]
 	pub c: G1,
 }
 
-#[derive(Debug)]
+/// `Proof` as written by snarkjs
+#[derive(Deserialize)]
+struct JsonProof<'a> {
+	protocol: &'a str,
+	curve: &'a str,
+	#[serde(alias = "pi_a", borrow)]
+	a: JsonG1<'a>,
+	#[serde(alias = "pi_b", borrow)]
+	b: JsonG2<'a>,
+	#[serde(alias = "pi_c", borrow)]
+	c: JsonG1<'a>,
+}
+
+#[derive(Debug, PartialEq)]
 pub enum ProofDeserializationError {
 	SerdeError,
+	CodecError,
+	NotSupportedProtocol,
+	InvalidNumber(NumberError),
+}
+
+impl From<serde_json::Error> for ProofDeserializationError {
+	fn from(_: serde_json::Error) -> Self {
+		ProofDeserializationError::SerdeError
+	}
+}
+
+impl From<NumberError> for ProofDeserializationError {
+	fn from(error: NumberError) -> Self {
+		ProofDeserializationError::InvalidNumber(error)
+	}
 }
 
 impl Proof {
 	/// Creates `Proof` from json representation
 	pub fn from_json_u8_slice(slice: &[u8]) -> Result<Self, ProofDeserializationError> {
-		serde_json::from_slice(slice).map_err(|_| ProofDeserializationError::SerdeError)
-	}
-}
-/// Turns G1 point represented by numbers in decimal format into G1 point represented by numbers in
-/// binary format
-pub fn g1_deserializer<'de, D>(de: D) -> Result<[Number; 3], D::Error>
-where
-	D: Deserializer<'de>,
-{
-	let mut dec_numbers: [Number; 3] = [[0; 48]; 3];
-	let s: [&str; 3] = serde::Deserialize::deserialize(de)?;
-	for i in 0..3 {
-		U256::from_dec_str(s[i]).unwrap().to_big_endian(dec_numbers[i].as_mut_slice());
-	}
-	Ok(dec_numbers)
-}
-
-/// Turns array of G1 points represented by numbers in decimal format into vector of G1 points
-/// represented by numbers in binary format
-pub fn vec_g1_deserializer<'de, D>(de: D) -> Result<Vec<[Number; 3]>, D::Error>
-where
-	D: Deserializer<'de>,
-{
-	let dec_numbers: Vec<[&str; 3]> = serde::Deserialize::deserialize(de)?;
-	Ok(dec_numbers
-		.iter()
-		.map(|ic| {
-			let mut arr: [Number; 3] = [[0; 48]; 3];
-			for i in 0..3 {
-				U256::from_dec_str(ic[i]).unwrap().to_big_endian(arr[i].as_mut_slice());
-			}
-			arr
+		let proof: JsonProof = serde_json::from_slice(slice)?;
+		Ok(Proof {
+			protocol: proof.protocol.as_bytes().into(),
+			curve: proof.curve.as_bytes().into(),
+			a: parse_g1(&proof.a)?,
+			b: parse_g2(&proof.b)?,
+			c: parse_g1(&proof.c)?,
 		})
-		.collect())
-}
-
-/// Turns G2 point represented by numbers in decimal format into G2 point represented by numbers in
-/// binary format
-pub fn g2_deserializer<'de, D>(de: D) -> Result<[[Number; 2]; 3], D::Error>
-where
-	D: Deserializer<'de>,
-{
-	let mut g2_numbers: [[Number; 2]; 3] = [[[0; 48]; 2]; 3];
-	let dec_numbers: [[&str; 2]; 3] = serde::Deserialize::deserialize(de)?;
-	for i in 0..3 {
-		for j in 0..2 {
-			U256::from_dec_str(dec_numbers[i][j])
-				.unwrap()
-				.to_big_endian(g2_numbers[i][j].as_mut_slice());
+	}
+}
+/// Struct representing snarkjs generated PLONK verification key
+pub struct PlonkVKey {
+	pub protocol: Vec<u8>,
+	pub curve: Vec<u8>,
+	pub public_inputs_len: u8,
+	pub power: u8,
+	pub k1: ScalarNumber,
+	pub k2: ScalarNumber,
+	pub qm: G1,
+	pub ql: G1,
+	pub qr: G1,
+	pub qo: G1,
+	pub qc: G1,
+	pub s1: G1,
+	pub s2: G1,
+	pub s3: G1,
+	pub x_2: G2,
+	pub w: ScalarNumber,
+}
+
+/// `PlonkVKey` as written by snarkjs
+#[derive(Deserialize)]
+struct JsonPlonkVKey<'a> {
+	protocol: &'a str,
+	curve: &'a str,
+	#[serde(alias = "nPublic")]
+	public_inputs_len: u8,
+	power: u8,
+	k1: &'a str,
+	k2: &'a str,
+	#[serde(alias = "Qm", borrow)]
+	qm: JsonG1<'a>,
+	#[serde(alias = "Ql", borrow)]
+	ql: JsonG1<'a>,
+	#[serde(alias = "Qr", borrow)]
+	qr: JsonG1<'a>,
+	#[serde(alias = "Qo", borrow)]
+	qo: JsonG1<'a>,
+	#[serde(alias = "Qc", borrow)]
+	qc: JsonG1<'a>,
+	#[serde(alias = "S1", borrow)]
+	s1: JsonG1<'a>,
+	#[serde(alias = "S2", borrow)]
+	s2: JsonG1<'a>,
+	#[serde(alias = "S3", borrow)]
+	s3: JsonG1<'a>,
+	#[serde(alias = "X_2", borrow)]
+	x_2: JsonG2<'a>,
+	w: &'a str,
+}
+
+impl PlonkVKey {
+	/// Creates `PlonkVKey` from json representation
+	pub fn from_json_u8_slice(slice: &[u8]) -> Result<Self, VKeyDeserializationError> {
+		let vk: JsonPlonkVKey = serde_json::from_slice(slice)?;
+		Ok(PlonkVKey {
+			protocol: vk.protocol.as_bytes().into(),
+			curve: vk.curve.as_bytes().into(),
+			public_inputs_len: vk.public_inputs_len,
+			power: vk.power,
+			k1: parse_scalar(vk.k1)?,
+			k2: parse_scalar(vk.k2)?,
+			qm: parse_g1(&vk.qm)?,
+			ql: parse_g1(&vk.ql)?,
+			qr: parse_g1(&vk.qr)?,
+			qo: parse_g1(&vk.qo)?,
+			qc: parse_g1(&vk.qc)?,
+			s1: parse_g1(&vk.s1)?,
+			s2: parse_g1(&vk.s2)?,
+			s3: parse_g1(&vk.s3)?,
+			x_2: parse_g2(&vk.x_2)?,
+			w: parse_scalar(vk.w)?,
+		})
+	}
+}
+
+/// Struct representing snarkjs generated PLONK proof
+pub struct PlonkProof {
+	pub protocol: Vec<u8>,
+	pub curve: Vec<u8>,
+	pub a: G1,
+	pub b: G1,
+	pub c: G1,
+	pub z: G1,
+	pub t1: G1,
+	pub t2: G1,
+	pub t3: G1,
+	pub wxi: G1,
+	pub wxiw: G1,
+	pub eval_a: ScalarNumber,
+	pub eval_b: ScalarNumber,
+	pub eval_c: ScalarNumber,
+	pub eval_s1: ScalarNumber,
+	pub eval_s2: ScalarNumber,
+	pub eval_zw: ScalarNumber,
+}
+
+/// `PlonkProof` as written by snarkjs
+#[derive(Deserialize)]
+struct JsonPlonkProof<'a> {
+	protocol: &'a str,
+	curve: &'a str,
+	#[serde(alias = "A", borrow)]
+	a: JsonG1<'a>,
+	#[serde(alias = "B", borrow)]
+	b: JsonG1<'a>,
+	#[serde(alias = "C", borrow)]
+	c: JsonG1<'a>,
+	#[serde(alias = "Z", borrow)]
+	z: JsonG1<'a>,
+	#[serde(alias = "T1", borrow)]
+	t1: JsonG1<'a>,
+	#[serde(alias = "T2", borrow)]
+	t2: JsonG1<'a>,
+	#[serde(alias = "T3", borrow)]
+	t3: JsonG1<'a>,
+	#[serde(alias = "Wxi", borrow)]
+	wxi: JsonG1<'a>,
+	#[serde(alias = "Wxiw", borrow)]
+	wxiw: JsonG1<'a>,
+	eval_a: &'a str,
+	eval_b: &'a str,
+	eval_c: &'a str,
+	eval_s1: &'a str,
+	eval_s2: &'a str,
+	eval_zw: &'a str,
+}
+
+impl PlonkProof {
+	/// Creates `PlonkProof` from json representation
+	pub fn from_json_u8_slice(slice: &[u8]) -> Result<Self, ProofDeserializationError> {
+		let proof: JsonPlonkProof = serde_json::from_slice(slice)?;
+		Ok(PlonkProof {
+			protocol: proof.protocol.as_bytes().into(),
+			curve: proof.curve.as_bytes().into(),
+			a: parse_g1(&proof.a)?,
+			b: parse_g1(&proof.b)?,
+			c: parse_g1(&proof.c)?,
+			z: parse_g1(&proof.z)?,
+			t1: parse_g1(&proof.t1)?,
+			t2: parse_g1(&proof.t2)?,
+			t3: parse_g1(&proof.t3)?,
+			wxi: parse_g1(&proof.wxi)?,
+			wxiw: parse_g1(&proof.wxiw)?,
+			eval_a: parse_scalar(proof.eval_a)?,
+			eval_b: parse_scalar(proof.eval_b)?,
+			eval_c: parse_scalar(proof.eval_c)?,
+			eval_s1: parse_scalar(proof.eval_s1)?,
+			eval_s2: parse_scalar(proof.eval_s2)?,
+			eval_zw: parse_scalar(proof.eval_zw)?,
+		})
+	}
+}
+
+/// Struct representing snarkjs generated fflonk verification key
+pub struct FflonkVKey {
+	pub protocol: Vec<u8>,
+	pub curve: Vec<u8>,
+	pub public_inputs_len: u8,
+	pub power: u8,
+	pub k1: ScalarNumber,
+	pub k2: ScalarNumber,
+	pub w: ScalarNumber,
+	pub w3: ScalarNumber,
+	pub w4: ScalarNumber,
+	pub w8: ScalarNumber,
+	pub wr: ScalarNumber,
+	pub x_2: G2,
+	pub c0: G1,
+}
+
+/// `FflonkVKey` as written by snarkjs
+#[derive(Deserialize)]
+struct JsonFflonkVKey<'a> {
+	protocol: &'a str,
+	curve: &'a str,
+	#[serde(alias = "nPublic")]
+	public_inputs_len: u8,
+	power: u8,
+	k1: &'a str,
+	k2: &'a str,
+	w: &'a str,
+	w3: &'a str,
+	w4: &'a str,
+	w8: &'a str,
+	wr: &'a str,
+	#[serde(alias = "X_2", borrow)]
+	x_2: JsonG2<'a>,
+	#[serde(alias = "C0", borrow)]
+	c0: JsonG1<'a>,
+}
+
+impl FflonkVKey {
+	/// Creates `FflonkVKey` from json representation
+	pub fn from_json_u8_slice(slice: &[u8]) -> Result<Self, VKeyDeserializationError> {
+		let vk: JsonFflonkVKey = serde_json::from_slice(slice)?;
+		Ok(FflonkVKey {
+			protocol: vk.protocol.as_bytes().into(),
+			curve: vk.curve.as_bytes().into(),
+			public_inputs_len: vk.public_inputs_len,
+			power: vk.power,
+			k1: parse_scalar(vk.k1)?,
+			k2: parse_scalar(vk.k2)?,
+			w: parse_scalar(vk.w)?,
+			w3: parse_scalar(vk.w3)?,
+			w4: parse_scalar(vk.w4)?,
+			w8: parse_scalar(vk.w8)?,
+			wr: parse_scalar(vk.wr)?,
+			x_2: parse_g2(&vk.x_2)?,
+			c0: parse_g1(&vk.c0)?,
+		})
+	}
+}
+
+/// Commitments of snarkjs generated fflonk proof
+pub struct FflonkPolynomials {
+	pub c1: G1,
+	pub c2: G1,
+	pub w1: G1,
+	pub w2: G1,
+}
+
+/// `FflonkPolynomials` as written by snarkjs
+#[derive(Deserialize)]
+struct JsonFflonkPolynomials<'a> {
+	#[serde(alias = "C1", borrow)]
+	c1: JsonG1<'a>,
+	#[serde(alias = "C2", borrow)]
+	c2: JsonG1<'a>,
+	#[serde(alias = "W1", borrow)]
+	w1: JsonG1<'a>,
+	#[serde(alias = "W2", borrow)]
+	w2: JsonG1<'a>,
+}
+
+/// Evaluations of snarkjs generated fflonk proof
+pub struct FflonkEvaluations {
+	pub ql: ScalarNumber,
+	pub qr: ScalarNumber,
+	pub qm: ScalarNumber,
+	pub qo: ScalarNumber,
+	pub qc: ScalarNumber,
+	pub s1: ScalarNumber,
+	pub s2: ScalarNumber,
+	pub s3: ScalarNumber,
+	pub a: ScalarNumber,
+	pub b: ScalarNumber,
+	pub c: ScalarNumber,
+	pub z: ScalarNumber,
+	pub zw: ScalarNumber,
+	pub t1w: ScalarNumber,
+	pub t2w: ScalarNumber,
+}
+
+/// `FflonkEvaluations` as written by snarkjs, the `inv` hint used by the Solidity verifier is
+/// ignored
+#[derive(Deserialize)]
+struct JsonFflonkEvaluations<'a> {
+	ql: &'a str,
+	qr: &'a str,
+	qm: &'a str,
+	qo: &'a str,
+	qc: &'a str,
+	s1: &'a str,
+	s2: &'a str,
+	s3: &'a str,
+	a: &'a str,
+	b: &'a str,
+	c: &'a str,
+	z: &'a str,
+	zw: &'a str,
+	t1w: &'a str,
+	t2w: &'a str,
+}
+
+/// Struct representing snarkjs generated fflonk proof
+pub struct FflonkProof {
+	pub protocol: Vec<u8>,
+	pub curve: Vec<u8>,
+	pub polynomials: FflonkPolynomials,
+	pub evaluations: FflonkEvaluations,
+}
+
+/// `FflonkProof` as written by snarkjs
+#[derive(Deserialize)]
+struct JsonFflonkProof<'a> {
+	protocol: &'a str,
+	curve: &'a str,
+	#[serde(borrow)]
+	polynomials: JsonFflonkPolynomials<'a>,
+	#[serde(borrow)]
+	evaluations: JsonFflonkEvaluations<'a>,
+}
+
+impl FflonkProof {
+	/// Creates `FflonkProof` from json representation
+	pub fn from_json_u8_slice(slice: &[u8]) -> Result<Self, ProofDeserializationError> {
+		let proof: JsonFflonkProof = serde_json::from_slice(slice)?;
+		let (polynomials, evaluations) = (proof.polynomials, proof.evaluations);
+		Ok(FflonkProof {
+			protocol: proof.protocol.as_bytes().into(),
+			curve: proof.curve.as_bytes().into(),
+			polynomials: FflonkPolynomials {
+				c1: parse_g1(&polynomials.c1)?,
+				c2: parse_g1(&polynomials.c2)?,
+				w1: parse_g1(&polynomials.w1)?,
+				w2: parse_g1(&polynomials.w2)?,
+			},
+			evaluations: FflonkEvaluations {
+				ql: parse_scalar(evaluations.ql)?,
+				qr: parse_scalar(evaluations.qr)?,
+				qm: parse_scalar(evaluations.qm)?,
+				qo: parse_scalar(evaluations.qo)?,
+				qc: parse_scalar(evaluations.qc)?,
+				s1: parse_scalar(evaluations.s1)?,
+				s2: parse_scalar(evaluations.s2)?,
+				s3: parse_scalar(evaluations.s3)?,
+				a: parse_scalar(evaluations.a)?,
+				b: parse_scalar(evaluations.b)?,
+				c: parse_scalar(evaluations.c)?,
+				z: parse_scalar(evaluations.z)?,
+				zw: parse_scalar(evaluations.zw)?,
+				t1w: parse_scalar(evaluations.t1w)?,
+				t2w: parse_scalar(evaluations.t2w)?,
+			},
+		})
+	}
+}
+
+/// Fields common to every snarkjs generated verification key and proof
+#[derive(Deserialize)]
+struct Header<'a> {
+	protocol: &'a str,
+}
+
+/// Tells whether the bytes hold snarkjs json rather than the compact binary format, SCALE encoded
+/// enums of the latter start with a small variant index, never with `{` or a whitespace
+fn is_json(slice: &[u8]) -> bool {
+	slice.iter().find(|byte| !byte.is_ascii_whitespace()) == Some(&b'{')
+}
+
+/// Verification key of any supported protocol
+#[allow(clippy::large_enum_variant)]
+pub enum ProtocolVKey {
+	Groth16(VKey),
+	CompactGroth16(CompactVKey),
+	Plonk(PlonkVKey),
+	Fflonk(FflonkVKey),
+}
+
+impl ProtocolVKey {
+	/// Creates verification key from its json or compact binary representation
+	pub fn from_u8_slice(slice: &[u8]) -> Result<Self, VKeyDeserializationError> {
+		if is_json(slice) {
+			return Self::from_json_u8_slice(slice)
+		}
+		CompactVKey::decode_all(&mut &slice[..])
+			.map(ProtocolVKey::CompactGroth16)
+			.map_err(|_| VKeyDeserializationError::CodecError)
+	}
+
+	/// Creates verification key of the protocol named in its json representation
+	pub fn from_json_u8_slice(slice: &[u8]) -> Result<Self, VKeyDeserializationError> {
+		let header: Header =
+			serde_json::from_slice(slice).map_err(|_| VKeyDeserializationError::SerdeError)?;
+		match SupportedProtocol::from_name(header.protocol.as_bytes()) {
+			Some(SupportedProtocol::Groth16) =>
+				Ok(ProtocolVKey::Groth16(VKey::from_json_u8_slice(slice)?)),
+			Some(SupportedProtocol::Plonk) =>
+				Ok(ProtocolVKey::Plonk(PlonkVKey::from_json_u8_slice(slice)?)),
+			Some(SupportedProtocol::Fflonk) =>
+				Ok(ProtocolVKey::Fflonk(FflonkVKey::from_json_u8_slice(slice)?)),
+			None => Err(VKeyDeserializationError::NotSupportedProtocol),
+		}
+	}
+
+	pub fn protocol(&self) -> SupportedProtocol {
+		match self {
+			ProtocolVKey::Groth16(_) | ProtocolVKey::CompactGroth16(_) =>
+				SupportedProtocol::Groth16,
+			ProtocolVKey::Plonk(_) => SupportedProtocol::Plonk,
+			ProtocolVKey::Fflonk(_) => SupportedProtocol::Fflonk,
+		}
+	}
+
+	pub fn curve(&self) -> &[u8] {
+		match self {
+			ProtocolVKey::Groth16(vk) => &vk.curve,
+			ProtocolVKey::CompactGroth16(vk) => vk.curve().name(),
+			ProtocolVKey::Plonk(vk) => &vk.curve,
+			ProtocolVKey::Fflonk(vk) => &vk.curve,
+		}
+	}
+
+	pub fn public_inputs_len(&self) -> u8 {
+		match self {
+			ProtocolVKey::Groth16(vk) => vk.public_inputs_len,
+			ProtocolVKey::CompactGroth16(vk) =>
+				vk.ic_len().saturating_sub(1).try_into().unwrap_or(u8::MAX),
+			ProtocolVKey::Plonk(vk) => vk.public_inputs_len,
+			ProtocolVKey::Fflonk(vk) => vk.public_inputs_len,
+		}
+	}
+}
+
+/// Proof of any supported protocol
+#[allow(clippy::large_enum_variant)]
+pub enum ProtocolProof {
+	Groth16(Proof),
+	CompactGroth16(CompactProof),
+	Plonk(PlonkProof),
+	Fflonk(FflonkProof),
+}
+
+impl ProtocolProof {
+	/// Creates proof from its json or compact binary representation
+	pub fn from_u8_slice(slice: &[u8]) -> Result<Self, ProofDeserializationError> {
+		if is_json(slice) {
+			return Self::from_json_u8_slice(slice)
+		}
+		CompactProof::decode_all(&mut &slice[..])
+			.map(ProtocolProof::CompactGroth16)
+			.map_err(|_| ProofDeserializationError::CodecError)
+	}
+
+	/// Creates proof of the protocol named in its json representation
+	pub fn from_json_u8_slice(slice: &[u8]) -> Result<Self, ProofDeserializationError> {
+		let header: Header =
+			serde_json::from_slice(slice).map_err(|_| ProofDeserializationError::SerdeError)?;
+		match SupportedProtocol::from_name(header.protocol.as_bytes()) {
+			Some(SupportedProtocol::Groth16) =>
+				Ok(ProtocolProof::Groth16(Proof::from_json_u8_slice(slice)?)),
+			Some(SupportedProtocol::Plonk) =>
+				Ok(ProtocolProof::Plonk(PlonkProof::from_json_u8_slice(slice)?)),
+			Some(SupportedProtocol::Fflonk) =>
+				Ok(ProtocolProof::Fflonk(FflonkProof::from_json_u8_slice(slice)?)),
+			None => Err(ProofDeserializationError::NotSupportedProtocol),
+		}
+	}
+
+	pub fn protocol(&self) -> SupportedProtocol {
+		match self {
+			ProtocolProof::Groth16(_) | ProtocolProof::CompactGroth16(_) =>
+				SupportedProtocol::Groth16,
+			ProtocolProof::Plonk(_) => SupportedProtocol::Plonk,
+			ProtocolProof::Fflonk(_) => SupportedProtocol::Fflonk,
+		}
+	}
+
+	pub fn curve(&self) -> &[u8] {
+		match self {
+			ProtocolProof::Groth16(proof) => &proof.curve,
+			ProtocolProof::CompactGroth16(proof) => proof.curve().name(),
+			ProtocolProof::Plonk(proof) => &proof.curve,
+			ProtocolProof::Fflonk(proof) => &proof.curve,
+		}
+	}
+}
+
+/// Turns coordinate in decimal format into big-endian integer
+fn parse_coordinate(dec_str: &str) -> Result<Number, NumberError> {
+	let number = from_dec_str(dec_str)?;
+	let mut coordinate: Number = [0; 48];
+	number.to_big_endian(coordinate.as_mut_slice());
+	if coordinate >= MAX_BASE_FIELD_MODULUS {
+		return Err(NumberError::NonCanonical)
+	}
+	Ok(coordinate)
+}
+
+/// Turns G1 point given as decimal strings into an array of its coordinates
+fn parse_g1(dec_numbers: &[&str]) -> Result<[Number; 3], NumberError> {
+	if dec_numbers.len() != 3 {
+		return Err(NumberError::WrongArity)
+	}
+	let mut numbers: [Number; 3] = [[0; 48]; 3];
+	for (number, dec_number) in numbers.iter_mut().zip(dec_numbers) {
+		*number = parse_coordinate(dec_number)?;
+	}
+	Ok(numbers)
+}
+
+/// Turns array of G1 points given as decimal strings into a vector of arrays of their coordinates
+fn parse_vec_g1(points: &[JsonG1]) -> Result<Vec<G1>, NumberError> {
+	points.iter().map(|point| parse_g1(point)).collect()
+}
+
+/// Turns G2 point given as pairs of decimal strings into an array of its coordinates
+fn parse_g2(dec_numbers: &[Vec<&str>]) -> Result<G2, NumberError> {
+	if dec_numbers.len() != 3 || dec_numbers.iter().any(|pair| pair.len() != 2) {
+		return Err(NumberError::WrongArity)
+	}
+	let mut g2_numbers: G2 = [[[0; 48]; 2]; 3];
+	for (numbers, dec_pair) in g2_numbers.iter_mut().zip(dec_numbers) {
+		for (number, dec_number) in numbers.iter_mut().zip(dec_pair) {
+			*number = parse_coordinate(dec_number)?;
 		}
 	}
 	Ok(g2_numbers)
 }
 
-/// Turns `str` into `Vec<u8>`
-pub fn str_to_u8_vec_deserializer<'de, D>(de: D) -> Result<Vec<u8>, D::Error>
-where
-	D: Deserializer<'de>,
-{
-	let s: &str = serde::Deserialize::deserialize(de)?;
-	Ok(s.as_bytes().into())
+/// Turns scalar field element in decimal or `0x` prefixed hexadecimal format into big-endian
+/// integer
+fn parse_scalar(s: &str) -> Result<ScalarNumber, NumberError> {
+	let scalar = parse_public_input(s)?;
+	if scalar >= MAX_SCALAR_FIELD_MODULUS {
+		return Err(NumberError::NonCanonical)
+	}
+	Ok(scalar)
 }
 
-#[derive(Debug)]
+/// Scalar field element represented as 32 bytes big-endian integer
+pub type ScalarNumber = [u8; 32];
+
+/// Public input represented as 32 bytes big-endian integer
+pub type PublicInput = [u8; 32];
+
+#[derive(Debug, PartialEq)]
 pub enum PublicInputsDeserializationError {
 	SerdeError,
+	InvalidNumber(NumberError),
 }
 
-/// Creates vector of `u64` representing public inputs
+/// Creates vector of `PublicInput` representing public inputs
 ///
 /// # Arguments
-/// * `inputs` - A byte array slice containing array of integers in json array form
+/// * `inputs` - A byte array slice containing array of integers in json array form. Every integer
+///   is a string in decimal or `0x` prefixed hexadecimal format.
 pub fn deserialize_public_inputs(
 	inputs: &[u8],
-) -> Result<Vec<u64>, PublicInputsDeserializationError> {
-	let inputs: Vec<&str> = serde_json::from_slice(inputs).unwrap();
-	let mut parsed_inputs: Vec<u64> = Vec::with_capacity(inputs.len());
-	for input in inputs {
-		match input.parse::<u64>() {
-			Ok(n) => parsed_inputs.push(n),
-			Err(_) => return Err(PublicInputsDeserializationError::SerdeError),
-		}
+) -> Result<Vec<PublicInput>, PublicInputsDeserializationError> {
+	let inputs: Vec<&str> =
+		serde_json::from_slice(inputs).map_err(|_| PublicInputsDeserializationError::SerdeError)?;
+	inputs
+		.into_iter()
+		.map(parse_public_input)
+		.collect::<Result<_, _>>()
+		.map_err(PublicInputsDeserializationError::InvalidNumber)
+}
+
+/// Parses decimal or `0x` prefixed hexadecimal string into 32 bytes big-endian integer
+fn parse_public_input(input: &str) -> Result<PublicInput, NumberError> {
+	let number = match input.strip_prefix("0x") {
+		Some(hex) => from_hex_str(hex)?,
+		None => from_dec_str(input)?,
+	};
+	if number.bits() > 256 {
+		return Err(NumberError::Overflow)
+	}
+	let mut bytes: Number = [0; 48];
+	number.to_big_endian(bytes.as_mut_slice());
+	let mut public_input: PublicInput = [0; 32];
+	public_input.copy_from_slice(&bytes[16..]);
+	Ok(public_input)
+}
+
+/// Turns decimal string into `U256`
+fn from_dec_str(dec: &str) -> Result<U256, NumberError> {
+	// `U256::from_dec_str` turns an empty string into zero
+	if dec.is_empty() {
+		return Err(NumberError::BadNumber)
+	}
+	U256::from_dec_str(dec).map_err(|error| match error {
+		FromDecStrErr::InvalidCharacter => NumberError::BadNumber,
+		FromDecStrErr::InvalidLength => NumberError::Overflow,
+	})
+}
+
+/// Turns hexadecimal string into `U256`
+fn from_hex_str(hex: &str) -> Result<U256, NumberError> {
+	if hex.is_empty() {
+		return Err(NumberError::BadNumber)
 	}
-	Ok(parsed_inputs)
+	if hex.len() > 96 {
+		return Err(NumberError::Overflow)
+	}
+	let mut number = U256::zero();
+	for c in hex.chars() {
+		number = (number << 4) | U256::from(c.to_digit(16).ok_or(NumberError::BadNumber)?);
+	}
+	Ok(number)
 }
 
 #[cfg(test)]
 mod tests {
-	use crate::deserialization::{deserialize_public_inputs, Number, Proof, VKey, U256};
+	use crate::deserialization::{
+		deserialize_public_inputs, Number, NumberError, Proof, ProofDeserializationError,
+		ProtocolProof, ProtocolVKey, PublicInputsDeserializationError, VKey,
+		VKeyDeserializationError, U256,
+	};
 
-	#[test]
-	fn test_vk_deserialization() {
-		let vk = r#"{
+	const VK_JSON: &str = r#"{
  "protocol": "groth16",
  "curve": "bls12381",
  "nPublic": 1,
@@ -288,7 +886,39 @@ mod tests {
   ]
  ]
 }"#;
-		let v_key: VKey = VKey::from_json_u8_slice(vk.as_bytes()).unwrap();
+
+	const PROOF_JSON: &str = r#"{
+ "pi_a": [
+  "2820173869801000183955769496344276101575675010174203082588560105436284422640780128231242184109173767085197647834267",
+  "1152541093585973172499551859168528642628429504007613830168996825879806250289422935864437193085184388469171892221011",
+  "1"
+ ],
+ "pi_b": [
+  [
+   "54413090665354594353317256815335793052197307111690011609872716599840507808706991989403605000342095250665180513594",
+   "3343285764332210309442703216841128605678475246673133285314301861643378387001264758819444434632415207857557469906035"
+  ],
+  [
+   "262180403851765105493218367619507205740764669171746348153545090105487398261554724750259283942935411519021270362742",
+   "3777303780170739988308854254585940898119682705621212814969008224084499326863117961704608873229314936725151172212883"
+  ],
+  [
+   "1",
+   "0"
+  ]
+ ],
+ "pi_c": [
+  "3006923877346016048391409264528383002939756547318806158402407618139299715778986391175418348881376388499383266389442",
+  "1307513151230758506579817970515482216448699470263630520204374492458260823057506418477833081567163581258564509876945",
+  "1"
+ ],
+ "protocol": "groth16",
+ "curve": "bls12381"
+}"#;
+
+	#[test]
+	fn test_vk_deserialization() {
+		let v_key: VKey = VKey::from_json_u8_slice(VK_JSON.as_bytes()).unwrap();
 
 		assert_eq!(v_key.alpha[0], from_dec_string("2635983656263320256511463995836413167331869092392943593306076905516259749312747842295447349507189592731785901862558"));
 		assert_eq!(v_key.alpha[1], from_dec_string("743892996456702519498029594549937288641619275055957975879157306988929970626325326222697609972550552691064908651931"));
@@ -330,36 +960,7 @@ mod tests {
 
 	#[test]
 	fn test_proof_deserialization() {
-		let proof = r#"{
- "pi_a": [
-  "2820173869801000183955769496344276101575675010174203082588560105436284422640780128231242184109173767085197647834267",
-  "1152541093585973172499551859168528642628429504007613830168996825879806250289422935864437193085184388469171892221011",
-  "1"
- ],
- "pi_b": [
-  [
-   "54413090665354594353317256815335793052197307111690011609872716599840507808706991989403605000342095250665180513594",
-   "3343285764332210309442703216841128605678475246673133285314301861643378387001264758819444434632415207857557469906035"
-  ],
-  [
-   "262180403851765105493218367619507205740764669171746348153545090105487398261554724750259283942935411519021270362742",
-   "3777303780170739988308854254585940898119682705621212814969008224084499326863117961704608873229314936725151172212883"
-  ],
-  [
-   "1",
-   "0"
-  ]
- ],
- "pi_c": [
-  "3006923877346016048391409264528383002939756547318806158402407618139299715778986391175418348881376388499383266389442",
-  "1307513151230758506579817970515482216448699470263630520204374492458260823057506418477833081567163581258564509876945",
-  "1"
- ],
- "protocol": "groth16",
- "curve": "bls12381"
-}"#;
-
-		let proof: Proof = Proof::from_json_u8_slice(proof.as_bytes()).unwrap();
+		let proof: Proof = Proof::from_json_u8_slice(PROOF_JSON.as_bytes()).unwrap();
 
 		assert_eq!(proof.a[0], from_dec_string("2820173869801000183955769496344276101575675010174203082588560105436284422640780128231242184109173767085197647834267"));
 		assert_eq!(proof.a[1], from_dec_string("1152541093585973172499551859168528642628429504007613830168996825879806250289422935864437193085184388469171892221011"));
@@ -388,7 +989,149 @@ mod tests {
 		let public_inputs =
 			deserialize_public_inputs(public_inputs_json.as_bytes().into()).unwrap();
 		assert_eq!(public_inputs.len(), 1);
-		assert_eq!(public_inputs[0], 33);
+		assert_eq!(public_inputs[0], from_dec_string("33")[16..]);
+	}
+
+	#[test]
+	fn big_public_inputs_deserialization() {
+		let public_inputs_json = r#"[
+ "52435875175126190479447740508185965837690552500527637822603658699938581184512",
+ "0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000000",
+ "0x0"
+]"#;
+		let public_inputs = deserialize_public_inputs(public_inputs_json.as_bytes()).unwrap();
+		assert_eq!(public_inputs.len(), 3);
+		assert_eq!(
+			public_inputs[0],
+			from_dec_string(
+				"52435875175126190479447740508185965837690552500527637822603658699938581184512"
+			)[16..]
+		);
+		assert_eq!(
+			public_inputs[1],
+			from_dec_string(
+				"52435875175126190479447740508185965837690552500527637822603658699938581184512"
+			)[16..]
+		);
+		assert_eq!(public_inputs[2], [0; 32]);
+	}
+
+	#[test]
+	fn public_inputs_wider_than_256_bits() {
+		let public_inputs_json = r#"[
+ "115792089237316195423570985008687907853269984665640564039457584007913129639936"
+]"#;
+		assert!(deserialize_public_inputs(public_inputs_json.as_bytes()).is_err());
+	}
+
+	#[test]
+	fn malformed_public_inputs() {
+		for public_inputs_json in [r#"["-1"]"#, r#"["0x"]"#, r#"[""]"#, r#"["0xzz"]"#, r#"["1.5"]"#]
+		{
+			assert!(deserialize_public_inputs(public_inputs_json.as_bytes()).is_err());
+		}
+	}
+
+	#[test]
+	fn invalid_numbers_in_proof() {
+		let pi_a_x = r#""2820173869801000183955769496344276101575675010174203082588560105436284422640780128231242184109173767085197647834267""#;
+		let base_field_modulus = "4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559787";
+		let cases = [
+			(pi_a_x, "\"12a\"".to_owned(), NumberError::BadNumber),
+			(pi_a_x, "\"\"".to_owned(), NumberError::BadNumber),
+			(pi_a_x, format!("\"{}\"", "9".repeat(200)), NumberError::Overflow),
+			(pi_a_x, "\"1\", \"2\"".to_owned(), NumberError::WrongArity),
+			(pi_a_x, format!("\"{}\"", base_field_modulus), NumberError::NonCanonical),
+			("\"1\",\n   \"0\"", "\"1\"".to_owned(), NumberError::WrongArity),
+		];
+		for (from, to, error) in cases {
+			let proof = PROOF_JSON.replacen(from, &to, 1);
+			assert_ne!(proof, PROOF_JSON);
+			assert_eq!(
+				Proof::from_json_u8_slice(proof.as_bytes()).err(),
+				Some(ProofDeserializationError::InvalidNumber(error))
+			);
+		}
+	}
+
+	#[test]
+	fn invalid_numbers_in_vk() {
+		let vk = VK_JSON.replacen("\"1\"\n  ]\n ]", "\"1\",\n   \"1\"\n  ]\n ]", 1);
+		assert_ne!(vk, VK_JSON);
+		assert_eq!(
+			VKey::from_json_u8_slice(vk.as_bytes()).err(),
+			Some(VKeyDeserializationError::InvalidNumber(NumberError::WrongArity))
+		);
+		let vk = VK_JSON.replacen("\"nPublic\": 1", "\"nPublic\": 1.5", 1);
+		assert_eq!(
+			VKey::from_json_u8_slice(vk.as_bytes()).err(),
+			Some(VKeyDeserializationError::SerdeError)
+		);
+	}
+
+	#[test]
+	fn invalid_numbers_in_public_inputs() {
+		let cases = [
+			(r#"["12a"]"#.to_owned(), NumberError::BadNumber),
+			(format!(r#"["{}"]"#, "9".repeat(200)), NumberError::Overflow),
+			(format!(r#"["0x{}"]"#, "f".repeat(97)), NumberError::Overflow),
+		];
+		for (public_inputs_json, error) in cases {
+			assert_eq!(
+				deserialize_public_inputs(public_inputs_json.as_bytes()),
+				Err(PublicInputsDeserializationError::InvalidNumber(error))
+			);
+		}
+		for public_inputs_json in [r#"[1, 2]"#, r#"["1""#, r#"{}"#, ""] {
+			assert_eq!(
+				deserialize_public_inputs(public_inputs_json.as_bytes()),
+				Err(PublicInputsDeserializationError::SerdeError)
+			);
+		}
+	}
+
+	#[test]
+	fn truncated_json_is_rejected() {
+		for len in 0..PROOF_JSON.len() {
+			assert!(ProtocolProof::from_u8_slice(&PROOF_JSON.as_bytes()[..len]).is_err());
+		}
+		for len in 0..VK_JSON.len() {
+			assert!(ProtocolVKey::from_u8_slice(&VK_JSON.as_bytes()[..len]).is_err());
+		}
+		let public_inputs_json = br#"["33", "0x21"]"#;
+		for len in 0..public_inputs_json.len() {
+			assert!(deserialize_public_inputs(&public_inputs_json[..len]).is_err());
+		}
+	}
+
+	#[test]
+	fn mutated_json_does_not_panic() {
+		// xorshift, deterministic so that failures are reproducible
+		let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+		let mut next = move || {
+			state ^= state << 13;
+			state ^= state >> 7;
+			state ^= state << 17;
+			state as usize
+		};
+		let alphabet = b"0123456789abcdefx-.,:\"[]{} e";
+		for _ in 0..2000 {
+			let mut proof = PROOF_JSON.as_bytes().to_vec();
+			let mut vk = VK_JSON.as_bytes().to_vec();
+			let mut public_inputs = br#"["33", "0x21"]"#.to_vec();
+			for _ in 0..1 + next() % 4 {
+				let byte = alphabet[next() % alphabet.len()];
+				let i = next() % proof.len();
+				proof[i] = byte;
+				let i = next() % vk.len();
+				vk[i] = byte;
+				let i = next() % public_inputs.len();
+				public_inputs[i] = byte;
+			}
+			let _ = ProtocolProof::from_u8_slice(&proof);
+			let _ = ProtocolVKey::from_u8_slice(&vk);
+			let _ = deserialize_public_inputs(&public_inputs);
+		}
 	}
 
 	fn from_dec_string(dec_str: &str) -> Number {
@@ -0,0 +1,469 @@
+// MIT License
+
+// Copyright (c) 2022 Bright Inventions
+
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+use crate::{
+	plonk::{lagrange_evaluations, public_input_evaluation, Transcript},
+	verify::{Curve, PublicInputs, VerificationError::InvalidVerificationKey, VerificationResult},
+};
+use sp_std::prelude::*;
+
+/// Represents fflonk verification key
+pub struct FflonkVerificationKey<C: Curve> {
+	pub public_inputs_len: u8,
+	/// The evaluation domain has `2^power` elements
+	pub power: u8,
+	pub k1: C::Scalar,
+	pub k2: C::Scalar,
+	/// Generator of the evaluation domain
+	pub w: C::Scalar,
+	/// Primitive 3rd root of unity
+	pub w3: C::Scalar,
+	/// Primitive 4th root of unity
+	pub w4: C::Scalar,
+	/// Primitive 8th root of unity
+	pub w8: C::Scalar,
+	/// Cube root of `w`
+	pub wr: C::Scalar,
+	/// `[τ]₂` of the KZG setup
+	pub x_2: C::G2,
+	/// Commitment to the combined selector and permutation polynomials
+	pub c0: C::G1,
+}
+
+/// Represents fflonk proof
+pub struct FProof<C: Curve> {
+	pub c1: C::G1,
+	pub c2: C::G1,
+	pub w1: C::G1,
+	pub w2: C::G1,
+	pub eval_ql: C::Scalar,
+	pub eval_qr: C::Scalar,
+	pub eval_qm: C::Scalar,
+	pub eval_qo: C::Scalar,
+	pub eval_qc: C::Scalar,
+	pub eval_s1: C::Scalar,
+	pub eval_s2: C::Scalar,
+	pub eval_s3: C::Scalar,
+	pub eval_a: C::Scalar,
+	pub eval_b: C::Scalar,
+	pub eval_c: C::Scalar,
+	pub eval_z: C::Scalar,
+	pub eval_zw: C::Scalar,
+	pub eval_t1w: C::Scalar,
+	pub eval_t2w: C::Scalar,
+}
+
+fn pow<C: Curve>(base: C::Scalar, exponent: usize) -> C::Scalar {
+	(0..exponent).fold(C::scalar_from_u64(1), |acc, _| acc * base)
+}
+
+/// Evaluates polynomial given by its coefficients, lowest degree first
+fn evaluate<C: Curve>(coefficients: &[C::Scalar], x: C::Scalar) -> C::Scalar {
+	coefficients
+		.iter()
+		.rev()
+		.fold(C::scalar_from_u64(0), |acc, coefficient| acc * x + *coefficient)
+}
+
+/// Evaluates at y the polynomial which matches C(rᵢ) at every root, given Lagrange basis Lᵢ(y)
+fn interpolate<C: Curve>(
+	coefficients: &[C::Scalar],
+	roots: &[C::Scalar],
+	basis: &[C::Scalar],
+) -> C::Scalar {
+	roots
+		.iter()
+		.zip(basis)
+		.fold(C::scalar_from_u64(0), |acc, (root, l)| acc + evaluate::<C>(coefficients, *root) * *l)
+}
+
+/// Returns `h * ωⁱ` for i in 0..len
+fn roots<C: Curve>(h: C::Scalar, w: C::Scalar, len: usize) -> Vec<C::Scalar> {
+	let mut roots = Vec::with_capacity(len);
+	let mut root = h;
+	for _ in 0..len {
+		roots.push(root);
+		root = root * w;
+	}
+	roots
+}
+
+/// Computes Lᵢ(y) = (yⁿ - hⁿ) / (n * rᵢⁿ⁻¹ * (y - rᵢ)) for the roots rᵢ of `Xⁿ - hⁿ`
+fn lagrange_basis<C: Curve>(
+	roots: &[C::Scalar],
+	h_n: C::Scalar,
+	y: C::Scalar,
+) -> Option<Vec<C::Scalar>> {
+	let len = roots.len();
+	let numerator = pow::<C>(y, len) - h_n;
+	let n = C::scalar_from_u64(len as u64);
+	roots
+		.iter()
+		.map(|root| {
+			C::scalar_inverse(&(n * pow::<C>(*root, len - 1) * (y - *root)))
+				.map(|denominator| numerator * denominator)
+		})
+		.collect()
+}
+
+/// Checks the roots of unity of the verification key, so the vanishing polynomials of the opening
+/// sets have the closed forms used by the verifier
+fn roots_are_valid<C: Curve>(vk: &FflonkVerificationKey<C>) -> bool {
+	let one = C::scalar_from_u64(1);
+	let is_primitive = |root: C::Scalar, order: usize| {
+		pow::<C>(root, order) == one && pow::<C>(root, order / 2) != one
+	};
+	pow::<C>(vk.wr, 3) == vk.w &&
+		pow::<C>(vk.w3, 3) == one &&
+		vk.w3 != one &&
+		is_primitive(vk.w4, 4) &&
+		is_primitive(vk.w8, 8)
+}
+
+/// Verifies given fflonk proof with given verification key and public inputs, following snarkjs
+/// `fflonk_verify`
+pub fn verify_fflonk<C: Curve>(
+	vk: FflonkVerificationKey<C>,
+	proof: FProof<C>,
+	inputs: PublicInputs<C>,
+) -> VerificationResult {
+	if inputs.len() != vk.public_inputs_len as usize || vk.power >= 64 || !roots_are_valid(&vk) {
+		return Err(InvalidVerificationKey)
+	}
+	let one = C::scalar_from_u64(1);
+
+	let mut transcript = Transcript::<C>::new();
+	transcript.add_point(&vk.c0);
+	for input in &inputs {
+		transcript.add_scalar(input);
+	}
+	transcript.add_point(&proof.c1);
+	let beta = transcript.challenge();
+
+	transcript.add_scalar(&beta);
+	let gamma = transcript.challenge();
+
+	transcript.add_scalar(&gamma);
+	transcript.add_point(&proof.c2);
+	let xi_seed = transcript.challenge();
+
+	// h₀ = ξ_seed³, h₁ = h₀², h₂ = h₁ * ξ_seed², h₃ = h₂ * ∛ω, so ξ = h₀⁸ = h₁⁴ = h₂³ and h₃³ = ξω
+	let xi_seed2 = xi_seed * xi_seed;
+	let h0 = xi_seed2 * xi_seed;
+	let h1 = h0 * h0;
+	let h2 = h1 * xi_seed2;
+	let h3 = h2 * vk.wr;
+	let xi = h2 * h2 * h2;
+	let xiw = xi * vk.w;
+
+	let evaluations = [
+		proof.eval_ql,
+		proof.eval_qr,
+		proof.eval_qm,
+		proof.eval_qo,
+		proof.eval_qc,
+		proof.eval_s1,
+		proof.eval_s2,
+		proof.eval_s3,
+		proof.eval_a,
+		proof.eval_b,
+		proof.eval_c,
+		proof.eval_z,
+		proof.eval_zw,
+		proof.eval_t1w,
+		proof.eval_t2w,
+	];
+	transcript.add_scalar(&xi_seed);
+	for eval in &evaluations {
+		transcript.add_scalar(eval);
+	}
+	let alpha = transcript.challenge();
+
+	transcript.add_scalar(&alpha);
+	transcript.add_point(&proof.w1);
+	let y = transcript.challenge();
+
+	// Zₕ(ξ) = ξⁿ - 1
+	let mut xin = xi;
+	for _ in 0..vk.power {
+		xin = xin * xin;
+	}
+	let zh = xin - one;
+	let zh_inverse = match C::scalar_inverse(&zh) {
+		Some(zh_inverse) => zh_inverse,
+		None => return Ok(false),
+	};
+
+	let lagrange = match lagrange_evaluations::<C>(xi, zh, vk.w, vk.power, inputs.len()) {
+		Some(lagrange) => lagrange,
+		None => return Ok(false),
+	};
+	let pi = public_input_evaluation::<C>(&inputs, &lagrange);
+
+	// T₀(ξ) = (qL * a + qR * b + qM * a * b + qO * c + qC + PI(ξ)) / Zₕ(ξ)
+	let t0 = (proof.eval_ql * proof.eval_a +
+		proof.eval_qr * proof.eval_b +
+		proof.eval_qm * proof.eval_a * proof.eval_b +
+		proof.eval_qo * proof.eval_c +
+		proof.eval_qc +
+		pi) * zh_inverse;
+
+	// T₁(ξ) = L₁(ξ) * (z - 1) / Zₕ(ξ)
+	let t1 = lagrange[0] * (proof.eval_z - one) * zh_inverse;
+
+	// T₂(ξ) = ((a + βξ + γ)(b + βξk₁ + γ)(c + βξk₂ + γ)z -
+	//          (a + βs₁ + γ)(b + βs₂ + γ)(c + βs₃ + γ)zω) / Zₕ(ξ)
+	let beta_xi = beta * xi;
+	let t2 = ((proof.eval_a + beta_xi + gamma) *
+		(proof.eval_b + beta_xi * vk.k1 + gamma) *
+		(proof.eval_c + beta_xi * vk.k2 + gamma) *
+		proof.eval_z -
+		(proof.eval_a + beta * proof.eval_s1 + gamma) *
+			(proof.eval_b + beta * proof.eval_s2 + gamma) *
+			(proof.eval_c + beta * proof.eval_s3 + gamma) *
+			proof.eval_zw) *
+		zh_inverse;
+
+	// Opening sets S₀ = {h₀ω₈ⁱ}, S₁ = {h₁ω₄ⁱ}, S₂ = {h₂ω₃ⁱ} ∪ {h₃ω₃ⁱ} and their vanishing
+	// polynomials evaluated at y
+	let s0 = roots::<C>(h0, vk.w8, 8);
+	let s1 = roots::<C>(h1, vk.w4, 4);
+	let s2 = roots::<C>(h2, vk.w3, 3);
+	let s3 = roots::<C>(h3, vk.w3, 3);
+	let z0 = pow::<C>(y, 8) - xi;
+	let z1 = pow::<C>(y, 4) - xi;
+	let z2 = pow::<C>(y, 3) - xi;
+	let z3 = pow::<C>(y, 3) - xiw;
+
+	let (l0, l1, l2, l3, xi_diff_inverse, quotient1, quotient2) = match (
+		lagrange_basis::<C>(&s0, xi, y),
+		lagrange_basis::<C>(&s1, xi, y),
+		lagrange_basis::<C>(&s2, xi, y),
+		lagrange_basis::<C>(&s3, xiw, y),
+		C::scalar_inverse(&(xi - xiw)),
+		C::scalar_inverse(&z1),
+		C::scalar_inverse(&(z2 * z3)),
+	) {
+		(Some(l0), Some(l1), Some(l2), Some(l3), Some(d), Some(q1), Some(q2)) =>
+			(l0, l1, l2, l3, d, alpha * z0 * q1, alpha * alpha * z0 * q2),
+		_ => return Ok(false),
+	};
+
+	// r₀(y) = Σ C₀(h₀ω₈ⁱ) Lᵢ(y), where
+	// C₀(X) = qL + qR X + qO X² + qM X³ + qC X⁴ + s₁ X⁵ + s₂ X⁶ + s₃ X⁷
+	let c0 = [
+		proof.eval_ql,
+		proof.eval_qr,
+		proof.eval_qo,
+		proof.eval_qm,
+		proof.eval_qc,
+		proof.eval_s1,
+		proof.eval_s2,
+		proof.eval_s3,
+	];
+	let r0 = interpolate::<C>(&c0, &s0, &l0);
+
+	// r₁(y) = Σ C₁(h₁ω₄ⁱ) Lᵢ(y), where C₁(X) = a + b X + c X² + T₀(ξ) X³
+	let c1 = [proof.eval_a, proof.eval_b, proof.eval_c, t0];
+	let r1 = interpolate::<C>(&c1, &s1, &l1);
+
+	// r₂(y) = Σ C₂(h₂ω₃ⁱ) Lᵢ(y) + Σ C₂(h₃ω₃ⁱ) Lᵢ₊₃(y), where C₂(X) = z + T₁ X + T₂ X², evaluated at
+	// ξ on the first coset and at ξω on the second one
+	let c2 = [proof.eval_z, t1, t2];
+	let c2w = [proof.eval_zw, proof.eval_t1w, proof.eval_t2w];
+	// The basis of S₂ ∪ S₃ is the basis of S₂ times Z_S₃(y) / Z_S₃(ξ) and the basis of S₃ times
+	// Z_S₂(y) / Z_S₂(ξω)
+	let r2 = (interpolate::<C>(&c2, &s2, &l2) * z3 - interpolate::<C>(&c2w, &s3, &l3) * z2) *
+		xi_diff_inverse;
+
+	// A₁ = F - E - J + y * W₂, where
+	// F = C₀ + quotient₁ * C₁ + quotient₂ * C₂
+	// E = (r₀ + quotient₁ * r₁ + quotient₂ * r₂) * G1
+	// J = Z_S₀(y) * W₁
+	let e = r0 + quotient1 * r1 + quotient2 * r2;
+	let a1 = C::g1_multi_exp(
+		&vk.c0,
+		&[proof.c1, proof.c2, proof.w1, proof.w2, C::g1_generator()],
+		&[quotient1, quotient2, -z0, y, -e],
+	);
+
+	// e(-A₁, [1]₂) * e(W₂, [τ]₂) = 1
+	Ok(C::pairing_product_is_identity(&[(C::g1_neg(&a1), C::g2_generator()), (proof.w2, vk.x_2)]))
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		bn254::Bn254,
+		common::{prepare_fflonk_proof, prepare_fflonk_verification_key},
+		deserialization::{FflonkProof, FflonkVKey},
+		fflonk::{verify_fflonk, FProof, FflonkVerificationKey},
+		verify::{prepare_public_inputs, PublicInputs, VerificationError},
+	};
+
+	// Key and proof of circom/task.circom over bn128 for x = 3, so the only public input is y = 12
+	const VK_JSON: &str = r#"{
+ "protocol": "fflonk",
+ "curve": "bn128",
+ "nPublic": 1,
+ "power": 3,
+ "k1": "2",
+ "k2": "3",
+ "w": "19540430494807482326159819597004422086093766032135589407132600596362845576832",
+ "wr": "13274704216607947843011480449124596415239537050559949017414504948711435969894",
+ "w3": "4407920970296243842393367215006156084916469457145843978461",
+ "w4": "21888242871839275217838484774961031246007050428528088939761107053157389710902",
+ "w8": "19540430494807482326159819597004422086093766032135589407132600596362845576832",
+ "X_2": [
+  [
+   "15903878421108759439848478618074911574701566925570229139405616333111755535845",
+   "4291294403358638570039388526470878576987994586754394716672632188568865367486"
+  ],
+  [
+   "18531874304451210335666117718257049670818307824295916814921889502511919838294",
+   "2979484662042156423400334245990653844506029192010916415899010348289180103697"
+  ],
+  [
+   "1",
+   "0"
+  ]
+ ],
+ "C0": [
+  "15430478022952747567281363769135958239699498064319376976856365673890527175523",
+  "14355169710197482966037336888063443614101929220282507648686726841697796256294",
+  "1"
+ ]
+}"#;
+
+	const PROOF_JSON: &str = r#"{
+ "polynomials": {
+  "C1": [
+   "6056851803853156526422026485589820674611636223475926784301179222414323137764",
+   "7072713591611864728130413511231546499851317092634744758857192410732036302583",
+   "1"
+  ],
+  "C2": [
+   "10138582921131393296871211261513073434842624002233417638173111496965207167305",
+   "15417743445269105065814266879891425710017914093418656378456680353028994955160",
+   "1"
+  ],
+  "W1": [
+   "8424003839246399592275061594687777201491777936530514040432369178932011919109",
+   "11048609772151598725219814359873939662058780018435353492835870006048718454489",
+   "1"
+  ],
+  "W2": [
+   "12410450569706012324819204875160791796933326608730268682085971310132808981683",
+   "13094661934285929561613766285132491483176878867455530586515698940252304966520",
+   "1"
+  ]
+ },
+ "evaluations": {
+  "ql": "13193859200870671640172085365437828020278616074331492992085587967179507346382",
+  "qr": "0",
+  "qm": "20384287399079250017330340823480099809091108165603403258056756745994831637528",
+  "qo": "12700870399927656298210586125721461841428067695267551143523333465519590275526",
+  "qc": "10185740962175657164609247878681690491184294419467308513750750298335776738923",
+  "s1": "9969822935792325902298324746648758154373190610033968351665462040152469807418",
+  "s2": "66115539258269445511521773361088335265545103184557754850001340835232153373",
+  "s3": "15518909339936644069099489320891493774305233844767999721194093506356646278002",
+  "a": "14496738519376518814156278225574046034165288695098044102020538566224520001642",
+  "b": "5263125896663929424976851135556719821095173004145797340522622518416871988092",
+  "c": "13486966956790162789797445748393905156262952321707961386340216767670398273386",
+  "z": "11581421146568986481597713236324377101479121116140516488867670053438216876326",
+  "zw": "12271767543796158703760030954039183864088952967517860635247167443500189839730",
+  "t1w": "19202546427701517470082958969123001153374970711625310247160987427839503702403",
+  "t2w": "3273223232739549770309504070173848084995396500525172608330596352614595597457"
+ },
+ "protocol": "fflonk",
+ "curve": "bn128"
+}"#;
+
+	fn prepare_inputs(inputs: &[u8]) -> PublicInputs<Bn254> {
+		let inputs = inputs
+			.iter()
+			.map(|input| {
+				let mut public_input = [0u8; 32];
+				public_input[31] = *input;
+				public_input
+			})
+			.collect();
+		prepare_public_inputs::<Bn254>(inputs).unwrap()
+	}
+
+	fn prepare_vk() -> FflonkVerificationKey<Bn254> {
+		prepare_fflonk_verification_key(FflonkVKey::from_json_u8_slice(VK_JSON.as_bytes()).unwrap())
+			.unwrap()
+	}
+
+	fn prepare_proof() -> FProof<Bn254> {
+		prepare_fflonk_proof(FflonkProof::from_json_u8_slice(PROOF_JSON.as_bytes()).unwrap())
+			.unwrap()
+	}
+
+	#[test]
+	fn verify_correct_proof() {
+		let result = verify_fflonk(prepare_vk(), prepare_proof(), prepare_inputs(&[12]));
+
+		assert!(result.unwrap())
+	}
+
+	#[test]
+	fn verify_incorrect_proof() {
+		let result = verify_fflonk(prepare_vk(), prepare_proof(), prepare_inputs(&[13]));
+
+		assert!(!result.unwrap())
+	}
+
+	#[test]
+	fn verify_with_tampered_evaluation() {
+		let mut proof = prepare_proof();
+		proof.eval_t2w = proof.eval_t2w + proof.eval_a;
+
+		let result = verify_fflonk(prepare_vk(), proof, prepare_inputs(&[12]));
+
+		assert!(!result.unwrap())
+	}
+
+	#[test]
+	fn verify_with_incorrect_public_inputs_len() {
+		let result = verify_fflonk(prepare_vk(), prepare_proof(), prepare_inputs(&[12, 12]));
+
+		assert_eq!(result.err().unwrap(), VerificationError::InvalidVerificationKey)
+	}
+
+	#[test]
+	fn verify_with_invalid_root_of_unity() {
+		let mut vk = prepare_vk();
+		vk.w4 = vk.w8;
+
+		let result = verify_fflonk(vk, prepare_proof(), prepare_inputs(&[12]));
+
+		assert_eq!(result.err().unwrap(), VerificationError::InvalidVerificationKey)
+	}
+}
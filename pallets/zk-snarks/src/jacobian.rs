@@ -0,0 +1,222 @@
+// MIT License
+
+// Copyright (c) 2022 Bright Inventions
+
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! snarkjs writes curve points in Jacobian coordinates `(x, y, z)`, which stand for the affine
+//! point `(x / z², y / z³)`, with `z = 0` for the point at infinity. snarkjs itself always
+//! normalizes `z` to `1`, but other tools may not, so the coordinates are brought to the affine
+//! form before the points are created.
+
+use crate::{deserialization::Number, verify::PointError};
+
+construct_uint! {
+	pub struct U768(12);
+}
+
+/// Arithmetic modulo the base field modulus of a curve. Elements are kept below the modulus,
+/// so a product of two of them always fits into `U768`.
+struct Field {
+	modulus: U768,
+}
+
+impl Field {
+	fn new(modulus: &Number) -> Self {
+		Field { modulus: U768::from_big_endian(modulus) }
+	}
+
+	fn element(&self, number: &Number) -> Result<U768, PointError> {
+		let element = U768::from_big_endian(number);
+		if element >= self.modulus {
+			return Err(PointError::NonCanonicalCoordinate)
+		}
+		Ok(element)
+	}
+
+	fn to_number(element: U768) -> Number {
+		let mut bytes = [0u8; 96];
+		element.to_big_endian(&mut bytes);
+		bytes[48..].try_into().expect("element is smaller than the modulus")
+	}
+
+	fn add(&self, a: U768, b: U768) -> U768 {
+		(a + b) % self.modulus
+	}
+
+	fn sub(&self, a: U768, b: U768) -> U768 {
+		(a + self.modulus - b) % self.modulus
+	}
+
+	fn mul(&self, a: U768, b: U768) -> U768 {
+		(a * b) % self.modulus
+	}
+
+	/// Inverse from the Fermat's little theorem, `a^(p - 2)`, `a` must not be zero
+	fn inverse(&self, a: U768) -> U768 {
+		let exponent = self.modulus - U768::from(2);
+		let mut result = U768::one();
+		for bit in (0..exponent.bits()).rev() {
+			result = self.mul(result, result);
+			if exponent.bit(bit) {
+				result = self.mul(result, a);
+			}
+		}
+		result
+	}
+
+	fn fq2_mul(&self, a: (U768, U768), b: (U768, U768)) -> (U768, U768) {
+		// Both supported curves build the quadratic extension with u² = -1
+		(
+			self.sub(self.mul(a.0, b.0), self.mul(a.1, b.1)),
+			self.add(self.mul(a.0, b.1), self.mul(a.1, b.0)),
+		)
+	}
+
+	/// `1 / (a₀ + a₁u) = (a₀ - a₁u) / (a₀² + a₁²)`, `a` must not be zero
+	fn fq2_inverse(&self, a: (U768, U768)) -> (U768, U768) {
+		let norm = self.inverse(self.add(self.mul(a.0, a.0), self.mul(a.1, a.1)));
+		(self.mul(a.0, norm), self.sub(U768::zero(), self.mul(a.1, norm)))
+	}
+}
+
+/// Brings G1 point to the affine coordinates, `None` stands for the point at infinity. Every
+/// coordinate has to be smaller than the `modulus`.
+pub fn g1_to_affine(
+	point: &[Number; 3],
+	modulus: &Number,
+) -> Result<Option<(Number, Number)>, PointError> {
+	let field = Field::new(modulus);
+	let [x, y, z] = point;
+	let (x, y, z) = (field.element(x)?, field.element(y)?, field.element(z)?);
+	if z.is_zero() {
+		return Ok(None)
+	}
+	if z == U768::one() {
+		return Ok(Some((Field::to_number(x), Field::to_number(y))))
+	}
+	let z_inverse = field.inverse(z);
+	let z_inverse_2 = field.mul(z_inverse, z_inverse);
+	let z_inverse_3 = field.mul(z_inverse_2, z_inverse);
+	let x = Field::to_number(field.mul(x, z_inverse_2));
+	let y = Field::to_number(field.mul(y, z_inverse_3));
+	Ok(Some((x, y)))
+}
+
+/// Brings G2 point to the affine coordinates, `None` stands for the point at infinity. Every
+/// coordinate has to be smaller than the `modulus`.
+pub fn g2_to_affine(
+	point: &[[Number; 2]; 3],
+	modulus: &Number,
+) -> Result<Option<([Number; 2], [Number; 2])>, PointError> {
+	let field = Field::new(modulus);
+	let fq2 = |[c0, c1]: &[Number; 2]| -> Result<(U768, U768), PointError> {
+		Ok((field.element(c0)?, field.element(c1)?))
+	};
+	let [x, y, z] = point;
+	let (x, y, z) = (fq2(x)?, fq2(y)?, fq2(z)?);
+	let to_numbers = |(c0, c1)| [Field::to_number(c0), Field::to_number(c1)];
+	if z.0.is_zero() && z.1.is_zero() {
+		return Ok(None)
+	}
+	if z == (U768::one(), U768::zero()) {
+		return Ok(Some((to_numbers(x), to_numbers(y))))
+	}
+	let z_inverse = field.fq2_inverse(z);
+	let z_inverse_2 = field.fq2_mul(z_inverse, z_inverse);
+	let z_inverse_3 = field.fq2_mul(z_inverse_2, z_inverse);
+	let x = to_numbers(field.fq2_mul(x, z_inverse_2));
+	let y = to_numbers(field.fq2_mul(y, z_inverse_3));
+	Ok(Some((x, y)))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{g1_to_affine, g2_to_affine, Field, U768};
+	use crate::{
+		bn254::Bn254,
+		deserialization::Number,
+		verify::{Bls12_381, Curve, PointError},
+	};
+
+	fn number(value: u64) -> Number {
+		Field::to_number(U768::from(value))
+	}
+
+	#[test]
+	fn inverse() {
+		for modulus in [Bls12_381::BASE_FIELD_MODULUS, Bn254::BASE_FIELD_MODULUS] {
+			let field = Field::new(&modulus);
+			for value in [1u64, 2, 3, 1 << 40] {
+				let value = U768::from(value);
+				assert_eq!(field.mul(value, field.inverse(value)), U768::one());
+			}
+			let value = (U768::from(7), U768::from(11));
+			assert_eq!(field.fq2_mul(value, field.fq2_inverse(value)), (U768::one(), U768::zero()));
+		}
+	}
+
+	#[test]
+	fn g1_jacobian_coordinates() {
+		let modulus = Bls12_381::BASE_FIELD_MODULUS;
+		// (5 * 3², 7 * 3³, 3) is (5, 7)
+		assert_eq!(
+			g1_to_affine(&[number(45), number(189), number(3)], &modulus),
+			Ok(Some((number(5), number(7))))
+		);
+		assert_eq!(
+			g1_to_affine(&[number(5), number(7), number(1)], &modulus),
+			Ok(Some((number(5), number(7))))
+		);
+		assert_eq!(g1_to_affine(&[number(5), number(7), number(0)], &modulus), Ok(None));
+		assert_eq!(
+			g1_to_affine(&[modulus, number(7), number(1)], &modulus),
+			Err(PointError::NonCanonicalCoordinate)
+		);
+		assert_eq!(
+			g1_to_affine(&[number(5), number(7), modulus], &modulus),
+			Err(PointError::NonCanonicalCoordinate)
+		);
+	}
+
+	#[test]
+	fn g2_jacobian_coordinates() {
+		let modulus = Bn254::BASE_FIELD_MODULUS;
+		// z = 2u gives z² = -4 and z³ = -8u, so (-4x, -8uy, 2u) is (x, y)
+		let field = Field::new(&modulus);
+		let minus = |value: u64| Field::to_number(field.sub(U768::zero(), U768::from(value)));
+		let x = [number(5), number(6)];
+		let y = [number(7), number(8)];
+		// -4 * (5 + 6u) = -20 - 24u, -8u * (7 + 8u) = 64 - 56u
+		let point = [[minus(20), minus(24)], [number(64), minus(56)], [number(0), number(2)]];
+		assert_eq!(g2_to_affine(&point, &modulus), Ok(Some((x, y))));
+		assert_eq!(g2_to_affine(&[x, y, [number(1), number(0)]], &modulus), Ok(Some((x, y))));
+		assert_eq!(g2_to_affine(&[x, y, [number(0), number(0)]], &modulus), Ok(None));
+		assert_eq!(
+			g2_to_affine(&[x, y, [number(1), modulus]], &modulus),
+			Err(PointError::NonCanonicalCoordinate)
+		);
+	}
+}
@@ -28,8 +28,8 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
-#[cfg(test)]
-mod mock;
+#[cfg(any(test, feature = "fuzzing"))]
+pub mod mock;
 #[cfg(test)]
 mod tests;
 
@@ -42,33 +42,137 @@ pub mod benchmarking;
 pub mod weights;
 pub use weights::*;
 
+pub mod bn254;
 pub mod common;
+pub mod compact;
 pub mod deserialization;
+pub mod extension;
+pub mod fflonk;
+pub mod jacobian;
+pub mod migrations;
+pub mod origin;
+pub mod plonk;
 pub mod verify;
 
-use frame_support::storage::bounded_vec::BoundedVec;
+use codec::{Decode, Encode};
+pub use extension::CheckZkProof;
+use frame_support::{storage::bounded_vec::BoundedVec, weights::Weight, RuntimeDebug};
+pub use origin::{EnsureZkProven, RawOrigin};
 pub use pallet::*;
+use scale_info::TypeInfo;
+use sp_runtime::transaction_validity::{TransactionLongevity, TransactionPriority};
 use sp_std::vec::Vec;
 
 type PublicInputsDef<T> = BoundedVec<u8, <T as Config>::MaxPublicInputsLength>;
 type ProofDef<T> = BoundedVec<u8, <T as Config>::MaxProofLength>;
 type VerificationKeyDef<T> = BoundedVec<u8, <T as Config>::MaxVerificationKeyLength>;
 
+/// Identifier under which a circuit's verification key is registered.
+pub type CircuitId = u32;
+
+/// Blake2-256 hash of a proof, as it was submitted.
+pub type ProofHash = [u8; 32];
+
+/// Blake2-256 hash of public inputs, each as 32 bytes big-endian integer.
+pub type InputsHash = [u8; 32];
+
+/// Outcome of the verification of a submitted proof.
+#[derive(Clone, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo)]
+pub struct VerificationRecord<AccountId, BlockNumber> {
+	/// The account which submitted the proof.
+	pub who: AccountId,
+	/// The block in which the proof was verified.
+	pub block: BlockNumber,
+	/// The hash of the public inputs of the proof.
+	pub inputs_hash: InputsHash,
+	/// Whether the proof is valid.
+	pub result: bool,
+}
+
+/// How the transaction pool treats proofs of a circuit submitted unsigned.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo)]
+pub struct UnsignedSubmission {
+	/// Priority of the transactions.
+	pub priority: TransactionPriority,
+	/// Number of blocks the transactions stay valid for.
+	pub longevity: TransactionLongevity,
+}
+
+/// Hook called when a proof was verified successfully, within the same extrinsic, so other
+/// pallets can act on the proven statement. Returning an error reverts the whole call.
+pub trait OnVerified<AccountId, CircuitId, PublicInputs> {
+	fn on_verified(
+		who: &AccountId,
+		circuit_id: CircuitId,
+		public_inputs: &PublicInputs,
+	) -> sp_runtime::DispatchResult;
+}
+
+/// Verification of proofs against registered circuits, for other pallets which verify proofs as
+/// part of their own calls.
+pub trait ZkVerifier {
+	/// Verifies `proof` of the statement given by `public_inputs` against the circuit registered
+	/// under `circuit_id`. Nothing is stored, nullifiers of the circuit are only checked.
+	fn verify(
+		circuit_id: CircuitId,
+		proof: Vec<u8>,
+		public_inputs: Vec<u8>,
+	) -> Result<bool, sp_runtime::DispatchError>;
+
+	/// Weight of `verify` for a proof of a statement with `public_inputs_len` public inputs, of the
+	/// heaviest protocol, curve and format, to be charged by the caller.
+	fn weight(public_inputs_len: u32) -> Weight;
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+impl<AccountId, CircuitId: Copy, PublicInputs> OnVerified<AccountId, CircuitId, PublicInputs>
+	for Tuple
+{
+	fn on_verified(
+		who: &AccountId,
+		circuit_id: CircuitId,
+		public_inputs: &PublicInputs,
+	) -> sp_runtime::DispatchResult {
+		for_tuples!( #( Tuple::on_verified(who, circuit_id, public_inputs)?; )* );
+		Ok(())
+	}
+}
+
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
 	use crate::{
-		common::prepare_verification_key,
-		deserialization::{deserialize_public_inputs, Proof, VKey},
+		bn254::Bn254,
+		common::{
+			prepare_fflonk_proof, prepare_fflonk_verification_key, prepare_plonk_proof,
+			prepare_plonk_verification_key, prepare_proof, prepare_verification_key,
+		},
+		deserialization::{
+			deserialize_public_inputs, FflonkProof, FflonkVKey, PlonkProof, PlonkVKey,
+			ProofDeserializationError, ProtocolProof, ProtocolVKey, PublicInput,
+			VKeyDeserializationError,
+		},
+		extension::invalid_transaction,
+		fflonk::verify_fflonk,
+		plonk::verify_plonk,
 		verify::{
-			prepare_public_inputs, verify, G1UncompressedBytes, G2UncompressedBytes, GProof,
-			VerificationKey, SUPPORTED_CURVE, SUPPORTED_PROTOCOL,
+			prepare_public_inputs, verify, verify_batch, Bls12_381, Curve, DecodedVerificationKey,
+			GProof, GProofCreationError, PointError, SupportedCurve, SupportedProtocol,
+			VerificationKey, VerificationKeyCreationError,
+		},
+	};
+	use frame_support::{
+		dispatch::{
+			extract_actual_weight, GetDispatchInfo, PostDispatchInfo, WithPostDispatchInfo,
 		},
+		pallet_prelude::*,
 	};
-	use frame_support::pallet_prelude::*;
 	use frame_system::pallet_prelude::*;
+	use sp_io::hashing::blake2_256;
+	use sp_runtime::traits::{Dispatchable, Zero};
+	use sp_std::boxed::Box;
 
-	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+	pub(crate) const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
 
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
@@ -82,6 +186,24 @@ pub mod pallet {
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 		type WeightInfo: WeightInfo;
 
+		/// The overarching origin, which includes the `ZkProven` origin.
+		type RuntimeOrigin: From<RawOrigin>;
+
+		/// The overarching call, dispatched by `dispatch_with_proof`.
+		type RuntimeCall: Parameter
+			+ Dispatchable<
+				RuntimeOrigin = <Self as Config>::RuntimeOrigin,
+				PostInfo = PostDispatchInfo,
+			> + GetDispatchInfo;
+
+		/// Origin allowed to register circuits.
+		type SetupOrigin: EnsureOrigin<<Self as frame_system::Config>::RuntimeOrigin>;
+
+		/// Called with the public inputs of every successfully verified proof, and the account
+		/// which submitted it - `None` for proofs submitted unsigned.
+		type OnProofVerified: OnVerified<Option<Self::AccountId>, CircuitId, Vec<PublicInput>>;
+
+		/// The maximum length of the public inputs.
 		#[pallet::constant]
 		type MaxPublicInputsLength: Get<u32>;
 
@@ -92,17 +214,66 @@ pub mod pallet {
 		/// The maximum length of the verification key.
 		#[pallet::constant]
 		type MaxVerificationKeyLength: Get<u32>;
+
+		/// The maximum number of proofs verified by a single `verify_batch`.
+		#[pallet::constant]
+		type MaxBatchSize: Get<u32>;
+
+		/// The number of blocks a verification record is kept for, zero keeps them forever.
+		#[pallet::constant]
+		type VerificationRecordLifetime: Get<Self::BlockNumber>;
 	}
 
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
-		VerificationSetupCompleted,
-		VerificationProofSet,
-		VerificationSuccess { who: T::AccountId },
-		VerificationFailed,
+		/// A circuit was registered, `vk_hash` is the blake2-256 hash of its verification key.
+		VerificationSetupCompleted { circuit_id: CircuitId, vk_hash: [u8; 32] },
+		/// A proof was submitted by `who`.
+		VerificationProofSet { circuit_id: CircuitId, who: T::AccountId, proof_hash: ProofHash },
+		/// The submitted proof is valid.
+		VerificationSuccess {
+			circuit_id: CircuitId,
+			who: T::AccountId,
+			proof_hash: ProofHash,
+			inputs_hash: InputsHash,
+		},
+		/// The submitted proof is not valid.
+		VerificationFailed {
+			circuit_id: CircuitId,
+			who: T::AccountId,
+			proof_hash: ProofHash,
+			inputs_hash: InputsHash,
+		},
+		/// A batch of proofs was verified, `results` tells for every one of `proofs`, in order,
+		/// whether it is valid.
+		BatchVerificationCompleted {
+			circuit_id: CircuitId,
+			who: T::AccountId,
+			proofs: Vec<(ProofHash, InputsHash)>,
+			results: Vec<bool>,
+		},
+		/// Proofs of the circuit can be submitted unsigned with `submission`, or can not with
+		/// `None`.
+		UnsignedSubmissionSet { circuit_id: CircuitId, submission: Option<UnsignedSubmission> },
+		/// A valid proof was submitted unsigned.
+		UnsignedProofVerified {
+			circuit_id: CircuitId,
+			proof_hash: ProofHash,
+			inputs_hash: InputsHash,
+		},
+		/// A call was dispatched by `who` with the `ZkProven` origin of a valid proof.
+		ProofDispatched {
+			circuit_id: CircuitId,
+			who: T::AccountId,
+			proof_hash: ProofHash,
+			inputs_hash: InputsHash,
+		},
 	}
 
+	#[pallet::origin]
+	pub type Origin = RawOrigin;
+
 	#[pallet::error]
 	pub enum Error<T> {
 		/// Public inputs mismatch
@@ -117,6 +288,8 @@ pub mod pallet {
 		ProofIsEmpty,
 		/// Verification key, not set.
 		VerificationKeyIsNotSet,
+		/// A circuit with the given id is already registered.
+		CircuitAlreadyRegistered,
 		/// Malformed key
 		MalformedVerificationKey,
 		/// Malformed proof
@@ -125,6 +298,10 @@ pub mod pallet {
 		MalformedPublicInputs,
 		/// Curve is not supported
 		NotSupportedCurve,
+		/// Proof and verification key are defined over different curves
+		CurveMismatch,
+		/// Proof and verification key belong to different protocols
+		ProtocolMismatch,
 		/// Protocol is not supported
 		NotSupportedProtocol,
 		/// There was error during proof verification
@@ -133,133 +310,973 @@ pub mod pallet {
 		ProofCreationError,
 		/// Verification Key creation error
 		VerificationKeyCreationError,
+		/// The batch contains no proofs.
+		EmptyBatch,
+		/// The batch contains more proofs than allowed.
+		TooLargeBatch,
+		/// The nullifier index points past the public inputs of the circuit.
+		InvalidNullifierIndex,
+		/// The nullifier was already used by a successfully verified proof.
+		NullifierAlreadyUsed,
+		/// A point coordinate is not a canonical base field element, or the point is not encoded
+		/// the canonical way.
+		NonCanonicalCoordinate,
+		/// A point does not lie on the curve.
+		PointNotOnCurve,
+		/// A point lies on the curve, but not in its prime order subgroup.
+		PointNotInSubgroup,
+		/// The proof or the verification key contains the point at infinity where it is not
+		/// allowed.
+		PointAtInfinity,
+		/// The circuit does not accept proofs submitted unsigned.
+		UnsignedSubmissionNotAllowed,
+		/// The circuit was registered without a nullifier.
+		NoNullifier,
+		/// The proof submitted unsigned or with a call is not valid.
+		InvalidProof,
+		/// The last public input of the proof submitted with a call is not the hash of the call.
+		CallHashMismatch,
 	}
 
-	/// Storing a public input.
+	/// Storing verification keys of registered circuits.
+	#[pallet::storage]
+	pub type VerificationKeyStorage<T: Config> =
+		StorageMap<_, Blake2_128Concat, CircuitId, VerificationKeyDef<T>, ValueQuery>;
+
+	/// Storing Groth16 verification keys of registered circuits with already validated points and
+	/// `e(alpha, beta)`, so they are not parsed, checked and paired again on every verification.
 	#[pallet::storage]
-	pub type PublicInputStorage<T: Config> = StorageValue<_, PublicInputsDef<T>, ValueQuery>;
+	pub type DecodedVerificationKeyStorage<T: Config> =
+		StorageMap<_, Blake2_128Concat, CircuitId, DecodedVerificationKey, OptionQuery>;
 
-	/// Storing a proof.
+	/// Storing the index of the public input which is the nullifier of proofs of the circuit, only
+	/// for circuits registered with one.
 	#[pallet::storage]
-	pub type ProofStorage<T: Config> = StorageValue<_, ProofDef<T>, ValueQuery>;
+	pub type NullifierIndexStorage<T: Config> =
+		StorageMap<_, Blake2_128Concat, CircuitId, u8, OptionQuery>;
 
-	/// Storing a verification key.
+	/// Storing how the transaction pool treats unsigned proofs, only for circuits which accept
+	/// them.
 	#[pallet::storage]
-	pub type VerificationKeyStorage<T: Config> = StorageValue<_, VerificationKeyDef<T>, ValueQuery>;
+	pub type UnsignedSubmissionStorage<T: Config> =
+		StorageMap<_, Blake2_128Concat, CircuitId, UnsignedSubmission, OptionQuery>;
+
+	/// Storing nullifiers of successfully verified proofs, so a proof or a statement with the same
+	/// nullifier can not be verified again.
+	#[pallet::storage]
+	pub type NullifierStorage<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		CircuitId,
+		Blake2_128Concat,
+		PublicInput,
+		(),
+		OptionQuery,
+	>;
+
+	/// Storing the outcome of every proof submitted through `verify` or `verify_batch`, under the
+	/// hash of the proof, for `VerificationRecordLifetime` blocks. A proof submitted again
+	/// overwrites its record.
+	#[pallet::storage]
+	pub type VerificationRecords<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		CircuitId,
+		Blake2_128Concat,
+		ProofHash,
+		VerificationRecord<T::AccountId, T::BlockNumber>,
+		OptionQuery,
+	>;
+
+	/// Storing the keys of verification records by the block they were recorded in, so they can
+	/// be pruned once they expire.
+	#[pallet::storage]
+	pub type VerificationRecordsByBlock<T: Config> =
+		StorageMap<_, Twox64Concat, T::BlockNumber, Vec<(CircuitId, ProofHash)>, ValueQuery>;
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		/// Prunes the verification records which expire in this block.
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let lifetime = T::VerificationRecordLifetime::get();
+			if lifetime.is_zero() || now < lifetime {
+				return Weight::zero()
+			}
+			let recorded = now - lifetime;
+			let keys = VerificationRecordsByBlock::<T>::take(recorded);
+			for (circuit_id, proof_hash) in &keys {
+				// Skip records overwritten by the same proof submitted again later
+				if VerificationRecords::<T>::get(circuit_id, proof_hash)
+					.map_or(false, |record| record.block == recorded)
+				{
+					VerificationRecords::<T>::remove(circuit_id, proof_hash);
+				}
+			}
+			T::DbWeight::get().reads_writes(1 + keys.len() as u64, 1 + keys.len() as u64)
+		}
+
+		fn on_runtime_upgrade() -> Weight {
+			migrations::v2::migrate::<T>().saturating_add(migrations::v3::migrate::<T>())
+		}
+	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
-		/// Store a verification key.
-		#[pallet::weight(<T as Config>::WeightInfo::setup_verification_benchmark(vec_vk.len()))]
-		pub fn setup_verification(
-			_origin: OriginFor<T>,
-			pub_input: Vec<u8>,
+		/// Register a circuit: store its verification key under `circuit_id`.
+		///
+		/// With `nullifier_index` set, the public input at that index is the nullifier: it is
+		/// recorded when a proof is verified successfully and proofs reusing it are rejected.
+		#[pallet::weight(<T as Config>::WeightInfo::register_circuit(max_ic_len::<T>(&vec_vk)))]
+		pub fn register_circuit(
+			origin: OriginFor<T>,
+			circuit_id: CircuitId,
 			vec_vk: Vec<u8>,
+			nullifier_index: Option<u8>,
+		) -> DispatchResultWithPostInfo {
+			T::SetupOrigin::ensure_origin(origin)?;
+			ensure!(
+				!VerificationKeyStorage::<T>::contains_key(circuit_id),
+				Error::<T>::CircuitAlreadyRegistered
+			);
+			let vk_hash = blake2_256(&vec_vk);
+			let ic_len = store_verification_key::<T>(circuit_id, vec_vk, nullifier_index)?;
+			Self::deposit_event(Event::<T>::VerificationSetupCompleted { circuit_id, vk_hash });
+			Ok(Some(T::WeightInfo::register_circuit(ic_len)).into())
+		}
+
+		/// Verify a proof of the statement given by `pub_input` against the circuit registered
+		/// under `circuit_id`. `OnProofVerified` is called when the proof is valid.
+		///
+		/// The weight of the heaviest protocol, curve and format is charged upfront, the difference
+		/// is refunded once the proof is parsed.
+		#[pallet::weight(max_verify_weight::<T>(max_public_inputs_len::<T>(&pub_input)))]
+		pub fn verify(
+			origin: OriginFor<T>,
+			circuit_id: CircuitId,
+			vec_proof: Vec<u8>,
+			pub_input: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+			let proof_hash = blake2_256(&vec_proof);
+			// Inputs longer than their limit are rejected before they are parsed
+			let parse_failure = T::WeightInfo::verify_parse_failure(
+				vec_proof.len().min(T::MaxProofLength::get() as usize) as u32,
+				pub_input.len().min(T::MaxPublicInputsLength::get() as usize) as u32,
+			);
+			let proof = parse_proof::<T>(vec_proof).map_err(|e| e.with_weight(parse_failure))?.1;
+			let inputs =
+				parse_public_inputs::<T>(pub_input).map_err(|e| e.with_weight(parse_failure))?;
+			let actual_weight = verify_weight::<T>(&proof, inputs.len() as u32);
+			// Any error of the verification is raised before the pairing check
+			let (verified, nullifier) = verify_proof::<T>(circuit_id, proof, inputs.clone())
+				.map_err(|e| e.with_weight(T::WeightInfo::verify_decode_failure()))?;
+			let inputs_hash = hash_inputs(&inputs);
+			record_verification::<T>(circuit_id, proof_hash, &sender, inputs_hash, verified);
+			Self::deposit_event(Event::<T>::VerificationProofSet {
+				circuit_id,
+				who: sender.clone(),
+				proof_hash,
+			});
+
+			if verified {
+				T::OnProofVerified::on_verified(&Some(sender.clone()), circuit_id, &inputs)?;
+				if let Some(nullifier) = nullifier {
+					NullifierStorage::<T>::insert(circuit_id, nullifier, ());
+				}
+				Self::deposit_event(Event::<T>::VerificationSuccess {
+					circuit_id,
+					who: sender,
+					proof_hash,
+					inputs_hash,
+				});
+			} else {
+				Self::deposit_event(Event::<T>::VerificationFailed {
+					circuit_id,
+					who: sender,
+					proof_hash,
+					inputs_hash,
+				});
+			}
+			Ok(Some(actual_weight).into())
+		}
+
+		/// Verify a batch of Groth16 proofs, each with its own public inputs, against the circuit
+		/// registered under `circuit_id` with a single pairing check. The emitted
+		/// `BatchVerificationCompleted` tells for every proof, in order, whether it is valid.
+		///
+		/// For circuits with a nullifier, the whole batch is rejected if any of the nullifiers was
+		/// already used or appears in the batch more than once. `OnProofVerified` is called for
+		/// every valid proof.
+		///
+		/// A batch with an invalid proof is charged for verifying all of its proofs one by one, a
+		/// batch whose combined check holds gets the difference refunded.
+		#[pallet::weight(<T as Config>::WeightInfo::verify_batch_invalid(
+			proofs.len() as u32,
+			proofs.iter().map(|(_, inputs)| max_public_inputs_len::<T>(inputs)).max().unwrap_or(0),
+		))]
+		pub fn verify_batch(
+			origin: OriginFor<T>,
+			circuit_id: CircuitId,
+			proofs: Vec<(Vec<u8>, Vec<u8>)>,
+		) -> DispatchResultWithPostInfo {
+			let sender = ensure_signed(origin)?;
+			ensure!(!proofs.is_empty(), Error::<T>::EmptyBatch);
+			ensure!(proofs.len() as u32 <= T::MaxBatchSize::get(), Error::<T>::TooLargeBatch);
+			let vk = get_decoded_verification_key::<T>(circuit_id)
+				.map_err(|e| e.with_weight(T::WeightInfo::verify_decode_failure()))?;
+			let mut batch = Vec::with_capacity(proofs.len());
+			let mut statements = Vec::with_capacity(proofs.len());
+			for (vec_proof, pub_input) in proofs {
+				// Only the proofs up to the rejected one were processed
+				let failure =
+					T::WeightInfo::verify_decode_failure().saturating_mul(batch.len() as u64 + 1);
+				let proof_hash = blake2_256(&vec_proof);
+				let proof = parse_proof::<T>(vec_proof).map_err(|e| e.with_weight(failure))?.1;
+				ensure!(
+					proof.protocol() == SupportedProtocol::Groth16,
+					Error::<T>::ProtocolMismatch.with_weight(failure)
+				);
+				ensure!(
+					SupportedCurve::from_name(proof.curve()) == Some(vk.curve()),
+					Error::<T>::CurveMismatch.with_weight(failure)
+				);
+				let inputs =
+					parse_public_inputs::<T>(pub_input).map_err(|e| e.with_weight(failure))?;
+				ensure!(
+					vk.public_inputs_len() == inputs.len(),
+					Error::<T>::PublicInputsMismatch.with_weight(failure)
+				);
+				let nullifier =
+					get_nullifier::<T>(circuit_id, &inputs).map_err(|e| e.with_weight(failure))?;
+				if let Some(nullifier) = nullifier {
+					ensure!(
+						!NullifierStorage::<T>::contains_key(circuit_id, nullifier) &&
+							!statements.iter().any(|(_, used, _)| *used == Some(nullifier)),
+						Error::<T>::NullifierAlreadyUsed.with_weight(failure)
+					);
+				}
+				statements.push((proof_hash, nullifier, inputs.clone()));
+				batch.push((proof, inputs));
+			}
+			let decode_failure =
+				T::WeightInfo::verify_decode_failure().saturating_mul(batch.len() as u64);
+			let results = match vk.curve() {
+				SupportedCurve::Bls12381 => verify_groth16_batch::<T, Bls12_381>(&vk, batch),
+				SupportedCurve::Bn254 => verify_groth16_batch::<T, Bn254>(&vk, batch),
+			}
+			.map_err(|e| e.with_weight(decode_failure))?;
+			// The proofs are verified one by one only if the batch check fails, which is when any
+			// of them is invalid
+			let (b, n_public) = (results.len() as u32, vk.public_inputs_len() as u32);
+			let actual_weight = if results.iter().all(|verified| *verified) {
+				T::WeightInfo::verify_batch(b, n_public)
+			} else {
+				T::WeightInfo::verify_batch_invalid(b, n_public)
+			};
+			let mut proofs = Vec::with_capacity(statements.len());
+			for (statement, verified) in statements.into_iter().zip(&results) {
+				let (proof_hash, nullifier, inputs) = statement;
+				let inputs_hash = hash_inputs(&inputs);
+				record_verification::<T>(circuit_id, proof_hash, &sender, inputs_hash, *verified);
+				proofs.push((proof_hash, inputs_hash));
+				if !verified {
+					continue
+				}
+				T::OnProofVerified::on_verified(&Some(sender.clone()), circuit_id, &inputs)?;
+				if let Some(nullifier) = nullifier {
+					NullifierStorage::<T>::insert(circuit_id, nullifier, ());
+				}
+			}
+
+			Self::deposit_event(Event::<T>::BatchVerificationCompleted {
+				circuit_id,
+				who: sender,
+				proofs,
+				results,
+			});
+			Ok(Some(actual_weight).into())
+		}
+
+		/// Allow proofs of the circuit registered under `circuit_id` to be submitted unsigned,
+		/// treated by the transaction pool as told by `submission`, or disallow it with `None`.
+		/// Only circuits with a nullifier can accept them, so a proof can not be submitted again.
+		#[pallet::weight(<T as Config>::WeightInfo::set_unsigned_submission())]
+		pub fn set_unsigned_submission(
+			origin: OriginFor<T>,
+			circuit_id: CircuitId,
+			submission: Option<UnsignedSubmission>,
 		) -> DispatchResult {
-			let inputs = store_public_inputs::<T>(pub_input)?;
-			let vk = store_verification_key::<T>(vec_vk)?;
-			ensure!(vk.public_inputs_len == inputs.len() as u8, Error::<T>::PublicInputsMismatch);
-			Self::deposit_event(Event::<T>::VerificationSetupCompleted);
+			T::SetupOrigin::ensure_origin(origin)?;
+			ensure!(
+				VerificationKeyStorage::<T>::contains_key(circuit_id),
+				Error::<T>::VerificationKeyIsNotSet
+			);
+			if submission.is_some() {
+				ensure!(
+					NullifierIndexStorage::<T>::contains_key(circuit_id),
+					Error::<T>::NoNullifier
+				);
+			}
+			UnsignedSubmissionStorage::<T>::set(circuit_id, submission);
+			Self::deposit_event(Event::<T>::UnsignedSubmissionSet { circuit_id, submission });
 			Ok(())
 		}
 
-		/// Verify a proof.
-		#[pallet::weight(<T as Config>::WeightInfo::verify_benchmark(vec_proof.len()))]
-		pub fn verify(origin: OriginFor<T>, vec_proof: Vec<u8>) -> DispatchResult {
-			let proof = store_proof::<T>(vec_proof)?;
-			let vk = get_verification_key::<T>()?;
-			let inputs = get_public_inputs::<T>()?;
+		/// Verify a proof submitted in an unsigned transaction, so users without an account or
+		/// balance can prove statements anonymously and for free. Only valid proofs with an unused
+		/// nullifier, of circuits which accept unsigned proofs, get into the transaction pool.
+		/// `OnProofVerified` is called without an account.
+		#[pallet::weight((
+			max_verify_weight::<T>(max_public_inputs_len::<T>(&pub_input)),
+			DispatchClass::Normal,
+			Pays::No
+		))]
+		pub fn submit_proof_unsigned(
+			origin: OriginFor<T>,
+			circuit_id: CircuitId,
+			vec_proof: Vec<u8>,
+			pub_input: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
+			ensure_none(origin)?;
+			let (proof_hash, inputs, nullifier, weight) =
+				verify_unsigned_proof::<T>(circuit_id, vec_proof, pub_input)?;
+			T::OnProofVerified::on_verified(&None, circuit_id, &inputs)?;
+			NullifierStorage::<T>::insert(circuit_id, nullifier, ());
+			Self::deposit_event(Event::<T>::UnsignedProofVerified {
+				circuit_id,
+				proof_hash,
+				inputs_hash: hash_inputs(&inputs),
+			});
+			Ok(Some(weight).into())
+		}
+
+		/// Verify a proof of a circuit with a nullifier and dispatch `call` with the `ZkProven`
+		/// origin of the circuit and the nullifier, so calls of other pallets can be gated on a
+		/// proven statement instead of an account, with `EnsureZkProven`. The last public input has
+		/// to be `call_hash_input` of `call`, so the proof can not be submitted again with another
+		/// call, e.g. by someone who saw it in the transaction pool. The nullifier is used only if
+		/// the call succeeds, and `OnProofVerified` is not called - the call acts on the proof
+		/// instead.
+		#[pallet::weight({
+			let dispatch_info = call.get_dispatch_info();
+			(
+				max_verify_weight::<T>(max_public_inputs_len::<T>(&pub_input))
+					.saturating_add(dispatch_info.weight),
+				dispatch_info.class,
+			)
+		})]
+		pub fn dispatch_with_proof(
+			origin: OriginFor<T>,
+			circuit_id: CircuitId,
+			vec_proof: Vec<u8>,
+			pub_input: Vec<u8>,
+			call: Box<<T as Config>::RuntimeCall>,
+		) -> DispatchResultWithPostInfo {
 			let sender = ensure_signed(origin)?;
-			Self::deposit_event(Event::<T>::VerificationProofSet);
-
-			match verify(vk, proof, prepare_public_inputs(inputs)) {
-				Ok(true) => {
-					Self::deposit_event(Event::<T>::VerificationSuccess { who: sender });
-					Ok(())
-				},
-				Ok(false) => {
-					Self::deposit_event(Event::<T>::VerificationFailed);
-					Ok(())
-				},
-				Err(_) => Err(Error::<T>::ProofVerificationError.into()),
+			let (proof_hash, inputs, nullifier, verify_weight) =
+				verify_nullified_proof::<T>(circuit_id, vec_proof, pub_input, Some(&call))?;
+			// Used before the dispatch, so the call can not use it again
+			NullifierStorage::<T>::insert(circuit_id, nullifier, ());
+			let dispatch_info = call.get_dispatch_info();
+			let result = call.dispatch(RawOrigin::ZkProven { circuit_id, nullifier }.into());
+			let weight =
+				verify_weight.saturating_add(extract_actual_weight(&result, &dispatch_info));
+			result.map_err(|e| e.error.with_weight(weight))?;
+			Self::deposit_event(Event::<T>::ProofDispatched {
+				circuit_id,
+				who: sender,
+				proof_hash,
+				inputs_hash: hash_inputs(&inputs),
+			});
+			Ok(Some(weight).into())
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		/// Proofs submitted unsigned are verified completely before they get into the pool, and
+		/// provide their nullifier, so only the first proof using it is kept.
+		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			let (circuit_id, vec_proof, pub_input) = match call {
+				Call::submit_proof_unsigned { circuit_id, vec_proof, pub_input } =>
+					(*circuit_id, vec_proof, pub_input),
+				_ => return InvalidTransaction::Call.into(),
+			};
+			let submission = UnsignedSubmissionStorage::<T>::get(circuit_id).ok_or_else(|| {
+				invalid_transaction(Error::<T>::UnsignedSubmissionNotAllowed.into())
+			})?;
+			let (_, _, nullifier, _) =
+				verify_unsigned_proof::<T>(circuit_id, vec_proof.clone(), pub_input.clone())
+					.map_err(invalid_transaction)?;
+			ValidTransaction::with_tag_prefix("ZkSnarks")
+				.priority(submission.priority)
+				.longevity(submission.longevity)
+				.and_provides((circuit_id, nullifier))
+				.propagate(true)
+				.build()
+		}
+
+		/// Runs only the checks done before the pairing check: the circuit still accepts unsigned
+		/// proofs, the nullifier is unused and the proof and public inputs decode. The proof is
+		/// verified once, by `submit_proof_unsigned`, which is what its weight covers.
+		fn pre_dispatch(call: &Self::Call) -> Result<(), TransactionValidityError> {
+			match call {
+				Call::submit_proof_unsigned { circuit_id, vec_proof, pub_input } =>
+					check_unsigned_proof::<T>(*circuit_id, vec_proof.clone(), pub_input.clone())
+						.map_err(invalid_transaction),
+				_ => Err(InvalidTransaction::Call.into()),
 			}
 		}
 	}
 
-	fn get_public_inputs<T: Config>() -> Result<Vec<u64>, sp_runtime::DispatchError> {
-		let public_inputs = PublicInputStorage::<T>::get();
-		let deserialized_public_inputs = deserialize_public_inputs(public_inputs.as_slice())
-			.map_err(|_| Error::<T>::MalformedPublicInputs)?;
-		Ok(deserialized_public_inputs)
+	impl<T: Config> Pallet<T> {
+		/// Verify a proof the way `verify` does, but without storing the proof or its nullifier.
+		/// Used by the runtime API to check proofs without submitting an extrinsic.
+		pub fn dry_run_verify(
+			circuit_id: CircuitId,
+			vec_proof: Vec<u8>,
+			pub_input: Vec<u8>,
+		) -> Result<bool, sp_runtime::DispatchError> {
+			let proof = parse_proof::<T>(vec_proof)?.1;
+			let inputs = parse_public_inputs::<T>(pub_input)?;
+			Ok(verify_proof::<T>(circuit_id, proof, inputs)?.0)
+		}
+
+		/// Checks the proof the way `verify` does, but stops before the pairing check: the lengths,
+		/// the parsing, the nullifier, the curve, protocol and number of public inputs against the
+		/// registered key, and the decoding of the points. Used to drop malformed proofs from the
+		/// transaction pool.
+		pub fn check_proof(
+			circuit_id: CircuitId,
+			vec_proof: Vec<u8>,
+			pub_input: Vec<u8>,
+		) -> DispatchResult {
+			let proof = parse_proof::<T>(vec_proof)?.1;
+			let inputs = parse_public_inputs::<T>(pub_input)?;
+			check_statement::<T>(circuit_id, proof, inputs)
+		}
+
+		/// Checks the size of the batch and every proof of it with `check_proof`.
+		pub fn check_batch(circuit_id: CircuitId, proofs: &[(Vec<u8>, Vec<u8>)]) -> DispatchResult {
+			ensure!(!proofs.is_empty(), Error::<T>::EmptyBatch);
+			ensure!(proofs.len() as u32 <= T::MaxBatchSize::get(), Error::<T>::TooLargeBatch);
+			for (vec_proof, pub_input) in proofs {
+				Self::check_proof(circuit_id, vec_proof.clone(), pub_input.clone())?;
+			}
+			Ok(())
+		}
+
+		/// The public input binding a proof to `call` in `dispatch_with_proof`: `blake2_256` of the
+		/// SCALE encoded call, read as a big-endian integer and reduced modulo the scalar field
+		/// modulus of `curve`.
+		pub fn call_hash_input(
+			curve: SupportedCurve,
+			call: &<T as Config>::RuntimeCall,
+		) -> PublicInput {
+			let hash = blake2_256(&call.encode());
+			match curve {
+				SupportedCurve::Bls12381 =>
+					Bls12_381::scalar_to_bytes(&Bls12_381::scalar_from_hash(hash)),
+				SupportedCurve::Bn254 => Bn254::scalar_to_bytes(&Bn254::scalar_from_hash(hash)),
+			}
+		}
+
+		/// The verification key registered under `circuit_id`, as it was submitted.
+		pub fn verification_key(circuit_id: CircuitId) -> Option<Vec<u8>> {
+			VerificationKeyStorage::<T>::try_get(circuit_id).ok().map(Into::into)
+		}
+	}
+
+	impl<T: Config> ZkVerifier for Pallet<T> {
+		fn verify(
+			circuit_id: CircuitId,
+			proof: Vec<u8>,
+			public_inputs: Vec<u8>,
+		) -> Result<bool, sp_runtime::DispatchError> {
+			Self::dry_run_verify(circuit_id, proof, public_inputs)
+		}
+
+		fn weight(public_inputs_len: u32) -> Weight {
+			max_verify_weight::<T>(public_inputs_len)
+		}
+	}
+
+	/// Verifies the proof against the circuit, checking first that its nullifier, if the circuit
+	/// has one, was not used yet. Returns the nullifier next to the result.
+	fn verify_proof<T: Config>(
+		circuit_id: CircuitId,
+		proof: ProtocolProof,
+		inputs: Vec<PublicInput>,
+	) -> Result<(bool, Option<PublicInput>), sp_runtime::DispatchError> {
+		let nullifier = get_unused_nullifier::<T>(circuit_id, &inputs)?;
+		let verified = match proof.protocol() {
+			SupportedProtocol::Groth16 => {
+				let vk = get_decoded_verification_key::<T>(circuit_id)?;
+				verify_with_decoded_key::<T>(vk, proof, inputs)?
+			},
+			_ => verify_with_key::<T>(circuit_id, proof, inputs)?,
+		};
+		Ok((verified, nullifier))
 	}
 
-	fn store_public_inputs<T: Config>(
+	/// Verifies a proof submitted unsigned, which has to be a valid proof of a circuit accepting
+	/// unsigned proofs, with an unused nullifier. Returns the hash of the proof, the public inputs,
+	/// the nullifier and the weight of the verification.
+	fn verify_unsigned_proof<T: Config>(
+		circuit_id: CircuitId,
+		vec_proof: Vec<u8>,
 		pub_input: Vec<u8>,
-	) -> Result<Vec<u64>, sp_runtime::DispatchError> {
+	) -> Result<(ProofHash, Vec<PublicInput>, PublicInput, Weight), sp_runtime::DispatchError> {
+		ensure!(
+			UnsignedSubmissionStorage::<T>::contains_key(circuit_id),
+			Error::<T>::UnsignedSubmissionNotAllowed
+		);
+		verify_nullified_proof::<T>(circuit_id, vec_proof, pub_input, None)
+	}
+
+	/// Everything `verify_unsigned_proof` checks before the pairing check.
+	fn check_unsigned_proof<T: Config>(
+		circuit_id: CircuitId,
+		vec_proof: Vec<u8>,
+		pub_input: Vec<u8>,
+	) -> DispatchResult {
+		ensure!(
+			UnsignedSubmissionStorage::<T>::contains_key(circuit_id),
+			Error::<T>::UnsignedSubmissionNotAllowed
+		);
+		ensure!(NullifierIndexStorage::<T>::contains_key(circuit_id), Error::<T>::NoNullifier);
+		Pallet::<T>::check_proof(circuit_id, vec_proof, pub_input)
+	}
+
+	/// Verifies a proof which has to be valid, of a circuit with a nullifier, and use an unused
+	/// nullifier. With `call` given, the last public input has to be its `call_hash_input`.
+	/// Returns the hash of the proof, the public inputs, the nullifier and the weight of the
+	/// verification.
+	fn verify_nullified_proof<T: Config>(
+		circuit_id: CircuitId,
+		vec_proof: Vec<u8>,
+		pub_input: Vec<u8>,
+		call: Option<&<T as Config>::RuntimeCall>,
+	) -> Result<(ProofHash, Vec<PublicInput>, PublicInput, Weight), sp_runtime::DispatchError> {
+		ensure!(NullifierIndexStorage::<T>::contains_key(circuit_id), Error::<T>::NoNullifier);
+		let proof_hash = blake2_256(&vec_proof);
+		let proof = parse_proof::<T>(vec_proof)?.1;
+		let inputs = parse_public_inputs::<T>(pub_input)?;
+		if let Some(call) = call {
+			let curve =
+				SupportedCurve::from_name(proof.curve()).ok_or(Error::<T>::NotSupportedCurve)?;
+			ensure!(
+				inputs.last() == Some(&Pallet::<T>::call_hash_input(curve, call)),
+				Error::<T>::CallHashMismatch
+			);
+		}
+		let weight = verify_weight::<T>(&proof, inputs.len() as u32);
+		let (verified, nullifier) = verify_proof::<T>(circuit_id, proof, inputs.clone())?;
+		ensure!(verified, Error::<T>::InvalidProof);
+		let nullifier = nullifier.ok_or(Error::<T>::NoNullifier)?;
+		Ok((proof_hash, inputs, nullifier, weight))
+	}
+
+	/// Everything `verify_proof` checks before the pairing check.
+	fn check_statement<T: Config>(
+		circuit_id: CircuitId,
+		proof: ProtocolProof,
+		inputs: Vec<PublicInput>,
+	) -> DispatchResult {
+		get_unused_nullifier::<T>(circuit_id, &inputs)?;
+		let curve = match proof.protocol() {
+			SupportedProtocol::Groth16 => {
+				let vk = get_decoded_verification_key::<T>(circuit_id)?;
+				ensure!(vk.public_inputs_len() == inputs.len(), Error::<T>::PublicInputsMismatch);
+				vk.curve()
+			},
+			protocol => {
+				let vk = get_verification_key::<T>(circuit_id)?;
+				ensure!(
+					vk.public_inputs_len() == inputs.len() as u8,
+					Error::<T>::PublicInputsMismatch
+				);
+				ensure!(vk.protocol() == protocol, Error::<T>::ProtocolMismatch);
+				SupportedCurve::from_name(vk.curve()).ok_or(Error::<T>::NotSupportedCurve)?
+			},
+		};
+		ensure!(SupportedCurve::from_name(proof.curve()) == Some(curve), Error::<T>::CurveMismatch);
+		match curve {
+			SupportedCurve::Bls12381 => decode_statement::<T, Bls12_381>(proof, inputs),
+			SupportedCurve::Bn254 => decode_statement::<T, Bn254>(proof, inputs),
+		}
+	}
+
+	/// Decodes the points of the proof and the public inputs, without verifying the proof.
+	fn decode_statement<T: Config, C: Curve>(
+		proof: ProtocolProof,
+		inputs: Vec<PublicInput>,
+	) -> DispatchResult {
+		match proof {
+			ProtocolProof::Plonk(proof) =>
+				prepare_plonk_proof::<C>(proof).map(|_| ()).map_err(proof_creation_error::<T>)?,
+			ProtocolProof::Fflonk(proof) => prepare_fflonk_proof::<C>(proof)
+				.map(|_| ())
+				.map_err(proof_creation_error::<T>)?,
+			proof => prepare_groth16_proof::<T, C>(proof).map(|_| ())?,
+		}
+		prepare_public_inputs::<C>(inputs).map_err(|_| Error::<T>::MalformedPublicInputs)?;
+		Ok(())
+	}
+
+	fn verify_with_decoded_key<T: Config>(
+		vk: DecodedVerificationKey,
+		proof: ProtocolProof,
+		inputs: Vec<PublicInput>,
+	) -> Result<bool, sp_runtime::DispatchError> {
+		ensure!(vk.public_inputs_len() == inputs.len(), Error::<T>::PublicInputsMismatch);
+		ensure!(
+			SupportedCurve::from_name(proof.curve()) == Some(vk.curve()),
+			Error::<T>::CurveMismatch
+		);
+		ensure!(proof.protocol() == SupportedProtocol::Groth16, Error::<T>::ProtocolMismatch);
+		match vk.curve() {
+			SupportedCurve::Bls12381 => verify_groth16::<T, Bls12_381>(&vk, proof, inputs),
+			SupportedCurve::Bn254 => verify_groth16::<T, Bn254>(&vk, proof, inputs),
+		}
+	}
+
+	fn verify_with_key<T: Config>(
+		circuit_id: CircuitId,
+		proof: ProtocolProof,
+		inputs: Vec<PublicInput>,
+	) -> Result<bool, sp_runtime::DispatchError> {
+		let vk = get_verification_key::<T>(circuit_id)?;
+		ensure!(vk.public_inputs_len() == inputs.len() as u8, Error::<T>::PublicInputsMismatch);
+		ensure!(vk.curve() == proof.curve(), Error::<T>::CurveMismatch);
+		let curve = SupportedCurve::from_name(vk.curve()).ok_or(Error::<T>::NotSupportedCurve)?;
+		match (vk, proof) {
+			(ProtocolVKey::Plonk(vk), ProtocolProof::Plonk(proof)) => match curve {
+				SupportedCurve::Bls12381 => verify_plonk_proof::<T, Bls12_381>(vk, proof, inputs),
+				SupportedCurve::Bn254 => verify_plonk_proof::<T, Bn254>(vk, proof, inputs),
+			},
+			(ProtocolVKey::Fflonk(vk), ProtocolProof::Fflonk(proof)) => match curve {
+				SupportedCurve::Bls12381 => verify_fflonk_proof::<T, Bls12_381>(vk, proof, inputs),
+				SupportedCurve::Bn254 => verify_fflonk_proof::<T, Bn254>(vk, proof, inputs),
+			},
+			_ => Err(Error::<T>::ProtocolMismatch.into()),
+		}
+	}
+
+	fn verify_groth16<T: Config, C: Curve>(
+		vk: &DecodedVerificationKey,
+		proof: ProtocolProof,
+		inputs: Vec<PublicInput>,
+	) -> Result<bool, sp_runtime::DispatchError> {
+		let vk = vk.restore::<C>().map_err(|_| Error::<T>::VerificationKeyCreationError)?;
+		let proof = prepare_groth16_proof::<T, C>(proof)?;
+		let inputs =
+			prepare_public_inputs::<C>(inputs).map_err(|_| Error::<T>::MalformedPublicInputs)?;
+
+		verify(vk, proof, inputs).map_err(|_| Error::<T>::ProofVerificationError.into())
+	}
+
+	fn verify_groth16_batch<T: Config, C: Curve>(
+		vk: &DecodedVerificationKey,
+		batch: Vec<(ProtocolProof, Vec<PublicInput>)>,
+	) -> Result<Vec<bool>, sp_runtime::DispatchError> {
+		let vk = vk.restore::<C>().map_err(|_| Error::<T>::VerificationKeyCreationError)?;
+		let mut proofs = Vec::with_capacity(batch.len());
+		for (proof, inputs) in batch {
+			let proof = prepare_groth16_proof::<T, C>(proof)?;
+			let inputs = prepare_public_inputs::<C>(inputs)
+				.map_err(|_| Error::<T>::MalformedPublicInputs)?;
+			proofs.push((proof, inputs));
+		}
+
+		verify_batch(vk, proofs).map_err(|_| Error::<T>::ProofVerificationError.into())
+	}
+
+	/// Accepts Groth16 proofs both in snarkjs json and in the compact format
+	fn prepare_groth16_proof<T: Config, C: Curve>(
+		proof: ProtocolProof,
+	) -> Result<GProof<C>, sp_runtime::DispatchError> {
+		match proof {
+			ProtocolProof::Groth16(proof) => prepare_proof::<C>(proof),
+			ProtocolProof::CompactGroth16(proof) => proof.decompress::<C>(),
+			_ => return Err(Error::<T>::ProtocolMismatch.into()),
+		}
+		.map_err(|error| proof_creation_error::<T>(error).into())
+	}
+
+	fn proof_creation_error<T: Config>(error: GProofCreationError) -> Error<T> {
+		match error {
+			GProofCreationError::PointCreationError(error) => point_error::<T>(error),
+			GProofCreationError::ScalarCreationError | GProofCreationError::NotSupportedCurve =>
+				Error::<T>::ProofCreationError,
+		}
+	}
+
+	fn verification_key_creation_error<T: Config>(error: VerificationKeyCreationError) -> Error<T> {
+		match error {
+			VerificationKeyCreationError::PointCreationError(error) => point_error::<T>(error),
+			VerificationKeyCreationError::ScalarCreationError |
+			VerificationKeyCreationError::NotSupportedCurve => Error::<T>::VerificationKeyCreationError,
+		}
+	}
+
+	fn point_error<T: Config>(error: PointError) -> Error<T> {
+		match error {
+			PointError::NonCanonicalCoordinate => Error::<T>::NonCanonicalCoordinate,
+			PointError::NotOnCurve => Error::<T>::PointNotOnCurve,
+			PointError::NotInSubgroup => Error::<T>::PointNotInSubgroup,
+			PointError::PointAtInfinity => Error::<T>::PointAtInfinity,
+		}
+	}
+
+	fn verify_plonk_proof<T: Config, C: Curve>(
+		vk: PlonkVKey,
+		proof: PlonkProof,
+		inputs: Vec<PublicInput>,
+	) -> Result<bool, sp_runtime::DispatchError> {
+		let vk = prepare_plonk_verification_key::<C>(vk)
+			.map_err(verification_key_creation_error::<T>)?;
+		let proof = prepare_plonk_proof::<C>(proof).map_err(proof_creation_error::<T>)?;
+		let inputs =
+			prepare_public_inputs::<C>(inputs).map_err(|_| Error::<T>::MalformedPublicInputs)?;
+
+		verify_plonk(vk, proof, inputs).map_err(|_| Error::<T>::ProofVerificationError.into())
+	}
+
+	fn verify_fflonk_proof<T: Config, C: Curve>(
+		vk: FflonkVKey,
+		proof: FflonkProof,
+		inputs: Vec<PublicInput>,
+	) -> Result<bool, sp_runtime::DispatchError> {
+		let vk = prepare_fflonk_verification_key::<C>(vk)
+			.map_err(verification_key_creation_error::<T>)?;
+		let proof = prepare_fflonk_proof::<C>(proof).map_err(proof_creation_error::<T>)?;
+		let inputs =
+			prepare_public_inputs::<C>(inputs).map_err(|_| Error::<T>::MalformedPublicInputs)?;
+
+		verify_fflonk(vk, proof, inputs).map_err(|_| Error::<T>::ProofVerificationError.into())
+	}
+
+	fn parse_public_inputs<T: Config>(
+		pub_input: Vec<u8>,
+	) -> Result<Vec<PublicInput>, sp_runtime::DispatchError> {
 		let public_inputs: PublicInputsDef<T> =
 			pub_input.try_into().map_err(|_| Error::<T>::TooLongPublicInputs)?;
 		let deserialized_public_inputs = deserialize_public_inputs(public_inputs.as_slice())
 			.map_err(|_| Error::<T>::MalformedPublicInputs)?;
-		PublicInputStorage::<T>::put(public_inputs);
 		Ok(deserialized_public_inputs)
 	}
 
-	fn get_verification_key<T: Config>() -> Result<VerificationKey, sp_runtime::DispatchError> {
-		let vk = VerificationKeyStorage::<T>::get();
+	/// The nullifier of the public inputs, failing if it was already used.
+	fn get_unused_nullifier<T: Config>(
+		circuit_id: CircuitId,
+		inputs: &[PublicInput],
+	) -> Result<Option<PublicInput>, sp_runtime::DispatchError> {
+		let nullifier = get_nullifier::<T>(circuit_id, inputs)?;
+		if let Some(nullifier) = nullifier {
+			ensure!(
+				!NullifierStorage::<T>::contains_key(circuit_id, nullifier),
+				Error::<T>::NullifierAlreadyUsed
+			);
+		}
+		Ok(nullifier)
+	}
+
+	/// `None` for circuits registered without a nullifier.
+	fn get_nullifier<T: Config>(
+		circuit_id: CircuitId,
+		inputs: &[PublicInput],
+	) -> Result<Option<PublicInput>, sp_runtime::DispatchError> {
+		match NullifierIndexStorage::<T>::get(circuit_id) {
+			Some(index) => inputs
+				.get(index as usize)
+				.copied()
+				.map(Some)
+				.ok_or_else(|| Error::<T>::PublicInputsMismatch.into()),
+			None => Ok(None),
+		}
+	}
+
+	fn get_verification_key<T: Config>(
+		circuit_id: CircuitId,
+	) -> Result<ProtocolVKey, sp_runtime::DispatchError> {
+		let vk = VerificationKeyStorage::<T>::get(circuit_id);
 
 		ensure!(!vk.is_empty(), Error::<T>::VerificationKeyIsNotSet);
-		let deserialized_vk = VKey::from_json_u8_slice(vk.as_slice())
+		let deserialized_vk = ProtocolVKey::from_u8_slice(vk.as_slice())
 			.map_err(|_| Error::<T>::MalformedVerificationKey)?;
-		let vk = prepare_verification_key(deserialized_vk)
-			.map_err(|_| Error::<T>::VerificationKeyCreationError)?;
-		Ok(vk)
+		Ok(deserialized_vk)
+	}
+
+	/// Circuits registered before the decoded keys were stored get their JSON key decoded on
+	/// the fly.
+	fn get_decoded_verification_key<T: Config>(
+		circuit_id: CircuitId,
+	) -> Result<DecodedVerificationKey, sp_runtime::DispatchError> {
+		if let Some(vk) = DecodedVerificationKeyStorage::<T>::get(circuit_id) {
+			return Ok(vk)
+		}
+		decoded_groth16_verification_key::<T>(get_verification_key::<T>(circuit_id)?)
+	}
+
+	pub(crate) fn decoded_groth16_verification_key<T: Config>(
+		vk: ProtocolVKey,
+	) -> Result<DecodedVerificationKey, sp_runtime::DispatchError> {
+		let curve = SupportedCurve::from_name(vk.curve()).ok_or(Error::<T>::NotSupportedCurve)?;
+		match curve {
+			SupportedCurve::Bls12381 => decode_groth16_verification_key::<T, Bls12_381>(vk)
+				.map(|vk| DecodedVerificationKey::new(curve, vk)),
+			SupportedCurve::Bn254 => decode_groth16_verification_key::<T, Bn254>(vk)
+				.map(|vk| DecodedVerificationKey::new(curve, vk)),
+		}
+	}
+
+	/// Accepts Groth16 verification keys both in snarkjs json and in the compact format
+	fn decode_groth16_verification_key<T: Config, C: Curve>(
+		vk: ProtocolVKey,
+	) -> Result<VerificationKey<C>, sp_runtime::DispatchError> {
+		match vk {
+			ProtocolVKey::Groth16(vk) => prepare_verification_key::<C>(vk),
+			ProtocolVKey::CompactGroth16(vk) => vk.decompress::<C>(),
+			_ => return Err(Error::<T>::ProtocolMismatch.into()),
+		}
+		.map_err(|error| verification_key_creation_error::<T>(error).into())
 	}
 
 	fn store_verification_key<T: Config>(
+		circuit_id: CircuitId,
 		vec_vk: Vec<u8>,
-	) -> Result<VKey, sp_runtime::DispatchError> {
+		nullifier_index: Option<u8>,
+	) -> Result<u32, sp_runtime::DispatchError> {
 		let vk: VerificationKeyDef<T> =
 			vec_vk.try_into().map_err(|_| Error::<T>::TooLongVerificationKey)?;
-		let deserialized_vk = VKey::from_json_u8_slice(vk.as_slice())
-			.map_err(|_| Error::<T>::MalformedVerificationKey)?;
-		ensure!(deserialized_vk.curve == SUPPORTED_CURVE.as_bytes(), Error::<T>::NotSupportedCurve);
+		let deserialized_vk = ProtocolVKey::from_u8_slice(vk.as_slice()).map_err(|e| match e {
+			VKeyDeserializationError::NotSupportedProtocol => Error::<T>::NotSupportedProtocol,
+			VKeyDeserializationError::SerdeError |
+			VKeyDeserializationError::CodecError |
+			VKeyDeserializationError::InvalidNumber(_) => Error::<T>::MalformedVerificationKey,
+		})?;
 		ensure!(
-			deserialized_vk.protocol == SUPPORTED_PROTOCOL.as_bytes(),
-			Error::<T>::NotSupportedProtocol
+			SupportedCurve::from_name(deserialized_vk.curve()).is_some(),
+			Error::<T>::NotSupportedCurve
 		);
+		match &deserialized_vk {
+			ProtocolVKey::Groth16(groth16_vk) => ensure!(
+				groth16_vk.ic.len() == groth16_vk.public_inputs_len as usize + 1,
+				Error::<T>::PublicInputsMismatch
+			),
+			// `nPublic` of snarkjs keys fits in `u8`
+			ProtocolVKey::CompactGroth16(groth16_vk) => ensure!(
+				(1..=u8::MAX as usize + 1).contains(&groth16_vk.ic_len()),
+				Error::<T>::PublicInputsMismatch
+			),
+			_ => {},
+		}
+		if let Some(index) = nullifier_index {
+			ensure!(index < deserialized_vk.public_inputs_len(), Error::<T>::InvalidNullifierIndex);
+		}
+		let ic_len = deserialized_vk.public_inputs_len() as u32 + 1;
+		if deserialized_vk.protocol() == SupportedProtocol::Groth16 {
+			let decoded = decoded_groth16_verification_key::<T>(deserialized_vk)?;
+			DecodedVerificationKeyStorage::<T>::insert(circuit_id, decoded);
+		}
+		if let Some(index) = nullifier_index {
+			NullifierIndexStorage::<T>::insert(circuit_id, index);
+		}
 
-		VerificationKeyStorage::<T>::put(vk);
-		Ok(deserialized_vk)
+		VerificationKeyStorage::<T>::insert(circuit_id, vk);
+		Ok(ic_len)
+	}
+
+	/// Upper bound of the number of `IC` points of the key, for the weight charged before it is
+	/// parsed: a point takes at least 14 bytes in json, `["0","1","0"],`, and more in the compact
+	/// format, and `nPublic` fits in `u8`.
+	fn max_ic_len<T: Config>(vec_vk: &[u8]) -> u32 {
+		let len = vec_vk.len().min(T::MaxVerificationKeyLength::get() as usize);
+		(len / 14).min(u8::MAX as usize + 1) as u32
+	}
+
+	/// Weight of `verify` for the proof, which depends on its protocol, curve and format.
+	fn verify_weight<T: Config>(proof: &ProtocolProof, n_public: u32) -> Weight {
+		let bn254 = SupportedCurve::from_name(proof.curve()) == Some(SupportedCurve::Bn254);
+		match (proof, bn254) {
+			(ProtocolProof::Groth16(_), false) => T::WeightInfo::verify(n_public),
+			(ProtocolProof::Groth16(_), true) => T::WeightInfo::verify_bn254(n_public),
+			(ProtocolProof::CompactGroth16(_), false) => T::WeightInfo::verify_compact(n_public),
+			(ProtocolProof::CompactGroth16(_), true) =>
+				T::WeightInfo::verify_compact_bn254(n_public),
+			(ProtocolProof::Plonk(_), false) => T::WeightInfo::verify_plonk(n_public),
+			(ProtocolProof::Plonk(_), true) => T::WeightInfo::verify_plonk_bn254(n_public),
+			(ProtocolProof::Fflonk(_), false) => T::WeightInfo::verify_fflonk(n_public),
+			(ProtocolProof::Fflonk(_), true) => T::WeightInfo::verify_fflonk_bn254(n_public),
+		}
+	}
+
+	/// The heaviest `verify_weight` of a proof with `n_public` public inputs, for the weight
+	/// charged before the proof is parsed.
+	fn max_verify_weight<T: Config>(n_public: u32) -> Weight {
+		[
+			T::WeightInfo::verify(n_public),
+			T::WeightInfo::verify_bn254(n_public),
+			T::WeightInfo::verify_compact(n_public),
+			T::WeightInfo::verify_compact_bn254(n_public),
+			T::WeightInfo::verify_plonk(n_public),
+			T::WeightInfo::verify_plonk_bn254(n_public),
+			T::WeightInfo::verify_fflonk(n_public),
+			T::WeightInfo::verify_fflonk_bn254(n_public),
+		]
+		.into_iter()
+		.fold(
+			Weight::zero(),
+			|max, weight| if weight.ref_time() > max.ref_time() { weight } else { max },
+		)
+	}
+
+	/// Upper bound of the number of public inputs, for the weight charged before they are parsed:
+	/// a public input takes at least 4 bytes, `"0",`, and their number has to fit in `u8`.
+	fn max_public_inputs_len<T: Config>(pub_input: &[u8]) -> u32 {
+		let len = pub_input.len().min(T::MaxPublicInputsLength::get() as usize);
+		(len / 4).min(u8::MAX as usize) as u32
 	}
 
-	fn store_proof<T: Config>(vec_proof: Vec<u8>) -> Result<GProof, sp_runtime::DispatchError> {
+	fn hash_inputs(inputs: &[PublicInput]) -> InputsHash {
+		blake2_256(&inputs.concat())
+	}
+
+	/// Records the outcome of the verification of the proof, to be pruned once it expires.
+	fn record_verification<T: Config>(
+		circuit_id: CircuitId,
+		proof_hash: ProofHash,
+		who: &T::AccountId,
+		inputs_hash: InputsHash,
+		result: bool,
+	) {
+		let block = frame_system::Pallet::<T>::block_number();
+		let record = VerificationRecord { who: who.clone(), block, inputs_hash, result };
+		VerificationRecords::<T>::insert(circuit_id, proof_hash, record);
+		if !T::VerificationRecordLifetime::get().is_zero() {
+			VerificationRecordsByBlock::<T>::append(block, (circuit_id, proof_hash));
+		}
+	}
+
+	fn parse_proof<T: Config>(
+		vec_proof: Vec<u8>,
+	) -> Result<(ProofDef<T>, ProtocolProof), sp_runtime::DispatchError> {
 		ensure!(!vec_proof.is_empty(), Error::<T>::ProofIsEmpty);
 		let proof: ProofDef<T> = vec_proof.try_into().map_err(|_| Error::<T>::TooLongProof)?;
 		let deserialized_proof =
-			Proof::from_json_u8_slice(proof.as_slice()).map_err(|_| Error::<T>::MalformedProof)?;
+			ProtocolProof::from_u8_slice(proof.as_slice()).map_err(|e| match e {
+				ProofDeserializationError::NotSupportedProtocol => Error::<T>::NotSupportedProtocol,
+				ProofDeserializationError::SerdeError |
+				ProofDeserializationError::CodecError |
+				ProofDeserializationError::InvalidNumber(_) => Error::<T>::MalformedProof,
+			})?;
 		ensure!(
-			deserialized_proof.curve == SUPPORTED_CURVE.as_bytes(),
+			SupportedCurve::from_name(deserialized_proof.curve()).is_some(),
 			Error::<T>::NotSupportedCurve
 		);
-		ensure!(
-			deserialized_proof.protocol == SUPPORTED_PROTOCOL.as_bytes(),
-			Error::<T>::NotSupportedProtocol
-		);
-
-		ProofStorage::<T>::put(proof);
-
-		let proof = GProof::from_uncompressed(
-			&G1UncompressedBytes::new(deserialized_proof.a[0], deserialized_proof.a[1]),
-			&G2UncompressedBytes::new(
-				deserialized_proof.b[0][0],
-				deserialized_proof.b[0][1],
-				deserialized_proof.b[1][0],
-				deserialized_proof.b[1][1],
-			),
-			&G1UncompressedBytes::new(deserialized_proof.c[0], deserialized_proof.c[1]),
-		)
-		.map_err(|_| Error::<T>::ProofCreationError)?;
-
-		Ok(proof)
+		Ok((proof, deserialized_proof))
 	}
 }
@@ -0,0 +1,108 @@
+// MIT License
+
+// Copyright (c) 2022 Bright Inventions
+
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! Storage migrations of the pallet.
+
+/// Version 2 replaced `ProofStorage`, the latest proof submitted for each circuit, with
+/// `VerificationRecords`. Version 1 first stored the key, the public inputs and the proof of a
+/// single circuit in plain values, which were left behind when the circuits got their ids.
+pub mod v2 {
+	use crate::{CircuitId, Config, Pallet};
+	use frame_support::{
+		storage_alias,
+		traits::{Get, GetStorageVersion, StorageVersion},
+		weights::Weight,
+		Blake2_128Concat,
+	};
+	use sp_std::vec::Vec;
+
+	#[storage_alias]
+	pub(crate) type ProofStorage<T: Config> =
+		StorageMap<Pallet<T>, Blake2_128Concat, CircuitId, Vec<u8>>;
+
+	/// The public inputs of the single circuit, the key is not used anymore.
+	#[storage_alias]
+	pub(crate) type PublicInputStorage<T: Config> = StorageValue<Pallet<T>, Vec<u8>>;
+
+	/// The key of the single circuit, stored under the prefix of the current map.
+	#[storage_alias]
+	pub(crate) type VerificationKeyStorage<T: Config> = StorageValue<Pallet<T>, Vec<u8>>;
+
+	/// Removes the proofs stored by version 1, they were never read, and the values of the
+	/// single circuit. The keys registered under circuit ids are kept.
+	pub fn migrate<T: Config>() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() >= StorageVersion::new(2) {
+			return T::DbWeight::get().reads(1)
+		}
+		// Also removes the proof of the single circuit, stored under the prefix of the map
+		let removed = ProofStorage::<T>::clear(u32::MAX, None).unique;
+		PublicInputStorage::<T>::kill();
+		VerificationKeyStorage::<T>::kill();
+		StorageVersion::new(2).put::<Pallet<T>>();
+		T::DbWeight::get().reads_writes(1, removed as u64 + 3)
+	}
+}
+
+/// Version 3 stores `e(alpha, beta)` with the decoded Groth16 keys and their points in arrays of
+/// the size of the curve, so the keys decoded by version 2 are decoded again from the registered
+/// ones.
+pub mod v3 {
+	use crate::{
+		deserialization::ProtocolVKey, pallet::decoded_groth16_verification_key,
+		verify::SupportedProtocol, Config, DecodedVerificationKeyStorage, Pallet,
+		VerificationKeyStorage, WeightInfo,
+	};
+	use frame_support::{
+		traits::{Get, GetStorageVersion, StorageVersion},
+		weights::Weight,
+	};
+
+	pub fn migrate<T: Config>() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() >= StorageVersion::new(3) {
+			return T::DbWeight::get().reads(1)
+		}
+		let mut weight = T::DbWeight::get().reads_writes(1, 1);
+		for (circuit_id, vk) in VerificationKeyStorage::<T>::iter() {
+			weight = weight.saturating_add(T::DbWeight::get().reads(1));
+			let vk = match ProtocolVKey::from_u8_slice(&vk) {
+				Ok(vk) if vk.protocol() == SupportedProtocol::Groth16 => vk,
+				_ => continue,
+			};
+			// Decoding costs as much as registering the key again
+			weight = weight
+				.saturating_add(T::WeightInfo::register_circuit(vk.public_inputs_len() as u32 + 1));
+			match decoded_groth16_verification_key::<T>(vk) {
+				Ok(decoded) => DecodedVerificationKeyStorage::<T>::insert(circuit_id, decoded),
+				// Verifications fail on the key decoded on the fly with the error of registration
+				Err(_) => DecodedVerificationKeyStorage::<T>::remove(circuit_id),
+			}
+		}
+		StorageVersion::new(3).put::<Pallet<T>>();
+		weight
+	}
+}
@@ -26,21 +26,23 @@
 // IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
 // DEALINGS IN THE SOFTWARE.
 
-#![cfg(test)]
-
 use super::*;
 use crate as pallet_zk_snarks;
 
+use crate::deserialization::PublicInput;
 use frame_support::{
 	parameter_types,
 	traits::{ConstU32, ConstU64},
 };
+use frame_system::EnsureRoot;
 
 use sp_core::H256;
 use sp_runtime::{
 	testing::Header,
 	traits::{BlakeTwo256, IdentityLookup},
+	DispatchError, DispatchResult,
 };
+use std::cell::RefCell;
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
 type Block = frame_system::mocking::MockBlock<Test>;
@@ -52,7 +54,7 @@ frame_support::construct_runtime!(
 		UncheckedExtrinsic = UncheckedExtrinsic,
 	{
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
-		ZKSnarks: pallet_zk_snarks::{Pallet, Storage, Event<T>},
+		ZKSnarks: pallet_zk_snarks::{Pallet, Call, Storage, Event<T>, Origin, ValidateUnsigned},
 	}
 );
 
@@ -84,17 +86,52 @@ impl frame_system::Config for Test {
 }
 
 parameter_types! {
-	pub const MaxPublicInputsLength: u32 = 9;
-	pub const MaxVerificationKeyLength: u32 = 4143;
-	pub const MaxProofLength: u32 = 1133;
+	pub const MaxPublicInputsLength: u32 = 256;
+	pub const MaxVerificationKeyLength: u32 = 8192;
+	pub const MaxProofLength: u32 = 4096;
+	pub const MaxBatchSize: u32 = 4;
+	pub const VerificationRecordLifetime: u64 = 10;
 }
 
 impl pallet_zk_snarks::Config for Test {
 	type MaxPublicInputsLength = MaxPublicInputsLength;
 	type MaxProofLength = MaxProofLength;
 	type MaxVerificationKeyLength = MaxVerificationKeyLength;
+	type MaxBatchSize = MaxBatchSize;
+	type VerificationRecordLifetime = VerificationRecordLifetime;
 	type RuntimeEvent = RuntimeEvent;
-	type WeightInfo = ();
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type WeightInfo = SubstrateWeight<Test>;
+	type SetupOrigin = EnsureRoot<u64>;
+	type OnProofVerified = RecordVerified;
+}
+
+thread_local! {
+	pub static VERIFIED: RefCell<Vec<(Option<u64>, CircuitId, Vec<PublicInput>)>> = RefCell::new(vec![]);
+	pub static REJECT_VERIFIED: RefCell<bool> = RefCell::new(false);
+}
+
+/// Records the verified statements, or rejects them when `REJECT_VERIFIED` is set
+pub struct RecordVerified;
+
+impl OnVerified<Option<u64>, CircuitId, Vec<PublicInput>> for RecordVerified {
+	fn on_verified(
+		who: &Option<u64>,
+		circuit_id: CircuitId,
+		public_inputs: &Vec<PublicInput>,
+	) -> DispatchResult {
+		if REJECT_VERIFIED.with(|reject| *reject.borrow()) {
+			return Err(DispatchError::Other("rejected"))
+		}
+		let statement = (*who, circuit_id, public_inputs.clone());
+		VERIFIED.with(|verified| verified.borrow_mut().push(statement));
+		Ok(())
+	}
+}
+
+pub fn verified_statements() -> Vec<(Option<u64>, CircuitId, Vec<PublicInput>)> {
+	VERIFIED.with(|verified| verified.borrow().clone())
 }
 
 pub fn new_test_ext() -> sp_io::TestExternalities {
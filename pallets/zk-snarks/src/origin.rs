@@ -0,0 +1,76 @@
+// MIT License
+
+// Copyright (c) 2022 Bright Inventions
+
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! The `ZkProven` origin of the calls dispatched with a proof, and the `EnsureOrigin` which
+//! gates calls of other pallets on it.
+
+use crate::{deserialization::PublicInput, CircuitId};
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::{
+	traits::{EnsureOrigin, Get},
+	RuntimeDebug,
+};
+use scale_info::TypeInfo;
+use sp_std::marker::PhantomData;
+
+/// Origin of the calls dispatched by `dispatch_with_proof`.
+#[derive(Clone, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub enum RawOrigin {
+	/// A valid proof of the circuit was submitted, using the nullifier.
+	ZkProven { circuit_id: CircuitId, nullifier: PublicInput },
+}
+
+/// Ensures the origin is `ZkProven` for the circuit `Id`, so a call can be gated on a proof
+/// instead of an account. Succeeds with the nullifier of the proof, which is unique for the
+/// circuit and can stand for the anonymous prover.
+///
+/// The circuit `Id` has to commit to the call: its last public input has to be the
+/// `Pallet::call_hash_input` of the dispatched call, which `dispatch_with_proof` checks. A
+/// circuit without such an input can not prove anything about the call it is submitted with.
+pub struct EnsureZkProven<Id>(PhantomData<Id>);
+
+impl<O, Id> EnsureOrigin<O> for EnsureZkProven<Id>
+where
+	O: Into<Result<RawOrigin, O>> + From<RawOrigin>,
+	Id: Get<CircuitId>,
+{
+	type Success = PublicInput;
+
+	fn try_origin(o: O) -> Result<Self::Success, O> {
+		o.into().and_then(|o| match o {
+			RawOrigin::ZkProven { circuit_id, nullifier } if circuit_id == Id::get() =>
+				Ok(nullifier),
+			o => Err(O::from(o)),
+		})
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn successful_origin() -> O {
+		O::from(RawOrigin::ZkProven { circuit_id: Id::get(), nullifier: Default::default() })
+	}
+}
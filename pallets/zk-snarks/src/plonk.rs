@@ -0,0 +1,462 @@
+// MIT License
+
+// Copyright (c) 2022 Bright Inventions
+
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+use crate::verify::{
+	Curve, PublicInputs, VerificationError::InvalidVerificationKey, VerificationResult,
+};
+use sp_io::hashing::keccak_256;
+use sp_std::{marker::PhantomData, prelude::*};
+
+/// Represents PLONK verification key
+pub struct PlonkVerificationKey<C: Curve> {
+	pub public_inputs_len: u8,
+	/// The evaluation domain has `2^power` elements
+	pub power: u8,
+	pub k1: C::Scalar,
+	pub k2: C::Scalar,
+	/// Generator of the evaluation domain
+	pub w: C::Scalar,
+	pub qm: C::G1,
+	pub ql: C::G1,
+	pub qr: C::G1,
+	pub qo: C::G1,
+	pub qc: C::G1,
+	pub s1: C::G1,
+	pub s2: C::G1,
+	pub s3: C::G1,
+	/// `[τ]₂` of the KZG setup
+	pub x_2: C::G2,
+}
+
+/// Represents PLONK proof
+pub struct PProof<C: Curve> {
+	pub a: C::G1,
+	pub b: C::G1,
+	pub c: C::G1,
+	pub z: C::G1,
+	pub t1: C::G1,
+	pub t2: C::G1,
+	pub t3: C::G1,
+	pub wxi: C::G1,
+	pub wxiw: C::G1,
+	pub eval_a: C::Scalar,
+	pub eval_b: C::Scalar,
+	pub eval_c: C::Scalar,
+	pub eval_s1: C::Scalar,
+	pub eval_s2: C::Scalar,
+	pub eval_zw: C::Scalar,
+}
+
+/// Fiat-Shamir transcript compatible with snarkjs `Keccak256Transcript`
+pub(crate) struct Transcript<C: Curve> {
+	data: Vec<u8>,
+	curve: PhantomData<C>,
+}
+
+impl<C: Curve> Transcript<C> {
+	pub(crate) fn new() -> Self {
+		Transcript { data: Vec::new(), curve: PhantomData }
+	}
+
+	pub(crate) fn add_point(&mut self, point: &C::G1) {
+		self.data.extend(C::g1_to_bytes(point));
+	}
+
+	pub(crate) fn add_scalar(&mut self, scalar: &C::Scalar) {
+		self.data.extend(C::scalar_to_bytes(scalar));
+	}
+
+	/// Hashes everything added so far and starts over
+	pub(crate) fn challenge(&mut self) -> C::Scalar {
+		let hash = keccak_256(&self.data);
+		self.data.clear();
+		C::scalar_from_hash(hash)
+	}
+}
+
+/// Computes Lᵢ(ξ) = ωⁱ⁻¹ * Zₕ(ξ) / (n * (ξ - ωⁱ⁻¹)) for i in 1..=max(1, count), returns `None`
+/// if ξ happens to be in the evaluation domain
+pub(crate) fn lagrange_evaluations<C: Curve>(
+	xi: C::Scalar,
+	zh: C::Scalar,
+	w: C::Scalar,
+	power: u8,
+	count: usize,
+) -> Option<Vec<C::Scalar>> {
+	let n = C::scalar_from_u64(1 << power);
+	let mut lagrange = Vec::with_capacity(count.max(1));
+	let mut wi = C::scalar_from_u64(1);
+	for _ in 0..count.max(1) {
+		lagrange.push(wi * zh * C::scalar_inverse(&(n * (xi - wi)))?);
+		wi = wi * w;
+	}
+	Some(lagrange)
+}
+
+/// Computes PI(ξ) = -Σ inputᵢ * Lᵢ(ξ)
+pub(crate) fn public_input_evaluation<C: Curve>(
+	inputs: &[C::Scalar],
+	lagrange: &[C::Scalar],
+) -> C::Scalar {
+	inputs
+		.iter()
+		.zip(lagrange)
+		.fold(C::scalar_from_u64(0), |pi, (input, l)| pi - *input * *l)
+}
+
+/// Verifies given PLONK proof with given verification key and public inputs, following snarkjs
+/// `plonk_verify`
+pub fn verify_plonk<C: Curve>(
+	vk: PlonkVerificationKey<C>,
+	proof: PProof<C>,
+	inputs: PublicInputs<C>,
+) -> VerificationResult {
+	if inputs.len() != vk.public_inputs_len as usize || vk.power >= 64 {
+		return Err(InvalidVerificationKey)
+	}
+	let one = C::scalar_from_u64(1);
+
+	let mut transcript = Transcript::<C>::new();
+	for point in [&vk.qm, &vk.ql, &vk.qr, &vk.qo, &vk.qc, &vk.s1, &vk.s2, &vk.s3] {
+		transcript.add_point(point);
+	}
+	for input in &inputs {
+		transcript.add_scalar(input);
+	}
+	for point in [&proof.a, &proof.b, &proof.c] {
+		transcript.add_point(point);
+	}
+	let beta = transcript.challenge();
+
+	transcript.add_scalar(&beta);
+	let gamma = transcript.challenge();
+
+	transcript.add_scalar(&beta);
+	transcript.add_scalar(&gamma);
+	transcript.add_point(&proof.z);
+	let alpha = transcript.challenge();
+
+	transcript.add_scalar(&alpha);
+	for point in [&proof.t1, &proof.t2, &proof.t3] {
+		transcript.add_point(point);
+	}
+	let xi = transcript.challenge();
+
+	transcript.add_scalar(&xi);
+	for eval in [
+		&proof.eval_a,
+		&proof.eval_b,
+		&proof.eval_c,
+		&proof.eval_s1,
+		&proof.eval_s2,
+		&proof.eval_zw,
+	] {
+		transcript.add_scalar(eval);
+	}
+	let v1 = transcript.challenge();
+	let v2 = v1 * v1;
+	let v3 = v2 * v1;
+	let v4 = v3 * v1;
+	let v5 = v4 * v1;
+
+	transcript.add_point(&proof.wxi);
+	transcript.add_point(&proof.wxiw);
+	let u = transcript.challenge();
+
+	// Zₕ(ξ) = ξⁿ - 1
+	let mut xin = xi;
+	for _ in 0..vk.power {
+		xin = xin * xin;
+	}
+	let zh = xin - one;
+
+	let lagrange = match lagrange_evaluations::<C>(xi, zh, vk.w, vk.power, inputs.len()) {
+		Some(lagrange) => lagrange,
+		None => return Ok(false),
+	};
+	let l1 = lagrange[0];
+	let pi = public_input_evaluation::<C>(&inputs, &lagrange);
+
+	let alpha2 = alpha * alpha;
+	let e3a = proof.eval_a + beta * proof.eval_s1 + gamma;
+	let e3b = proof.eval_b + beta * proof.eval_s2 + gamma;
+	let e3c = proof.eval_c + gamma;
+	let r0 = pi - l1 * alpha2 - e3a * e3b * e3c * proof.eval_zw * alpha;
+
+	let beta_xi = beta * xi;
+	let d2a = (proof.eval_a + beta_xi + gamma) *
+		(proof.eval_b + beta_xi * vk.k1 + gamma) *
+		(proof.eval_c + beta_xi * vk.k2 + gamma) *
+		alpha;
+	let d2 = d2a + l1 * alpha2 + u;
+	let d3 = e3a * e3b * alpha * beta * proof.eval_zw;
+
+	let e = -r0 +
+		v1 * proof.eval_a +
+		v2 * proof.eval_b +
+		v3 * proof.eval_c +
+		v4 * proof.eval_s1 +
+		v5 * proof.eval_s2 +
+		u * proof.eval_zw;
+
+	// A₁ = W_ξ + u * W_ξω
+	let a1 = C::g1_multi_exp(&proof.wxi, &[proof.wxiw], &[u]);
+
+	// B₁ = ξ * W_ξ + u * ξ * ω * W_ξω + F - E, where
+	// D = a * b * Qm + a * Ql + b * Qr + c * Qo + Qc + d2 * Z - d3 * S3 - Zₕ(ξ) * T
+	// F = D + v * A + v² * B + v³ * C + v⁴ * S1 + v⁵ * S2
+	// E = e * G1
+	let b1 = C::g1_multi_exp(
+		&vk.qc,
+		&[
+			vk.qm,
+			vk.ql,
+			vk.qr,
+			vk.qo,
+			proof.z,
+			vk.s3,
+			proof.t1,
+			proof.t2,
+			proof.t3,
+			proof.a,
+			proof.b,
+			proof.c,
+			vk.s1,
+			vk.s2,
+			proof.wxi,
+			proof.wxiw,
+			C::g1_generator(),
+		],
+		&[
+			proof.eval_a * proof.eval_b,
+			proof.eval_a,
+			proof.eval_b,
+			proof.eval_c,
+			d2,
+			-d3,
+			-zh,
+			-(zh * xin),
+			-(zh * xin * xin),
+			v1,
+			v2,
+			v3,
+			v4,
+			v5,
+			xi,
+			u * xi * vk.w,
+			-e,
+		],
+	);
+
+	// e(-A₁, [τ]₂) * e(B₁, [1]₂) = 1
+	Ok(C::pairing_product_is_identity(&[(C::g1_neg(&a1), vk.x_2), (b1, C::g2_generator())]))
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		bn254::Bn254,
+		common::{prepare_plonk_proof, prepare_plonk_verification_key},
+		deserialization::{PlonkProof, PlonkVKey},
+		plonk::{verify_plonk, PProof, PlonkVerificationKey},
+		verify::{prepare_public_inputs, PublicInputs, VerificationError},
+	};
+
+	// Key and proof of circom/task.circom over bn128 for x = 3, so the only public input is y = 12
+	const VK_JSON: &str = r#"{
+ "protocol": "plonk",
+ "curve": "bn128",
+ "nPublic": 1,
+ "power": 3,
+ "k1": "2",
+ "k2": "3",
+ "Qm": [
+  "21043728423416338430918951451064271033722560221385216624472295944389744135560",
+  "7531252060151573373164348699915826074436956742849317532716300618815277245067",
+  "1"
+ ],
+ "Ql": [
+  "821097604170088485852085622868773717728631991742632327825724171564969414940",
+  "2647172813390738520257209287350116007289741063967461296380833200236237911717",
+  "1"
+ ],
+ "Qr": [
+  "0",
+  "1",
+  "0"
+ ],
+ "Qo": [
+  "11516313973206610564043357888519182990698593340601211726714758763508389710900",
+  "8950598501716091183718760238186989290116217320938084142119593068167442558245",
+  "1"
+ ],
+ "Qc": [
+  "11845860552908422364377744600016924434155157704034720818873354144394207982499",
+  "5508321323441358493836342943307841778381969941790809858981303114645964991025",
+  "1"
+ ],
+ "S1": [
+  "2235607443531693859265916918065333834364394190336305762182641447802417372009",
+  "9848726838867374993783505086398926861491531068652461841459181843203432255664",
+  "1"
+ ],
+ "S2": [
+  "9788181228714848772856361516618486192036338766431315959511228871487574735959",
+  "11221562281131986188415627600663131038835616420522042998394813277851945751797",
+  "1"
+ ],
+ "S3": [
+  "14304519745318582339725285585244597307325201272194314673551577665092212705474",
+  "19228951283770524755079559375283576350128339072723271183594778255963373978321",
+  "1"
+ ],
+ "X_2": [
+  [
+   "15903878421108759439848478618074911574701566925570229139405616333111755535845",
+   "4291294403358638570039388526470878576987994586754394716672632188568865367486"
+  ],
+  [
+   "18531874304451210335666117718257049670818307824295916814921889502511919838294",
+   "2979484662042156423400334245990653844506029192010916415899010348289180103697"
+  ],
+  [
+   "1",
+   "0"
+  ]
+ ],
+ "w": "19540430494807482326159819597004422086093766032135589407132600596362845576832"
+}"#;
+
+	const PROOF_JSON: &str = r#"{
+ "A": [
+  "8571288710236751879730997581741400792611069332973679042048973055709261243539",
+  "14505133663306351091444265640431853697465226870714079593501461575202456283605",
+  "1"
+ ],
+ "B": [
+  "8709602404358829963806292574900641750888950165640854482941286731183889057416",
+  "4064372348266938924003430859990616001411605888948457505726361949340793486731",
+  "1"
+ ],
+ "C": [
+  "974258032619639082307086666508405829638732027132683257675071685470820259617",
+  "9947979352472998407693157448368152775659671397610754925546869613987363000286",
+  "1"
+ ],
+ "Z": [
+  "21582975335397802135236442183473720597280437435395155820134943519900190476791",
+  "20117906821286077619527251149860529546974198958438333756274932492267806607715",
+  "1"
+ ],
+ "T1": [
+  "3545047308376181817457078032537497780767037728542205684418983080568224901623",
+  "20423766024501605378925265690246980657758181274119290968639476903797673392576",
+  "1"
+ ],
+ "T2": [
+  "21412735937442269165543243942908813316679278517749759534329703631962993706878",
+  "11991608116089446212999640219388187810476941106794902828194145597922404854191",
+  "1"
+ ],
+ "T3": [
+  "17137014371243091548441524707310565370586455640374122777978460290174411171371",
+  "19223419751530258366450704352728745316790095985433903042435104847840594671030",
+  "1"
+ ],
+ "Wxi": [
+  "20598467983392346143310491132720614691629395457401862373602901392244159413474",
+  "8493872317884825048181903459975198654783028584403315108405093230577797471934",
+  "1"
+ ],
+ "Wxiw": [
+  "3301513922664847757831644666475169063379780282647379546677097932558048563340",
+  "1685002504133676457031889893955555229564947216091760582208208537050562706470",
+  "1"
+ ],
+ "eval_a": "16223272930278254588247201010062725648761034075168364130363741966231738021709",
+ "eval_b": "2152978088049985579898263018241760104122429006545931490938632122890502494750",
+ "eval_c": "8136359670427995329697337395228596914522316680441619537643323534835157314322",
+ "eval_s1": "21049033008309520438571113921643403326422991470175443198166049146187094037046",
+ "eval_s2": "1957731797245194343016497149135681201032756824191774452262203751882632276210",
+ "eval_zw": "11509309785962252428353253848745032447564617327566520412965007707469704422600",
+ "protocol": "plonk",
+ "curve": "bn128"
+}"#;
+
+	fn prepare_inputs(inputs: &[u8]) -> PublicInputs<Bn254> {
+		let inputs = inputs
+			.iter()
+			.map(|input| {
+				let mut public_input = [0u8; 32];
+				public_input[31] = *input;
+				public_input
+			})
+			.collect();
+		prepare_public_inputs::<Bn254>(inputs).unwrap()
+	}
+
+	fn prepare_vk() -> PlonkVerificationKey<Bn254> {
+		prepare_plonk_verification_key(PlonkVKey::from_json_u8_slice(VK_JSON.as_bytes()).unwrap())
+			.unwrap()
+	}
+
+	fn prepare_proof() -> PProof<Bn254> {
+		prepare_plonk_proof(PlonkProof::from_json_u8_slice(PROOF_JSON.as_bytes()).unwrap()).unwrap()
+	}
+
+	#[test]
+	fn verify_correct_proof() {
+		let result = verify_plonk(prepare_vk(), prepare_proof(), prepare_inputs(&[12]));
+
+		assert!(result.unwrap())
+	}
+
+	#[test]
+	fn verify_incorrect_proof() {
+		let result = verify_plonk(prepare_vk(), prepare_proof(), prepare_inputs(&[13]));
+
+		assert!(!result.unwrap())
+	}
+
+	#[test]
+	fn verify_with_tampered_evaluation() {
+		let mut proof = prepare_proof();
+		proof.eval_zw = proof.eval_zw + proof.eval_a;
+
+		let result = verify_plonk(prepare_vk(), proof, prepare_inputs(&[12]));
+
+		assert!(!result.unwrap())
+	}
+
+	#[test]
+	fn verify_with_incorrect_public_inputs_len() {
+		let result = verify_plonk(prepare_vk(), prepare_proof(), prepare_inputs(&[12, 12]));
+
+		assert_eq!(result.err().unwrap(), VerificationError::InvalidVerificationKey)
+	}
+}
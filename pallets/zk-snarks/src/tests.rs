@@ -28,36 +28,89 @@
 
 #![cfg(test)]
 
-use crate::{mock::*, *};
+use crate::{
+	bn254::Bn254,
+	compact::{
+		Bn254G1, Bn254G2, CompactProof, CompactVKey, CompressedProof, CompressedVerificationKey,
+	},
+	deserialization::{deserialize_public_inputs, Proof, PublicInput, VKey, U256},
+	mock::*,
+	verify::{Bls12_381, Curve, SupportedCurve},
+	*,
+};
+use codec::Encode;
 
-use frame_support::{assert_err, assert_ok};
+use frame_support::{
+	assert_err, assert_err_ignore_postinfo, assert_ok,
+	dispatch::{DispatchInfo, DispatchResultWithPostInfo},
+	traits::{ConstU32, EnsureOrigin, GetStorageVersion, Hooks, StorageVersion},
+	weights::Weight,
+};
+use sp_runtime::{
+	traits::{Dispatchable, SignedExtension, ValidateUnsigned},
+	transaction_validity::{
+		InvalidTransaction, TransactionSource, TransactionValidity, TransactionValidityError,
+	},
+	DispatchError, ModuleError,
+};
 
 const ALICE_ACCOUNT_ID: u64 = 2;
 const BOB_ACCOUNT_ID: u64 = 3;
+const CIRCUIT_ID: CircuitId = 1;
+const OTHER_CIRCUIT_ID: CircuitId = 2;
 
 #[test]
-fn test_setup_verification() {
+fn test_register_circuit() {
 	new_test_ext().execute_with(|| {
 		let vk = prepare_vk_json("groth16", "bls12381", None);
-		assert_ok!(ZKSnarks::setup_verification(
-			RuntimeOrigin::none(),
-			prepare_correct_public_inputs_json().as_bytes().into(),
-			vk.as_bytes().into()
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
 		));
 		let events = zk_events();
 		assert_eq!(events.len(), 1);
-		assert_eq!(events[0], Event::<Test>::VerificationSetupCompleted);
+		assert_eq!(events[0], setup_completed(CIRCUIT_ID, &vk));
 	});
 }
 #[test]
-fn test_not_supported_vk_curve() {
+fn test_register_circuit_bad_origin() {
 	new_test_ext().execute_with(|| {
-		let vk = prepare_vk_json("groth16", "bn128", None);
+		let vk = prepare_vk_json("groth16", "bls12381", None);
+		assert_err!(
+			ZKSnarks::register_circuit(
+				RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+				CIRCUIT_ID,
+				vk.as_bytes().into(),
+				None
+			),
+			DispatchError::BadOrigin
+		);
 		assert_err!(
-			ZKSnarks::setup_verification(
+			ZKSnarks::register_circuit(
 				RuntimeOrigin::none(),
-				prepare_correct_public_inputs_json().as_bytes().into(),
-				vk.as_bytes().into()
+				CIRCUIT_ID,
+				vk.as_bytes().into(),
+				None
+			),
+			DispatchError::BadOrigin
+		);
+		assert!(!VerificationKeyStorage::<Test>::contains_key(CIRCUIT_ID));
+		assert_eq!(zk_events().len(), 0);
+	});
+}
+
+#[test]
+fn test_not_supported_vk_curve() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_vk_json("groth16", "bls12377", None);
+		assert_err!(
+			ZKSnarks::register_circuit(
+				RuntimeOrigin::root(),
+				CIRCUIT_ID,
+				vk.as_bytes().into(),
+				None
 			),
 			Error::<Test>::NotSupportedCurve
 		);
@@ -71,10 +124,11 @@ fn test_not_supported_vk_protocol() {
 	new_test_ext().execute_with(|| {
 		let vk = prepare_vk_json("-", "bls12381", None);
 		assert_err!(
-			ZKSnarks::setup_verification(
-				RuntimeOrigin::none(),
-				prepare_correct_public_inputs_json().as_bytes().into(),
-				vk.as_bytes().into()
+			ZKSnarks::register_circuit(
+				RuntimeOrigin::root(),
+				CIRCUIT_ID,
+				vk.as_bytes().into(),
+				None
 			),
 			Error::<Test>::NotSupportedProtocol
 		);
@@ -87,10 +141,11 @@ fn test_not_supported_vk_protocol() {
 fn test_too_long_verification_key() {
 	new_test_ext().execute_with(|| {
 		assert_err!(
-			ZKSnarks::setup_verification(
-				RuntimeOrigin::none(),
-				prepare_correct_public_inputs_json().as_bytes().into(),
-				vec![0; (<Test as Config>::MaxVerificationKeyLength::get() + 1) as usize]
+			ZKSnarks::register_circuit(
+				RuntimeOrigin::root(),
+				CIRCUIT_ID,
+				vec![0; (<Test as Config>::MaxVerificationKeyLength::get() + 1) as usize],
+				None
 			),
 			Error::<Test>::TooLongVerificationKey
 		);
@@ -101,26 +156,64 @@ fn test_too_long_verification_key() {
 #[test]
 fn test_too_long_public_inputs() {
 	new_test_ext().execute_with(|| {
-		assert_err!(
-			ZKSnarks::setup_verification(
-				RuntimeOrigin::none(),
-				vec![0; (<Test as Config>::MaxPublicInputsLength::get() + 1) as usize],
-				prepare_vk_json("groth16", "bls12381", None).as_bytes().into()
+		let vk = prepare_vk_json("groth16", "bls12381", None);
+		let proof = prepare_proof_json("groth16", "bls12381", None);
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		assert_err_ignore_postinfo!(
+			ZKSnarks::verify(
+				RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+				CIRCUIT_ID,
+				proof.as_bytes().into(),
+				vec![0; (<Test as Config>::MaxPublicInputsLength::get() + 1) as usize]
 			),
 			Error::<Test>::TooLongPublicInputs
 		);
-		assert_eq!(zk_events().len(), 0);
+		assert_eq!(zk_events().len(), 1);
 	});
 }
 
 #[test]
 fn test_public_inputs_mismatch() {
 	new_test_ext().execute_with(|| {
+		let vk = prepare_vk_json("groth16", "bls12381", None);
+		let proof = prepare_proof_json("groth16", "bls12381", None);
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		assert_err_ignore_postinfo!(
+			ZKSnarks::verify(
+				RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+				CIRCUIT_ID,
+				proof.as_bytes().into(),
+				prepare_empty_public_inputs_json().as_bytes().into()
+			),
+			Error::<Test>::PublicInputsMismatch
+		);
+		assert_eq!(zk_events().len(), 1);
+	});
+}
+
+#[test]
+fn test_vk_public_inputs_mismatch() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_vk_json("groth16", "bls12381", None)
+			.replace(r#""nPublic": 1"#, r#""nPublic": 2"#);
 		assert_err!(
-			ZKSnarks::setup_verification(
-				RuntimeOrigin::none(),
-				prepare_empty_public_inputs_json().as_bytes().into(),
-				prepare_vk_json("groth16", "bls12381", None).as_bytes().into()
+			ZKSnarks::register_circuit(
+				RuntimeOrigin::root(),
+				CIRCUIT_ID,
+				vk.as_bytes().into(),
+				None
 			),
 			Error::<Test>::PublicInputsMismatch
 		);
@@ -131,157 +224,2685 @@ fn test_public_inputs_mismatch() {
 #[test]
 fn test_too_long_proof() {
 	new_test_ext().execute_with(|| {
-		assert_err!(
+		assert_err_ignore_postinfo!(
 			ZKSnarks::verify(
-				RuntimeOrigin::none(),
-				vec![0; (<Test as Config>::MaxProofLength::get() + 1) as usize]
+				RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+				CIRCUIT_ID,
+				vec![0; (<Test as Config>::MaxProofLength::get() + 1) as usize],
+				prepare_correct_public_inputs_json().as_bytes().into()
 			),
 			Error::<Test>::TooLongProof
 		);
-		assert_eq!(zk_events().len(), 0);
+		assert_eq!(zk_events().len(), 0);
+	});
+}
+
+#[test]
+fn test_not_supported_proof_protocol() {
+	let proof = prepare_proof_json("-", "bls12381", None);
+
+	new_test_ext().execute_with(|| {
+		assert_err_ignore_postinfo!(
+			ZKSnarks::verify(
+				RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+				CIRCUIT_ID,
+				proof.as_bytes().into(),
+				prepare_correct_public_inputs_json().as_bytes().into()
+			),
+			Error::<Test>::NotSupportedProtocol
+		);
+		assert_eq!(zk_events().len(), 0);
+	});
+}
+
+#[test]
+fn test_not_supported_proof_curve() {
+	let proof = prepare_proof_json("groth16", "bls12377", None);
+
+	new_test_ext().execute_with(|| {
+		assert_err_ignore_postinfo!(
+			ZKSnarks::verify(
+				RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+				CIRCUIT_ID,
+				proof.as_bytes().into(),
+				prepare_correct_public_inputs_json().as_bytes().into()
+			),
+			Error::<Test>::NotSupportedCurve
+		);
+		assert_eq!(zk_events().len(), 0);
+	});
+}
+
+#[test]
+fn test_empty_proof() {
+	new_test_ext().execute_with(|| {
+		assert_err_ignore_postinfo!(
+			ZKSnarks::verify(
+				RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+				CIRCUIT_ID,
+				Vec::new(),
+				prepare_correct_public_inputs_json().as_bytes().into()
+			),
+			Error::<Test>::ProofIsEmpty
+		);
+		assert_eq!(zk_events().len(), 0);
+	});
+}
+
+#[test]
+fn test_verify_without_verification_key() {
+	let proof = prepare_proof_json("groth16", "bls12381", None);
+
+	new_test_ext().execute_with(|| {
+		assert_err_ignore_postinfo!(
+			ZKSnarks::verify(
+				RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+				CIRCUIT_ID,
+				proof.as_bytes().into(),
+				prepare_correct_public_inputs_json().as_bytes().into()
+			),
+			Error::<Test>::VerificationKeyIsNotSet
+		);
+		assert_eq!(zk_events().len(), 0);
+	});
+}
+
+#[test]
+fn test_verify_bad_origin() {
+	new_test_ext().execute_with(|| {
+		// The origin is checked before the proof, even an empty one
+		for origin in [RuntimeOrigin::none(), RuntimeOrigin::root()] {
+			assert_err_ignore_postinfo!(
+				ZKSnarks::verify(
+					origin.clone(),
+					CIRCUIT_ID,
+					vec![],
+					prepare_correct_public_inputs_json().as_bytes().into()
+				),
+				DispatchError::BadOrigin
+			);
+			assert_err_ignore_postinfo!(
+				ZKSnarks::verify_batch(origin, CIRCUIT_ID, vec![]),
+				DispatchError::BadOrigin
+			);
+		}
+		assert_eq!(zk_events().len(), 0);
+	});
+}
+
+#[test]
+fn test_verification_success() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_vk_json("groth16", "bls12381", None);
+		let proof = prepare_proof_json("groth16", "bls12381", None);
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		assert_ok!(ZKSnarks::verify(
+			RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+			CIRCUIT_ID,
+			proof.as_bytes().into(),
+			prepare_correct_public_inputs_json().as_bytes().into()
+		));
+
+		let events = zk_events();
+		assert_eq!(events.len(), 3);
+		assert_eq!(events[0], setup_completed(CIRCUIT_ID, &vk));
+		assert_eq!(events[1], proof_set(ALICE_ACCOUNT_ID, proof.as_bytes()));
+		assert_eq!(
+			events[2],
+			verification_success(
+				ALICE_ACCOUNT_ID,
+				proof.as_bytes(),
+				&prepare_correct_public_inputs_json()
+			)
+		);
+	});
+}
+
+#[test]
+fn test_verification_failed() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_vk_json("groth16", "bls12381", None);
+		let proof = prepare_proof_json("groth16", "bls12381", None);
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		assert_ok!(ZKSnarks::verify(
+			RuntimeOrigin::signed(BOB_ACCOUNT_ID),
+			CIRCUIT_ID,
+			proof.as_bytes().into(),
+			prepare_incorrect_public_inputs_json().as_bytes().into()
+		));
+
+		let events = zk_events();
+		assert_eq!(events.len(), 3);
+		assert_eq!(events[0], setup_completed(CIRCUIT_ID, &vk));
+		assert_eq!(events[1], proof_set(BOB_ACCOUNT_ID, proof.as_bytes()));
+		assert_eq!(
+			events[2],
+			verification_failed(
+				BOB_ACCOUNT_ID,
+				proof.as_bytes(),
+				&prepare_incorrect_public_inputs_json()
+			)
+		);
+	});
+}
+
+#[test]
+fn test_verification_weight() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_vk_json("groth16", "bls12381", None);
+		let proof = prepare_proof_json("groth16", "bls12381", None);
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+
+		for public_inputs in
+			[prepare_correct_public_inputs_json(), prepare_incorrect_public_inputs_json()]
+		{
+			let result = ZKSnarks::verify(
+				RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+				CIRCUIT_ID,
+				proof.as_bytes().into(),
+				public_inputs.as_bytes().into(),
+			);
+			assert_eq!(actual_weight(result), Some(<Test as Config>::WeightInfo::verify(1)));
+		}
+	});
+}
+
+#[test]
+fn test_verification_weight_by_protocol_curve_and_format() {
+	new_test_ext().execute_with(|| {
+		let mut circuit_id = CIRCUIT_ID;
+		let mut weight = |vk: Vec<u8>, proof: Vec<u8>, inputs: String| {
+			circuit_id += 1;
+			assert_ok!(ZKSnarks::register_circuit(RuntimeOrigin::root(), circuit_id, vk, None));
+			let result = ZKSnarks::verify(
+				RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+				circuit_id,
+				proof,
+				inputs.as_bytes().into(),
+			);
+			actual_weight(result)
+		};
+		let bls12_381_vk = prepare_vk_json("groth16", "bls12381", None);
+		let bls12_381_proof = prepare_proof_json("groth16", "bls12381", None);
+		let bn254_inputs = || prepare_bn254_public_inputs_json("33");
+		let task_inputs = || prepare_bn254_public_inputs_json("12");
+
+		assert_eq!(
+			weight(
+				compact_vk(&bls12_381_vk),
+				compact_proof(&bls12_381_proof),
+				prepare_correct_public_inputs_json()
+			),
+			Some(<Test as Config>::WeightInfo::verify_compact(1))
+		);
+		assert_eq!(
+			weight(
+				prepare_bn254_vk_json().into_bytes(),
+				prepare_bn254_proof_json("bn128").into_bytes(),
+				bn254_inputs()
+			),
+			Some(<Test as Config>::WeightInfo::verify_bn254(1))
+		);
+		assert_eq!(
+			weight(
+				compact_vk(&prepare_bn254_vk_json()),
+				compact_proof(&prepare_bn254_proof_json("bn128")),
+				bn254_inputs()
+			),
+			Some(<Test as Config>::WeightInfo::verify_compact_bn254(1))
+		);
+		assert_eq!(
+			weight(
+				prepare_plonk_vk_json().into_bytes(),
+				prepare_plonk_proof_json("bn128").into_bytes(),
+				task_inputs()
+			),
+			Some(<Test as Config>::WeightInfo::verify_plonk_bn254(1))
+		);
+		assert_eq!(
+			weight(
+				prepare_fflonk_vk_json().into_bytes(),
+				prepare_fflonk_proof_json("bn128").into_bytes(),
+				task_inputs()
+			),
+			Some(<Test as Config>::WeightInfo::verify_fflonk_bn254(1))
+		);
+	});
+}
+
+#[test]
+fn test_parse_failure_refunds_weight() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_vk_json("groth16", "bls12381", None);
+		let proof = prepare_proof_json("groth16", "bls12381", None);
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+
+		let too_long_proof = vec![0; (<Test as Config>::MaxProofLength::get() + 1) as usize];
+		for (proof, public_inputs, error) in [
+			(Vec::new(), prepare_correct_public_inputs_json(), Error::<Test>::ProofIsEmpty),
+			(too_long_proof, prepare_correct_public_inputs_json(), Error::<Test>::TooLongProof),
+			(proof.as_bytes().into(), "[12]".to_owned(), Error::<Test>::MalformedPublicInputs),
+		] {
+			let max_proof_len = <Test as Config>::MaxProofLength::get() as usize;
+			let parse_failure = <Test as Config>::WeightInfo::verify_parse_failure(
+				proof.len().min(max_proof_len) as u32,
+				public_inputs.len() as u32,
+			);
+			let result = ZKSnarks::verify(
+				RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+				CIRCUIT_ID,
+				proof,
+				public_inputs.as_bytes().into(),
+			);
+			assert_err_ignore_postinfo!(result, error);
+			assert_eq!(actual_weight(result), Some(parse_failure));
+		}
+	});
+}
+
+#[test]
+fn test_decode_failure_refunds_weight() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_vk_json("groth16", "bls12381", None);
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		// `z` of `pi_a` is 2, without `x` and `y` scaled accordingly
+		let not_scaled = prepare_proof_json("groth16", "bls12381", None).replacen(
+			"\"1\"\n],\n\"pi_b\"",
+			"\"2\"\n],\n\"pi_b\"",
+			1,
+		);
+		let proof = prepare_proof_json("groth16", "bls12381", None);
+
+		for (circuit_id, proof, error) in [
+			(CIRCUIT_ID, not_scaled, Error::<Test>::PointNotOnCurve),
+			(OTHER_CIRCUIT_ID, proof, Error::<Test>::VerificationKeyIsNotSet),
+		] {
+			let result = ZKSnarks::verify(
+				RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+				circuit_id,
+				proof.as_bytes().into(),
+				prepare_correct_public_inputs_json().as_bytes().into(),
+			);
+			assert_err_ignore_postinfo!(result, error);
+			assert_eq!(
+				actual_weight(result),
+				Some(<Test as Config>::WeightInfo::verify_decode_failure())
+			);
+		}
+	});
+}
+
+#[test]
+fn test_batch_failure_refunds_weight() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_vk_json("groth16", "bls12381", None);
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		let proof = prepare_proof_json("groth16", "bls12381", None);
+		let not_scaled = proof.replacen("\"1\"\n],\n\"pi_b\"", "\"2\"\n],\n\"pi_b\"", 1);
+		let inputs = prepare_correct_public_inputs_json();
+		let decode_failure = <Test as Config>::WeightInfo::verify_decode_failure();
+
+		// Rejected while the second of three proofs is parsed
+		let result = ZKSnarks::verify_batch(
+			RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+			CIRCUIT_ID,
+			vec![
+				(proof.as_bytes().into(), inputs.as_bytes().into()),
+				(Vec::new(), inputs.as_bytes().into()),
+				(proof.as_bytes().into(), inputs.as_bytes().into()),
+			],
+		);
+		assert_err_ignore_postinfo!(result, Error::<Test>::ProofIsEmpty);
+		assert_eq!(actual_weight(result), Some(decode_failure.saturating_mul(2)));
+
+		// Rejected once the points of all three proofs are decoded
+		let result = ZKSnarks::verify_batch(
+			RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+			CIRCUIT_ID,
+			vec![
+				(proof.as_bytes().into(), inputs.as_bytes().into()),
+				(proof.as_bytes().into(), inputs.as_bytes().into()),
+				(not_scaled.as_bytes().into(), inputs.as_bytes().into()),
+			],
+		);
+		assert_err_ignore_postinfo!(result, Error::<Test>::PointNotOnCurve);
+		assert_eq!(actual_weight(result), Some(decode_failure.saturating_mul(3)));
+
+		let result = ZKSnarks::verify_batch(
+			RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+			CIRCUIT_ID,
+			vec![
+				(proof.as_bytes().into(), inputs.as_bytes().into()),
+				(proof.as_bytes().into(), inputs.as_bytes().into()),
+				(proof.as_bytes().into(), inputs.as_bytes().into()),
+			],
+		);
+		assert_eq!(actual_weight(result), Some(<Test as Config>::WeightInfo::verify_batch(3, 1)));
+
+		// The batch check fails, so the proofs are verified one by one
+		let result = ZKSnarks::verify_batch(
+			RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+			CIRCUIT_ID,
+			vec![
+				(proof.as_bytes().into(), inputs.as_bytes().into()),
+				(proof.as_bytes().into(), prepare_incorrect_public_inputs_json().as_bytes().into()),
+				(proof.as_bytes().into(), inputs.as_bytes().into()),
+			],
+		);
+		assert_eq!(
+			actual_weight(result),
+			Some(<Test as Config>::WeightInfo::verify_batch_invalid(3, 1))
+		);
+	});
+}
+
+#[test]
+fn test_verification_with_big_public_input() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_vk_json("groth16", "bls12381", None);
+		let proof = prepare_proof_json("groth16", "bls12381", None);
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		assert_ok!(ZKSnarks::verify(
+			RuntimeOrigin::signed(BOB_ACCOUNT_ID),
+			CIRCUIT_ID,
+			proof.as_bytes().into(),
+			prepare_big_public_inputs_json().as_bytes().into()
+		));
+
+		let events = zk_events();
+		assert_eq!(events.len(), 3);
+		assert_eq!(
+			events[2],
+			verification_failed(
+				BOB_ACCOUNT_ID,
+				proof.as_bytes(),
+				&prepare_big_public_inputs_json()
+			)
+		);
+	});
+}
+
+#[test]
+fn test_public_input_out_of_scalar_field() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_vk_json("groth16", "bls12381", None);
+		let proof = prepare_proof_json("groth16", "bls12381", None);
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		assert_err_ignore_postinfo!(
+			ZKSnarks::verify(
+				RuntimeOrigin::signed(BOB_ACCOUNT_ID),
+				CIRCUIT_ID,
+				proof.as_bytes().into(),
+				prepare_out_of_field_public_inputs_json().as_bytes().into()
+			),
+			Error::<Test>::MalformedPublicInputs
+		);
+		assert_eq!(zk_events().len(), 1);
+	});
+}
+
+#[test]
+fn test_circuit_already_registered() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_vk_json("groth16", "bls12381", None);
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		assert_err!(
+			ZKSnarks::register_circuit(
+				RuntimeOrigin::root(),
+				CIRCUIT_ID,
+				vk.as_bytes().into(),
+				None
+			),
+			Error::<Test>::CircuitAlreadyRegistered
+		);
+
+		let events = zk_events();
+		assert_eq!(events.len(), 1);
+		assert_eq!(events[0], setup_completed(CIRCUIT_ID, &vk));
+	});
+}
+
+#[test]
+fn test_circuits_are_independent() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_vk_json("groth16", "bls12381", None);
+		let other_vk = prepare_bn254_vk_json();
+		let proof = prepare_proof_json("groth16", "bls12381", None);
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			OTHER_CIRCUIT_ID,
+			other_vk.as_bytes().into(),
+			None
+		));
+		assert_ok!(ZKSnarks::verify(
+			RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+			CIRCUIT_ID,
+			proof.as_bytes().into(),
+			prepare_correct_public_inputs_json().as_bytes().into()
+		));
+		assert_err_ignore_postinfo!(
+			ZKSnarks::verify(
+				RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+				OTHER_CIRCUIT_ID,
+				proof.as_bytes().into(),
+				prepare_correct_public_inputs_json().as_bytes().into()
+			),
+			Error::<Test>::CurveMismatch
+		);
+
+		let events = zk_events();
+		assert_eq!(events.len(), 4);
+		assert_eq!(events[1], setup_completed(OTHER_CIRCUIT_ID, &other_vk));
+		assert_eq!(
+			events[3],
+			verification_success(
+				ALICE_ACCOUNT_ID,
+				proof.as_bytes(),
+				&prepare_correct_public_inputs_json()
+			)
+		);
+	});
+}
+
+#[test]
+fn test_verify_unregistered_circuit() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_vk_json("groth16", "bls12381", None);
+		let proof = prepare_proof_json("groth16", "bls12381", None);
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		assert_err_ignore_postinfo!(
+			ZKSnarks::verify(
+				RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+				OTHER_CIRCUIT_ID,
+				proof.as_bytes().into(),
+				prepare_correct_public_inputs_json().as_bytes().into()
+			),
+			Error::<Test>::VerificationKeyIsNotSet
+		);
+
+		let events = zk_events();
+		assert_eq!(events.len(), 1);
+	});
+}
+
+#[test]
+fn test_could_not_create_proof() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_vk_json("groth16", "bls12381", None);
+		let proof = prepare_proof_json("groth16", "bls12381", Some("12".to_owned()));
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		assert_err_ignore_postinfo!(
+			ZKSnarks::verify(
+				RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+				CIRCUIT_ID,
+				proof.as_bytes().into(),
+				prepare_correct_public_inputs_json().as_bytes().into()
+			),
+			Error::<Test>::PointNotOnCurve
+		);
+
+		let events = zk_events();
+		assert_eq!(events.len(), 1);
+		assert_eq!(events[0], setup_completed(CIRCUIT_ID, &vk));
+	});
+}
+
+#[test]
+fn test_could_not_create_verification_key() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_vk_json("groth16", "bls12381", Some("12".to_owned()));
+
+		assert_err!(
+			ZKSnarks::register_circuit(
+				RuntimeOrigin::root(),
+				CIRCUIT_ID,
+				vk.as_bytes().into(),
+				None
+			),
+			Error::<Test>::PointNotOnCurve
+		);
+
+		assert!(!VerificationKeyStorage::<Test>::contains_key(CIRCUIT_ID));
+		assert!(zk_events().is_empty());
+	});
+}
+
+#[test]
+fn test_invalid_points_are_rejected() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_vk_json("groth16", "bls12381", None);
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		let pi_a_y = "133377702143528739575377729631360601614088262416333931136172973337607317017542609318946667454426700160620492918070";
+		// On the curve, but not in the subgroup of order r
+		let not_in_subgroup = prepare_proof_json("groth16", "bls12381", Some("4".to_owned()))
+			.replacen(pi_a_y, "1630892974828014537729259858097113969650871260980656934049590190201941782487224876496582135785777461178964897591404", 1);
+		// snarkjs encodes the point at infinity as `(0, 1, 0)`
+		let at_infinity = prepare_proof_json("groth16", "bls12381", Some("0".to_owned()))
+			.replacen(pi_a_y, "1", 1)
+			.replacen("\"1\"\n],\n\"pi_b\"", "\"0\"\n],\n\"pi_b\"", 1);
+		// `z = 2` without scaling `x` and `y` accordingly
+		let not_scaled = prepare_proof_json("groth16", "bls12381", None)
+			.replacen("\"1\"\n],\n\"pi_b\"", "\"2\"\n],\n\"pi_b\"", 1);
+		// `z` equal to the base field modulus
+		let z_out_of_base_field = prepare_proof_json("groth16", "bls12381", None).replacen(
+			"\"1\"\n],\n\"pi_b\"",
+			"\"4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559787\"\n],\n\"pi_b\"",
+			1,
+		);
+
+		for (proof, error) in [
+			(not_in_subgroup, Error::<Test>::PointNotInSubgroup),
+			(at_infinity, Error::<Test>::PointAtInfinity),
+			(not_scaled, Error::<Test>::PointNotOnCurve),
+			(z_out_of_base_field, Error::<Test>::NonCanonicalCoordinate),
+		] {
+			assert_err_ignore_postinfo!(
+				ZKSnarks::verify(
+					RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+					CIRCUIT_ID,
+					proof.as_bytes().into(),
+					prepare_correct_public_inputs_json().as_bytes().into()
+				),
+				error
+			);
+		}
+	});
+}
+
+#[test]
+fn test_jacobian_coordinates() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_vk_json("groth16", "bls12381", None);
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		// `pi_a` with `z = 2` is `(4x, 8y, 2)`, `pi_b` with `z = u` is `(-x, -uy, u)`
+		let proof = prepare_proof_json("groth16", "bls12381", Some("2189063583025016145560093490529263290457964041771136902735858334786599239658631362978947487164584365388028751409357".to_owned()))
+			.replacen("133377702143528739575377729631360601614088262416333931136172973337607317017542609318946667454426700160620492918070", "1067021617148229916603021837050884812912706099330671449089383786700858536140340874551573339635413601284963943344560", 1)
+			.replacen("\"1\"\n],\n\"pi_b\"", "\"2\"\n],\n\"pi_b\"", 1)
+			.replacen("3464179927623990666132434581669710292812271436336621246126774308069940684644800766694467705159555008883836001203558", "538229627597676727285355244066193863744611383602386639205283828054090965846037097748219923969460655154058271356229", 1)
+			.replacen("2546213637341159614042232103352468058136925633034122854640067781563520449770334670597953179425897845578304324932654", "1456195917880507779375557722383436098419957186904885030691990354560511200720503193844734449703117818459589947627133", 1)
+			.replacen("1727172519477219519750367293438016239792036515829871417520013243406611034907195588907593103368826194109213319586533", "1608709552654556864133663038831358765687167633553533833302139692670076873672935498325809703404354703063813928303923", 1)
+			.replacen("1608709552654556864133663038831358765687167633553533833302139692670076873672935498325809703404354703063813928303923\"\n    ],", "2275237035744447873667422532297887916764846304109136467812044892717420615583642275535094525760189469928680952973254\"\n    ],", 1)
+			.replacen("\"1\",\n    \"0\"", "\"0\",\n    \"1\"", 1);
+		assert_ok!(ZKSnarks::verify(
+			RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+			CIRCUIT_ID,
+			proof.as_bytes().into(),
+			prepare_correct_public_inputs_json().as_bytes().into()
+		));
+		assert_eq!(
+			zk_events().last(),
+			Some(&verification_success(ALICE_ACCOUNT_ID, proof.as_bytes(), &prepare_correct_public_inputs_json()))
+		);
+
+		// Any point with `z = 0` is the point at infinity
+		let at_infinity = prepare_proof_json("groth16", "bls12381", None)
+			.replacen("\"1\"\n],\n\"pi_b\"", "\"0\"\n],\n\"pi_b\"", 1);
+		assert_err_ignore_postinfo!(
+			ZKSnarks::verify(
+				RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+				CIRCUIT_ID,
+				at_infinity.as_bytes().into(),
+				prepare_correct_public_inputs_json().as_bytes().into()
+			),
+			Error::<Test>::PointAtInfinity
+		);
+	});
+}
+
+#[test]
+fn test_bn254_coordinate_out_of_base_field() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_bn254_vk_json();
+		// The BN254 base field modulus, which is below the BLS12-381 one
+		let proof = prepare_bn254_proof_json("bn128").replacen(
+			"8407441580725980188856084962231235924707878671344545471241261411953209254851",
+			"21888242871839275222246405745257275088696311157297823662689037894645226208583",
+			1,
+		);
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		assert_err_ignore_postinfo!(
+			ZKSnarks::verify(
+				RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+				CIRCUIT_ID,
+				proof.as_bytes().into(),
+				prepare_bn254_public_inputs_json("33").as_bytes().into()
+			),
+			Error::<Test>::NonCanonicalCoordinate
+		);
+	});
+}
+
+#[test]
+fn test_compact_points_at_infinity_are_rejected() {
+	new_test_ext().execute_with(|| {
+		let mut vk = compact_vk(&prepare_vk_json("groth16", "bls12381", None));
+		// `delta` follows the curve index, `alpha` and two other G2 points
+		let delta = 1 + 48 + 96 + 96;
+		vk[delta..delta + 96].fill(0);
+		vk[delta] = 0xc0;
+		assert_err!(
+			ZKSnarks::register_circuit(RuntimeOrigin::root(), CIRCUIT_ID, vk, None),
+			Error::<Test>::PointAtInfinity
+		);
+
+		let vk = prepare_vk_json("groth16", "bls12381", None);
+		let mut proof = compact_proof(&prepare_proof_json("groth16", "bls12381", None));
+		proof[1..49].fill(0);
+		proof[1] = 0xc0;
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		assert_err_ignore_postinfo!(
+			ZKSnarks::verify(
+				RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+				CIRCUIT_ID,
+				proof,
+				prepare_correct_public_inputs_json().as_bytes().into()
+			),
+			Error::<Test>::PointAtInfinity
+		);
+	});
+}
+
+#[test]
+fn test_invalid_numbers_are_rejected() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_vk_json("groth16", "bls12381", Some("12a".to_owned()));
+		assert_err!(
+			ZKSnarks::register_circuit(
+				RuntimeOrigin::root(),
+				CIRCUIT_ID,
+				vk.as_bytes().into(),
+				None
+			),
+			Error::<Test>::MalformedVerificationKey
+		);
+
+		let vk = prepare_vk_json("groth16", "bls12381", None);
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		let proof = prepare_proof_json("groth16", "bls12381", Some("9".repeat(200)));
+		assert_err_ignore_postinfo!(
+			ZKSnarks::verify(
+				RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+				CIRCUIT_ID,
+				proof.as_bytes().into(),
+				prepare_correct_public_inputs_json().as_bytes().into()
+			),
+			Error::<Test>::MalformedProof
+		);
+		let proof = prepare_proof_json("groth16", "bls12381", None);
+		assert_err_ignore_postinfo!(
+			ZKSnarks::verify(
+				RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+				CIRCUIT_ID,
+				proof.as_bytes().into(),
+				"[33]".as_bytes().into()
+			),
+			Error::<Test>::MalformedPublicInputs
+		);
+	});
+}
+
+#[test]
+fn test_bn254_verification_success() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_bn254_vk_json();
+		let proof = prepare_bn254_proof_json("bn128");
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		assert_ok!(ZKSnarks::verify(
+			RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+			CIRCUIT_ID,
+			proof.as_bytes().into(),
+			prepare_bn254_public_inputs_json("33").as_bytes().into()
+		));
+
+		let events = zk_events();
+		assert_eq!(events.len(), 3);
+		assert_eq!(events[1], proof_set(ALICE_ACCOUNT_ID, proof.as_bytes()));
+		assert_eq!(
+			events[2],
+			verification_success(
+				ALICE_ACCOUNT_ID,
+				proof.as_bytes(),
+				&prepare_bn254_public_inputs_json("33")
+			)
+		);
+	});
+}
+
+#[test]
+fn test_bn254_verification_failed() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_bn254_vk_json();
+		let proof = prepare_bn254_proof_json("bn128");
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		assert_ok!(ZKSnarks::verify(
+			RuntimeOrigin::signed(BOB_ACCOUNT_ID),
+			CIRCUIT_ID,
+			proof.as_bytes().into(),
+			prepare_bn254_public_inputs_json("34").as_bytes().into()
+		));
+
+		let events = zk_events();
+		assert_eq!(events.len(), 3);
+		assert_eq!(
+			events[2],
+			verification_failed(
+				BOB_ACCOUNT_ID,
+				proof.as_bytes(),
+				&prepare_bn254_public_inputs_json("34")
+			)
+		);
+	});
+}
+
+#[test]
+fn test_bn254_public_input_out_of_scalar_field() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_bn254_vk_json();
+		let proof = prepare_bn254_proof_json("bn128");
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		// Below the BLS12-381 scalar field modulus, but not below the BN254 one
+		assert_err_ignore_postinfo!(
+			ZKSnarks::verify(
+				RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+				CIRCUIT_ID,
+				proof.as_bytes().into(),
+				prepare_big_public_inputs_json().as_bytes().into()
+			),
+			Error::<Test>::MalformedPublicInputs
+		);
+	});
+}
+
+#[test]
+fn test_curve_mismatch() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_bn254_vk_json();
+		let proof = prepare_proof_json("groth16", "bls12381", None);
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		assert_err_ignore_postinfo!(
+			ZKSnarks::verify(
+				RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+				CIRCUIT_ID,
+				proof.as_bytes().into(),
+				prepare_bn254_public_inputs_json("33").as_bytes().into()
+			),
+			Error::<Test>::CurveMismatch
+		);
+
+		let events = zk_events();
+		assert_eq!(events.len(), 1);
+	});
+}
+
+#[test]
+fn test_plonk_verification_success() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_plonk_vk_json();
+		let proof = prepare_plonk_proof_json("bn128");
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		assert_ok!(ZKSnarks::verify(
+			RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+			CIRCUIT_ID,
+			proof.as_bytes().into(),
+			prepare_bn254_public_inputs_json("12").as_bytes().into()
+		));
+
+		let events = zk_events();
+		assert_eq!(events.len(), 3);
+		assert_eq!(events[1], proof_set(ALICE_ACCOUNT_ID, proof.as_bytes()));
+		assert_eq!(
+			events[2],
+			verification_success(
+				ALICE_ACCOUNT_ID,
+				proof.as_bytes(),
+				&prepare_bn254_public_inputs_json("12")
+			)
+		);
+	});
+}
+
+#[test]
+fn test_plonk_verification_failed() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_plonk_vk_json();
+		let proof = prepare_plonk_proof_json("bn128");
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		assert_ok!(ZKSnarks::verify(
+			RuntimeOrigin::signed(BOB_ACCOUNT_ID),
+			CIRCUIT_ID,
+			proof.as_bytes().into(),
+			prepare_bn254_public_inputs_json("13").as_bytes().into()
+		));
+
+		let events = zk_events();
+		assert_eq!(events.len(), 3);
+		assert_eq!(
+			events[2],
+			verification_failed(
+				BOB_ACCOUNT_ID,
+				proof.as_bytes(),
+				&prepare_bn254_public_inputs_json("13")
+			)
+		);
+	});
+}
+
+#[test]
+fn test_protocol_mismatch() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_plonk_vk_json();
+		let proof = prepare_bn254_proof_json("bn128");
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		assert_err_ignore_postinfo!(
+			ZKSnarks::verify(
+				RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+				CIRCUIT_ID,
+				proof.as_bytes().into(),
+				prepare_bn254_public_inputs_json("33").as_bytes().into()
+			),
+			Error::<Test>::ProtocolMismatch
+		);
+
+		let events = zk_events();
+		assert_eq!(events.len(), 1);
+	});
+}
+
+#[test]
+fn test_fflonk_verification_success() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_fflonk_vk_json();
+		let proof = prepare_fflonk_proof_json("bn128");
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		assert_ok!(ZKSnarks::verify(
+			RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+			CIRCUIT_ID,
+			proof.as_bytes().into(),
+			prepare_bn254_public_inputs_json("12").as_bytes().into()
+		));
+
+		let events = zk_events();
+		assert_eq!(events.len(), 3);
+		assert_eq!(events[1], proof_set(ALICE_ACCOUNT_ID, proof.as_bytes()));
+		assert_eq!(
+			events[2],
+			verification_success(
+				ALICE_ACCOUNT_ID,
+				proof.as_bytes(),
+				&prepare_bn254_public_inputs_json("12")
+			)
+		);
+	});
+}
+
+#[test]
+fn test_fflonk_verification_failed() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_fflonk_vk_json();
+		let proof = prepare_fflonk_proof_json("bn128");
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		assert_ok!(ZKSnarks::verify(
+			RuntimeOrigin::signed(BOB_ACCOUNT_ID),
+			CIRCUIT_ID,
+			proof.as_bytes().into(),
+			prepare_bn254_public_inputs_json("13").as_bytes().into()
+		));
+
+		let events = zk_events();
+		assert_eq!(events.len(), 3);
+		assert_eq!(
+			events[2],
+			verification_failed(
+				BOB_ACCOUNT_ID,
+				proof.as_bytes(),
+				&prepare_bn254_public_inputs_json("13")
+			)
+		);
+	});
+}
+
+#[test]
+fn test_fflonk_and_plonk_mismatch() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_fflonk_vk_json();
+		let proof = prepare_plonk_proof_json("bn128");
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		assert_err_ignore_postinfo!(
+			ZKSnarks::verify(
+				RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+				CIRCUIT_ID,
+				proof.as_bytes().into(),
+				prepare_bn254_public_inputs_json("12").as_bytes().into()
+			),
+			Error::<Test>::ProtocolMismatch
+		);
+	});
+}
+
+#[test]
+fn test_batch_verification() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_vk_json("groth16", "bls12381", None);
+		let proof = prepare_proof_json("groth16", "bls12381", None);
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		assert_ok!(ZKSnarks::verify_batch(
+			RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+			CIRCUIT_ID,
+			vec![
+				(proof.as_bytes().into(), prepare_correct_public_inputs_json().as_bytes().into()),
+				(proof.as_bytes().into(), prepare_incorrect_public_inputs_json().as_bytes().into()),
+				(proof.as_bytes().into(), prepare_correct_public_inputs_json().as_bytes().into()),
+			]
+		));
+
+		let events = zk_events();
+		assert_eq!(events.len(), 2);
+		assert_eq!(
+			events[1],
+			batch_verification_completed(
+				ALICE_ACCOUNT_ID,
+				&[
+					(proof.as_bytes(), &prepare_correct_public_inputs_json()),
+					(proof.as_bytes(), &prepare_incorrect_public_inputs_json()),
+					(proof.as_bytes(), &prepare_correct_public_inputs_json()),
+				],
+				vec![true, false, true]
+			)
+		);
+		// The same proof was submitted three times, the last submission is recorded
+		let records: Vec<_> = VerificationRecords::<Test>::iter_prefix_values(CIRCUIT_ID).collect();
+		assert_eq!(records.len(), 1);
+		assert_eq!(records[0].inputs_hash, inputs_hash(&prepare_correct_public_inputs_json()));
+		assert!(records[0].result);
+	});
+}
+
+#[test]
+fn test_verification_records() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_vk_json("groth16", "bls12381", None);
+		let proof = prepare_proof_json("groth16", "bls12381", None);
+		let proof_hash = sp_io::hashing::blake2_256(proof.as_bytes());
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		assert_eq!(VerificationRecords::<Test>::get(CIRCUIT_ID, proof_hash), None);
+		assert_ok!(ZKSnarks::verify(
+			RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+			CIRCUIT_ID,
+			proof.as_bytes().into(),
+			prepare_correct_public_inputs_json().as_bytes().into()
+		));
+		assert_eq!(
+			VerificationRecords::<Test>::get(CIRCUIT_ID, proof_hash),
+			Some(VerificationRecord {
+				who: ALICE_ACCOUNT_ID,
+				block: 1,
+				inputs_hash: inputs_hash(&prepare_correct_public_inputs_json()),
+				result: true
+			})
+		);
+
+		System::set_block_number(2);
+		assert_ok!(ZKSnarks::verify(
+			RuntimeOrigin::signed(BOB_ACCOUNT_ID),
+			CIRCUIT_ID,
+			proof.as_bytes().into(),
+			prepare_incorrect_public_inputs_json().as_bytes().into()
+		));
+		assert_eq!(
+			VerificationRecords::<Test>::get(CIRCUIT_ID, proof_hash),
+			Some(VerificationRecord {
+				who: BOB_ACCOUNT_ID,
+				block: 2,
+				inputs_hash: inputs_hash(&prepare_incorrect_public_inputs_json()),
+				result: false
+			})
+		);
+		assert_eq!(VerificationRecords::<Test>::iter_prefix(OTHER_CIRCUIT_ID).count(), 0);
+	});
+}
+
+#[test]
+fn test_verification_records_are_pruned() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_vk_json("groth16", "bls12381", None);
+		let proof = prepare_proof_json("groth16", "bls12381", None);
+		let other_proof = prepare_proof_json("groth16", "bls12381", None).replace('\n', "");
+		let lifetime = VerificationRecordLifetime::get();
+		let verify_at = |block, proof: &str| {
+			System::set_block_number(block);
+			assert_ok!(ZKSnarks::verify(
+				RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+				CIRCUIT_ID,
+				proof.as_bytes().into(),
+				prepare_correct_public_inputs_json().as_bytes().into()
+			));
+		};
+		let recorded = |proof: &str| {
+			VerificationRecords::<Test>::contains_key(
+				CIRCUIT_ID,
+				sp_io::hashing::blake2_256(proof.as_bytes()),
+			)
+		};
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		verify_at(1, &proof);
+		verify_at(3, &other_proof);
+		// Submitted again, so it expires later
+		verify_at(5, &proof);
+
+		ZKSnarks::on_initialize(1 + lifetime);
+		assert!(recorded(&proof));
+		assert!(recorded(&other_proof));
+		ZKSnarks::on_initialize(3 + lifetime);
+		assert!(recorded(&proof));
+		assert!(!recorded(&other_proof));
+		ZKSnarks::on_initialize(5 + lifetime);
+		assert!(!recorded(&proof));
+		assert_eq!(VerificationRecordsByBlock::<Test>::iter().count(), 0);
+	});
+}
+
+#[test]
+fn test_migration_to_v2_removes_proofs() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(1).put::<ZKSnarks>();
+		migrations::v2::ProofStorage::<Test>::insert(CIRCUIT_ID, vec![1, 2, 3]);
+		migrations::v2::ProofStorage::<Test>::insert(OTHER_CIRCUIT_ID, vec![4, 5, 6]);
+
+		migrations::v2::migrate::<Test>();
+
+		assert_eq!(migrations::v2::ProofStorage::<Test>::iter().count(), 0);
+		assert_eq!(ZKSnarks::on_chain_storage_version(), StorageVersion::new(2));
+	});
+}
+
+#[test]
+fn test_migration_to_v2_removes_single_circuit_values() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_vk_json("groth16", "bls12381", None);
+		StorageVersion::new(1).put::<ZKSnarks>();
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		migrations::v2::VerificationKeyStorage::<Test>::put(vk.as_bytes().to_vec());
+		migrations::v2::PublicInputStorage::<Test>::put(b"[\"1\"]".to_vec());
+
+		migrations::v2::migrate::<Test>();
+
+		assert!(!migrations::v2::VerificationKeyStorage::<Test>::exists());
+		assert!(!migrations::v2::PublicInputStorage::<Test>::exists());
+		assert!(VerificationKeyStorage::<Test>::contains_key(CIRCUIT_ID));
+		assert_eq!(ZKSnarks::on_chain_storage_version(), StorageVersion::new(2));
+	});
+}
+
+#[test]
+fn test_migration_to_v3_decodes_keys_again() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_vk_json("groth16", "bls12381", None);
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		let decoded = DecodedVerificationKeyStorage::<Test>::get(CIRCUIT_ID);
+		StorageVersion::new(1).put::<ZKSnarks>();
+		// Laid out the way version 2 stored it
+		let v2_key = (SupportedCurve::Bls12381, vec![0u8; 96], vec![0u8; 192], vec![0u8; 192]);
+		frame_support::storage::unhashed::put_raw(
+			&DecodedVerificationKeyStorage::<Test>::hashed_key_for(CIRCUIT_ID),
+			&v2_key.encode(),
+		);
+
+		ZKSnarks::on_runtime_upgrade();
+
+		assert!(decoded.is_some());
+		assert_eq!(DecodedVerificationKeyStorage::<Test>::get(CIRCUIT_ID), decoded);
+		assert_eq!(ZKSnarks::on_chain_storage_version(), StorageVersion::new(3));
+		assert_ok!(ZKSnarks::verify(
+			RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+			CIRCUIT_ID,
+			prepare_proof_json("groth16", "bls12381", None).as_bytes().into(),
+			prepare_correct_public_inputs_json().as_bytes().into()
+		));
+	});
+}
+
+#[test]
+fn test_bn254_batch_verification() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_bn254_vk_json();
+		let proof = prepare_bn254_proof_json("bn128");
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		assert_ok!(ZKSnarks::verify_batch(
+			RuntimeOrigin::signed(BOB_ACCOUNT_ID),
+			CIRCUIT_ID,
+			vec![
+				(proof.as_bytes().into(), prepare_bn254_public_inputs_json("33").as_bytes().into()),
+				(proof.as_bytes().into(), prepare_bn254_public_inputs_json("33").as_bytes().into()),
+			]
+		));
+
+		let events = zk_events();
+		assert_eq!(events.len(), 2);
+		assert_eq!(
+			events[1],
+			batch_verification_completed(
+				BOB_ACCOUNT_ID,
+				&[
+					(proof.as_bytes(), &prepare_bn254_public_inputs_json("33")),
+					(proof.as_bytes(), &prepare_bn254_public_inputs_json("33"))
+				],
+				vec![true, true]
+			)
+		);
+	});
+}
+
+#[test]
+fn test_empty_batch() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_vk_json("groth16", "bls12381", None);
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		assert_err_ignore_postinfo!(
+			ZKSnarks::verify_batch(RuntimeOrigin::signed(ALICE_ACCOUNT_ID), CIRCUIT_ID, vec![]),
+			Error::<Test>::EmptyBatch
+		);
+	});
+}
+
+#[test]
+fn test_too_large_batch() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_vk_json("groth16", "bls12381", None);
+		let proof = prepare_proof_json("groth16", "bls12381", None);
+		let proofs = (0..<Test as Config>::MaxBatchSize::get() + 1)
+			.map(|_| {
+				(proof.as_bytes().into(), prepare_correct_public_inputs_json().as_bytes().into())
+			})
+			.collect();
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		assert_err_ignore_postinfo!(
+			ZKSnarks::verify_batch(RuntimeOrigin::signed(ALICE_ACCOUNT_ID), CIRCUIT_ID, proofs),
+			Error::<Test>::TooLargeBatch
+		);
+	});
+}
+
+#[test]
+fn test_batch_of_plonk_proofs() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_plonk_vk_json();
+		let proof = prepare_plonk_proof_json("bn128");
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		assert_err_ignore_postinfo!(
+			ZKSnarks::verify_batch(
+				RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+				CIRCUIT_ID,
+				vec![(
+					proof.as_bytes().into(),
+					prepare_bn254_public_inputs_json("12").as_bytes().into()
+				)]
+			),
+			Error::<Test>::ProtocolMismatch
+		);
+	});
+}
+
+#[test]
+fn test_batch_curve_mismatch() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_bn254_vk_json();
+		let proof = prepare_proof_json("groth16", "bls12381", None);
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		assert_err_ignore_postinfo!(
+			ZKSnarks::verify_batch(
+				RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+				CIRCUIT_ID,
+				vec![(
+					proof.as_bytes().into(),
+					prepare_correct_public_inputs_json().as_bytes().into()
+				)]
+			),
+			Error::<Test>::CurveMismatch
+		);
+	});
+}
+
+#[test]
+fn test_register_circuit_stores_decoded_key() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_vk_json("groth16", "bls12381", None);
+		let plonk_vk = prepare_plonk_vk_json();
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			OTHER_CIRCUIT_ID,
+			plonk_vk.as_bytes().into(),
+			None
+		));
+
+		let decoded = DecodedVerificationKeyStorage::<Test>::get(CIRCUIT_ID).unwrap();
+		assert_eq!(decoded.curve(), SupportedCurve::Bls12381);
+		assert_eq!(decoded.public_inputs_len(), 1);
+		assert!(!DecodedVerificationKeyStorage::<Test>::contains_key(OTHER_CIRCUIT_ID));
+	});
+}
+
+#[test]
+fn test_verification_does_not_parse_json_key() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_vk_json("groth16", "bls12381", None);
+		let proof = prepare_proof_json("groth16", "bls12381", None);
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		let malformed_vk = VerificationKeyDef::<Test>::try_from(b"{}".to_vec()).unwrap();
+		VerificationKeyStorage::<Test>::insert(CIRCUIT_ID, malformed_vk);
+		assert_ok!(ZKSnarks::verify(
+			RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+			CIRCUIT_ID,
+			proof.as_bytes().into(),
+			prepare_correct_public_inputs_json().as_bytes().into()
+		));
+
+		let events = zk_events();
+		assert_eq!(events.len(), 3);
+		assert_eq!(
+			events[2],
+			verification_success(
+				ALICE_ACCOUNT_ID,
+				proof.as_bytes(),
+				&prepare_correct_public_inputs_json()
+			)
+		);
+	});
+}
+
+#[test]
+fn test_verification_without_decoded_key() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_vk_json("groth16", "bls12381", None);
+		let proof = prepare_proof_json("groth16", "bls12381", None);
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		DecodedVerificationKeyStorage::<Test>::remove(CIRCUIT_ID);
+		assert_ok!(ZKSnarks::verify(
+			RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+			CIRCUIT_ID,
+			proof.as_bytes().into(),
+			prepare_correct_public_inputs_json().as_bytes().into()
+		));
+		assert_ok!(ZKSnarks::verify_batch(
+			RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+			CIRCUIT_ID,
+			vec![(proof.as_bytes().into(), prepare_correct_public_inputs_json().as_bytes().into())]
+		));
+
+		let events = zk_events();
+		assert_eq!(events.len(), 4);
+		assert_eq!(
+			events[2],
+			verification_success(
+				ALICE_ACCOUNT_ID,
+				proof.as_bytes(),
+				&prepare_correct_public_inputs_json()
+			)
+		);
+		assert_eq!(
+			events[3],
+			batch_verification_completed(
+				ALICE_ACCOUNT_ID,
+				&[(proof.as_bytes(), &prepare_correct_public_inputs_json())],
+				vec![true]
+			)
+		);
+	});
+}
+
+#[test]
+fn test_compact_verification_success() {
+	new_test_ext().execute_with(|| {
+		let vk = compact_vk(&prepare_vk_json("groth16", "bls12381", None));
+		let proof = compact_proof(&prepare_proof_json("groth16", "bls12381", None));
+		// curve index and three compressed points
+		assert_eq!(proof.len(), 1 + 48 + 96 + 48);
+
+		assert_ok!(ZKSnarks::register_circuit(RuntimeOrigin::root(), CIRCUIT_ID, vk, None));
+		assert_ok!(ZKSnarks::verify(
+			RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+			CIRCUIT_ID,
+			proof.clone(),
+			prepare_correct_public_inputs_json().as_bytes().into()
+		));
+		assert_ok!(ZKSnarks::verify(
+			RuntimeOrigin::signed(BOB_ACCOUNT_ID),
+			CIRCUIT_ID,
+			proof.clone(),
+			prepare_incorrect_public_inputs_json().as_bytes().into()
+		));
+
+		let events = zk_events();
+		assert_eq!(events.len(), 5);
+		assert_eq!(
+			events[2],
+			verification_success(ALICE_ACCOUNT_ID, &proof, &prepare_correct_public_inputs_json())
+		);
+		assert_eq!(
+			events[4],
+			verification_failed(BOB_ACCOUNT_ID, &proof, &prepare_incorrect_public_inputs_json())
+		);
+	});
+}
+
+#[test]
+fn test_bn254_compact_proof_with_json_key() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_bn254_vk_json();
+		let proof = compact_proof(&prepare_bn254_proof_json("bn128"));
+		assert_eq!(proof.len(), 1 + 32 + 64 + 32);
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		assert_ok!(ZKSnarks::verify_batch(
+			RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+			CIRCUIT_ID,
+			vec![
+				(proof.clone(), prepare_bn254_public_inputs_json("33").as_bytes().into()),
+				(proof.clone(), prepare_bn254_public_inputs_json("34").as_bytes().into()),
+			]
+		));
+
+		let events = zk_events();
+		assert_eq!(events.len(), 2);
+		assert_eq!(
+			events[1],
+			batch_verification_completed(
+				ALICE_ACCOUNT_ID,
+				&[
+					(&proof, &prepare_bn254_public_inputs_json("33")),
+					(&proof, &prepare_bn254_public_inputs_json("34"))
+				],
+				vec![true, false]
+			)
+		);
+	});
+}
+
+#[test]
+fn test_compact_key_is_equivalent_to_json_key() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_bn254_vk_json();
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			OTHER_CIRCUIT_ID,
+			compact_vk(&vk),
+			None
+		));
+
+		assert_eq!(
+			DecodedVerificationKeyStorage::<Test>::get(CIRCUIT_ID),
+			DecodedVerificationKeyStorage::<Test>::get(OTHER_CIRCUIT_ID)
+		);
+	});
+}
+
+#[test]
+fn test_malformed_compact_proof() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_vk_json("groth16", "bls12381", None);
+		let mut proof = compact_proof(&prepare_proof_json("groth16", "bls12381", None));
+		// `a` loses the flag marking it as a compressed point
+		proof[1] &= 0x7f;
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		assert_err_ignore_postinfo!(
+			ZKSnarks::verify(
+				RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+				CIRCUIT_ID,
+				proof.clone(),
+				prepare_correct_public_inputs_json().as_bytes().into()
+			),
+			Error::<Test>::NonCanonicalCoordinate
+		);
+		proof.pop();
+		assert_err_ignore_postinfo!(
+			ZKSnarks::verify(
+				RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+				CIRCUIT_ID,
+				proof,
+				prepare_correct_public_inputs_json().as_bytes().into()
+			),
+			Error::<Test>::MalformedProof
+		);
+	});
+}
+
+#[test]
+fn test_compact_proof_curve_mismatch() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_bn254_vk_json();
+		let proof = compact_proof(&prepare_proof_json("groth16", "bls12381", None));
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		assert_err_ignore_postinfo!(
+			ZKSnarks::verify(
+				RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+				CIRCUIT_ID,
+				proof,
+				prepare_bn254_public_inputs_json("33").as_bytes().into()
+			),
+			Error::<Test>::CurveMismatch
+		);
+	});
+}
+
+#[test]
+fn test_nullifier_prevents_replay() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_bn254_vk_json();
+		let proof = prepare_bn254_proof_json("bn128");
+		let mut nullifier = [0u8; 32];
+		nullifier[31] = 33;
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			Some(0)
+		));
+		assert_ok!(ZKSnarks::verify(
+			RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+			CIRCUIT_ID,
+			proof.as_bytes().into(),
+			prepare_bn254_public_inputs_json("33").as_bytes().into()
+		));
+		assert!(NullifierStorage::<Test>::contains_key(CIRCUIT_ID, nullifier));
+		assert_err_ignore_postinfo!(
+			ZKSnarks::verify(
+				RuntimeOrigin::signed(BOB_ACCOUNT_ID),
+				CIRCUIT_ID,
+				proof.as_bytes().into(),
+				prepare_bn254_public_inputs_json("0x21").as_bytes().into()
+			),
+			Error::<Test>::NullifierAlreadyUsed
+		);
+		assert_err_ignore_postinfo!(
+			ZKSnarks::verify_batch(
+				RuntimeOrigin::signed(BOB_ACCOUNT_ID),
+				CIRCUIT_ID,
+				vec![(
+					proof.as_bytes().into(),
+					prepare_bn254_public_inputs_json("33").as_bytes().into()
+				)]
+			),
+			Error::<Test>::NullifierAlreadyUsed
+		);
+
+		let events = zk_events();
+		assert_eq!(events.len(), 3);
+		assert_eq!(
+			events[2],
+			verification_success(
+				ALICE_ACCOUNT_ID,
+				proof.as_bytes(),
+				&prepare_bn254_public_inputs_json("33")
+			)
+		);
+	});
+}
+
+#[test]
+fn test_failed_verification_does_not_use_nullifier() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_bn254_vk_json();
+		let proof = prepare_bn254_proof_json("bn128");
+		let mut nullifier = [0u8; 32];
+		nullifier[31] = 34;
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			Some(0)
+		));
+		for _ in 0..2 {
+			assert_ok!(ZKSnarks::verify(
+				RuntimeOrigin::signed(BOB_ACCOUNT_ID),
+				CIRCUIT_ID,
+				proof.as_bytes().into(),
+				prepare_bn254_public_inputs_json("34").as_bytes().into()
+			));
+		}
+
+		assert!(!NullifierStorage::<Test>::contains_key(CIRCUIT_ID, nullifier));
+		let events = zk_events();
+		assert_eq!(events.len(), 5);
+		assert_eq!(
+			events[4],
+			verification_failed(
+				BOB_ACCOUNT_ID,
+				proof.as_bytes(),
+				&prepare_bn254_public_inputs_json("34")
+			)
+		);
+	});
+}
+
+#[test]
+fn test_nullifier_repeated_in_batch() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_bn254_vk_json();
+		let proof: Vec<u8> = prepare_bn254_proof_json("bn128").into();
+		let inputs: Vec<u8> = prepare_bn254_public_inputs_json("33").into();
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			Some(0)
+		));
+		assert_err_ignore_postinfo!(
+			ZKSnarks::verify_batch(
+				RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+				CIRCUIT_ID,
+				vec![(proof.clone(), inputs.clone()), (proof, inputs)]
+			),
+			Error::<Test>::NullifierAlreadyUsed
+		);
+		assert_eq!(NullifierStorage::<Test>::iter_prefix(CIRCUIT_ID).count(), 0);
+	});
+}
+
+#[test]
+fn test_nullifiers_are_per_circuit() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_bn254_vk_json();
+		let proof = prepare_bn254_proof_json("bn128");
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			Some(0)
+		));
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			OTHER_CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		for circuit_id in [CIRCUIT_ID, OTHER_CIRCUIT_ID, OTHER_CIRCUIT_ID] {
+			assert_ok!(ZKSnarks::verify(
+				RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+				circuit_id,
+				proof.as_bytes().into(),
+				prepare_bn254_public_inputs_json("33").as_bytes().into()
+			));
+		}
+
+		assert_eq!(NullifierStorage::<Test>::iter_prefix(CIRCUIT_ID).count(), 1);
+		assert_eq!(NullifierStorage::<Test>::iter_prefix(OTHER_CIRCUIT_ID).count(), 0);
+	});
+}
+
+#[test]
+fn test_invalid_nullifier_index() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_bn254_vk_json();
+
+		assert_err!(
+			ZKSnarks::register_circuit(
+				RuntimeOrigin::root(),
+				CIRCUIT_ID,
+				vk.as_bytes().into(),
+				Some(1)
+			),
+			Error::<Test>::InvalidNullifierIndex
+		);
+		assert!(!VerificationKeyStorage::<Test>::contains_key(CIRCUIT_ID));
+		assert!(!NullifierIndexStorage::<Test>::contains_key(CIRCUIT_ID));
+		assert_eq!(zk_events().len(), 0);
+	});
+}
+
+#[test]
+fn test_dry_run_verification() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_bn254_vk_json();
+		let proof = prepare_bn254_proof_json("bn128");
+
+		assert_eq!(ZKSnarks::verification_key(CIRCUIT_ID), None);
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			Some(0)
+		));
+		assert_eq!(ZKSnarks::verification_key(CIRCUIT_ID), Some(vk.as_bytes().into()));
+		for _ in 0..2 {
+			assert_eq!(
+				ZKSnarks::dry_run_verify(
+					CIRCUIT_ID,
+					proof.as_bytes().into(),
+					prepare_bn254_public_inputs_json("33").as_bytes().into()
+				),
+				Ok(true)
+			);
+		}
+		assert_eq!(
+			ZKSnarks::dry_run_verify(
+				CIRCUIT_ID,
+				proof.as_bytes().into(),
+				prepare_bn254_public_inputs_json("34").as_bytes().into()
+			),
+			Ok(false)
+		);
+		assert_eq!(
+			ZKSnarks::dry_run_verify(
+				OTHER_CIRCUIT_ID,
+				proof.as_bytes().into(),
+				prepare_bn254_public_inputs_json("33").as_bytes().into()
+			),
+			Err(Error::<Test>::VerificationKeyIsNotSet.into())
+		);
+
+		assert_eq!(VerificationRecords::<Test>::iter_prefix(CIRCUIT_ID).count(), 0);
+		assert_eq!(NullifierStorage::<Test>::iter_prefix(CIRCUIT_ID).count(), 0);
+		assert_eq!(zk_events().len(), 1);
+	});
+}
+
+#[test]
+fn test_on_proof_verified_hook() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_bn254_vk_json();
+		let proof: Vec<u8> = prepare_bn254_proof_json("bn128").into();
+		let mut input = [0u8; 32];
+		input[31] = 33;
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		assert_ok!(ZKSnarks::verify(
+			RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+			CIRCUIT_ID,
+			proof.clone(),
+			prepare_bn254_public_inputs_json("33").as_bytes().into()
+		));
+		assert_ok!(ZKSnarks::verify(
+			RuntimeOrigin::signed(BOB_ACCOUNT_ID),
+			CIRCUIT_ID,
+			proof.clone(),
+			prepare_bn254_public_inputs_json("34").as_bytes().into()
+		));
+		assert_ok!(ZKSnarks::verify_batch(
+			RuntimeOrigin::signed(BOB_ACCOUNT_ID),
+			CIRCUIT_ID,
+			vec![
+				(proof.clone(), prepare_bn254_public_inputs_json("34").as_bytes().into()),
+				(proof, prepare_bn254_public_inputs_json("33").as_bytes().into()),
+			]
+		));
+
+		assert_eq!(
+			verified_statements(),
+			vec![
+				(Some(ALICE_ACCOUNT_ID), CIRCUIT_ID, vec![input]),
+				(Some(BOB_ACCOUNT_ID), CIRCUIT_ID, vec![input])
+			]
+		);
+	});
+}
+
+#[test]
+fn test_on_proof_verified_hook_rejection() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_bn254_vk_json();
+		let proof = prepare_bn254_proof_json("bn128");
+		REJECT_VERIFIED.with(|reject| *reject.borrow_mut() = true);
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			Some(0)
+		));
+		assert_err_ignore_postinfo!(
+			ZKSnarks::verify(
+				RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+				CIRCUIT_ID,
+				proof.as_bytes().into(),
+				prepare_bn254_public_inputs_json("33").as_bytes().into()
+			),
+			DispatchError::Other("rejected")
+		);
+		assert_ok!(ZKSnarks::verify(
+			RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+			CIRCUIT_ID,
+			proof.as_bytes().into(),
+			prepare_bn254_public_inputs_json("34").as_bytes().into()
+		));
+
+		assert_eq!(NullifierStorage::<Test>::iter_prefix(CIRCUIT_ID).count(), 0);
+		assert!(verified_statements().is_empty());
+	});
+}
+
+#[test]
+fn test_zk_verifier() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_vk_json("groth16", "bls12381", None);
+		let proof = prepare_proof_json("groth16", "bls12381", None);
+
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		assert_eq!(
+			<ZKSnarks as ZkVerifier>::verify(
+				CIRCUIT_ID,
+				proof.as_bytes().into(),
+				prepare_correct_public_inputs_json().as_bytes().into()
+			),
+			Ok(true)
+		);
+		assert_eq!(
+			<ZKSnarks as ZkVerifier>::verify(
+				CIRCUIT_ID,
+				proof.as_bytes().into(),
+				prepare_incorrect_public_inputs_json().as_bytes().into()
+			),
+			Ok(false)
+		);
+		assert_eq!(
+			<ZKSnarks as ZkVerifier>::verify(
+				CIRCUIT_ID,
+				vec![],
+				prepare_correct_public_inputs_json().as_bytes().into()
+			),
+			Err(Error::<Test>::ProofIsEmpty.into())
+		);
+
+		assert_eq!(VerificationRecords::<Test>::iter_prefix(CIRCUIT_ID).count(), 0);
+		assert!(verified_statements().is_empty());
+		assert_eq!(zk_events().len(), 1);
+	});
+}
+
+#[test]
+fn test_check_zk_proof() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_vk_json("groth16", "bls12381", None);
+		let proof = prepare_proof_json("groth16", "bls12381", None);
+		// `z` of `pi_a` is 2, without `x` and `y` scaled accordingly
+		let not_scaled = proof.replacen("\"1\"\n],\n\"pi_b\"", "\"2\"\n],\n\"pi_b\"", 1);
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+
+		// The pairing check is left to the call, so invalid proofs pass as long as they are well
+		// formed
+		assert_ok!(validate(verify_call(
+			CIRCUIT_ID,
+			&proof,
+			&prepare_correct_public_inputs_json()
+		)));
+		assert_ok!(validate(verify_call(
+			CIRCUIT_ID,
+			&proof,
+			&prepare_incorrect_public_inputs_json()
+		)));
+		for (circuit_id, proof, public_inputs, error) in [
+			(CIRCUIT_ID, "", "[\"12\"]", Error::<Test>::ProofIsEmpty),
+			(CIRCUIT_ID, proof.as_str(), "[12]", Error::<Test>::MalformedPublicInputs),
+			(CIRCUIT_ID, proof.as_str(), "[\"12\",\"13\"]", Error::<Test>::PublicInputsMismatch),
+			(CIRCUIT_ID, not_scaled.as_str(), "[\"12\"]", Error::<Test>::PointNotOnCurve),
+			(OTHER_CIRCUIT_ID, proof.as_str(), "[\"12\"]", Error::<Test>::VerificationKeyIsNotSet),
+		] {
+			assert_eq!(
+				validate(verify_call(circuit_id, proof, public_inputs)),
+				Err(invalid_transaction(error))
+			);
+		}
+		// Other calls are not checked
+		assert_ok!(validate(RuntimeCall::System(frame_system::Call::remark { remark: vec![] })));
+		assert_eq!(VerificationRecords::<Test>::iter_prefix(CIRCUIT_ID).count(), 0);
+	});
+}
+
+#[test]
+fn test_check_zk_proof_batch() {
+	new_test_ext().execute_with(|| {
+		let vk = prepare_vk_json("groth16", "bls12381", None);
+		let proof = prepare_proof_json("groth16", "bls12381", None);
+		let inputs = prepare_correct_public_inputs_json();
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
+		let batch_call = |proofs: &[&str]| {
+			let proofs =
+				proofs.iter().map(|proof| (proof.as_bytes().into(), inputs.as_bytes().into()));
+			RuntimeCall::ZKSnarks(Call::verify_batch {
+				circuit_id: CIRCUIT_ID,
+				proofs: proofs.collect(),
+			})
+		};
+
+		assert_ok!(validate(batch_call(&[proof.as_str(), proof.as_str()])));
+		assert_eq!(
+			validate(batch_call(&[proof.as_str(), ""])),
+			Err(invalid_transaction(Error::<Test>::ProofIsEmpty))
+		);
+		assert_eq!(validate(batch_call(&[])), Err(invalid_transaction(Error::<Test>::EmptyBatch)));
+		assert_eq!(
+			validate(batch_call(&[proof.as_str(); 5])),
+			Err(invalid_transaction(Error::<Test>::TooLargeBatch))
+		);
 	});
 }
 
 #[test]
-fn test_not_supported_proof_protocol() {
-	let proof = prepare_proof_json("-", "bls12381", None);
-
+fn test_set_unsigned_submission() {
 	new_test_ext().execute_with(|| {
+		let vk = prepare_bn254_vk_json();
+		let submission = Some(UnsignedSubmission { priority: 10, longevity: 64 });
 		assert_err!(
-			ZKSnarks::verify(RuntimeOrigin::none(), proof.as_bytes().into()),
-			Error::<Test>::NotSupportedProtocol
+			ZKSnarks::set_unsigned_submission(RuntimeOrigin::root(), CIRCUIT_ID, submission),
+			Error::<Test>::VerificationKeyIsNotSet
 		);
-		assert_eq!(zk_events().len(), 0);
-	});
-}
-
-#[test]
-fn test_not_supported_proof_curve() {
-	let proof = prepare_proof_json("groth16", "bn128", None);
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			Some(0)
+		));
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			OTHER_CIRCUIT_ID,
+			vk.as_bytes().into(),
+			None
+		));
 
-	new_test_ext().execute_with(|| {
 		assert_err!(
-			ZKSnarks::verify(RuntimeOrigin::none(), proof.as_bytes().into()),
-			Error::<Test>::NotSupportedCurve
+			ZKSnarks::set_unsigned_submission(
+				RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+				CIRCUIT_ID,
+				submission
+			),
+			DispatchError::BadOrigin
+		);
+		assert_err!(
+			ZKSnarks::set_unsigned_submission(RuntimeOrigin::root(), OTHER_CIRCUIT_ID, submission),
+			Error::<Test>::NoNullifier
+		);
+		assert_ok!(ZKSnarks::set_unsigned_submission(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			submission
+		));
+		assert_eq!(UnsignedSubmissionStorage::<Test>::get(CIRCUIT_ID), submission);
+		assert_ok!(ZKSnarks::set_unsigned_submission(RuntimeOrigin::root(), CIRCUIT_ID, None));
+		assert!(!UnsignedSubmissionStorage::<Test>::contains_key(CIRCUIT_ID));
+
+		let events = zk_events();
+		assert_eq!(events.len(), 4);
+		assert_eq!(
+			events[2],
+			Event::<Test>::UnsignedSubmissionSet { circuit_id: CIRCUIT_ID, submission }
+		);
+		assert_eq!(
+			events[3],
+			Event::<Test>::UnsignedSubmissionSet { circuit_id: CIRCUIT_ID, submission: None }
 		);
-		assert_eq!(zk_events().len(), 0);
 	});
 }
 
 #[test]
-fn test_empty_proof() {
+fn test_validate_unsigned_proof() {
 	new_test_ext().execute_with(|| {
-		assert_err!(
-			ZKSnarks::verify(RuntimeOrigin::none(), Vec::new()),
-			Error::<Test>::ProofIsEmpty
+		let vk = prepare_bn254_vk_json();
+		let proof = prepare_bn254_proof_json("bn128");
+		let submission = UnsignedSubmission { priority: 10, longevity: 64 };
+		let mut nullifier = [0u8; 32];
+		nullifier[31] = 33;
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			Some(0)
+		));
+
+		let correct = unsigned_call(CIRCUIT_ID, &proof, &prepare_bn254_public_inputs_json("33"));
+		assert_eq!(
+			validate_unsigned(&correct),
+			Err(invalid_transaction(Error::<Test>::UnsignedSubmissionNotAllowed))
+		);
+		assert_ok!(ZKSnarks::set_unsigned_submission(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			Some(submission)
+		));
+
+		let valid = validate_unsigned(&correct).expect("The proof is valid");
+		assert_eq!(valid.priority, submission.priority);
+		assert_eq!(valid.longevity, submission.longevity);
+		assert_eq!(valid.provides.len(), 1);
+		assert!(valid.propagate);
+		// Invalid proofs are rejected, as nobody pays for them
+		assert_eq!(
+			validate_unsigned(&unsigned_call(
+				CIRCUIT_ID,
+				&proof,
+				&prepare_bn254_public_inputs_json("34")
+			)),
+			Err(invalid_transaction(Error::<Test>::InvalidProof))
+		);
+		assert_eq!(
+			validate_unsigned(&unsigned_call(CIRCUIT_ID, "", "[\"33\"]")),
+			Err(invalid_transaction(Error::<Test>::ProofIsEmpty))
+		);
+		assert_eq!(
+			validate_unsigned(&Call::verify {
+				circuit_id: CIRCUIT_ID,
+				vec_proof: proof.as_bytes().into(),
+				pub_input: prepare_bn254_public_inputs_json("33").as_bytes().into(),
+			}),
+			Err(InvalidTransaction::Call.into())
+		);
+
+		NullifierStorage::<Test>::insert(CIRCUIT_ID, nullifier, ());
+		assert_eq!(
+			validate_unsigned(&correct),
+			Err(invalid_transaction(Error::<Test>::NullifierAlreadyUsed))
 		);
-		assert_eq!(zk_events().len(), 0);
 	});
 }
 
 #[test]
-fn test_verify_without_verification_key() {
-	let proof = prepare_proof_json("groth16", "bls12381", None);
-
+fn test_pre_dispatch_unsigned_proof() {
 	new_test_ext().execute_with(|| {
-		assert_err!(
-			ZKSnarks::verify(RuntimeOrigin::none(), proof.as_bytes().into()),
-			Error::<Test>::VerificationKeyIsNotSet
+		let vk = prepare_bn254_vk_json();
+		let proof = prepare_bn254_proof_json("bn128");
+		let mut nullifier = [0u8; 32];
+		nullifier[31] = 33;
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			Some(0)
+		));
+
+		let correct = unsigned_call(CIRCUIT_ID, &proof, &prepare_bn254_public_inputs_json("33"));
+		assert_eq!(
+			pre_dispatch(&correct),
+			Err(invalid_transaction(Error::<Test>::UnsignedSubmissionNotAllowed))
+		);
+		assert_ok!(ZKSnarks::set_unsigned_submission(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			Some(UnsignedSubmission { priority: 10, longevity: 64 })
+		));
+		assert_ok!(pre_dispatch(&correct));
+
+		// The pairing check is left to the dispatch, which verifies the proof anyway
+		let invalid = unsigned_call(CIRCUIT_ID, &proof, &prepare_bn254_public_inputs_json("34"));
+		assert_eq!(
+			validate_unsigned(&invalid),
+			Err(invalid_transaction(Error::<Test>::InvalidProof))
+		);
+		assert_ok!(pre_dispatch(&invalid));
+		assert_err_ignore_postinfo!(
+			ZKSnarks::submit_proof_unsigned(
+				RuntimeOrigin::none(),
+				CIRCUIT_ID,
+				proof.as_bytes().into(),
+				prepare_bn254_public_inputs_json("34").as_bytes().into()
+			),
+			Error::<Test>::InvalidProof
+		);
+		assert_eq!(
+			pre_dispatch(&unsigned_call(CIRCUIT_ID, "", "[\"33\"]")),
+			Err(invalid_transaction(Error::<Test>::ProofIsEmpty))
+		);
+
+		NullifierStorage::<Test>::insert(CIRCUIT_ID, nullifier, ());
+		assert_eq!(
+			pre_dispatch(&correct),
+			Err(invalid_transaction(Error::<Test>::NullifierAlreadyUsed))
 		);
-		assert_eq!(zk_events().len(), 0);
 	});
 }
 
 #[test]
-fn test_verification_success() {
+fn test_submit_proof_unsigned() {
 	new_test_ext().execute_with(|| {
-		let vk = prepare_vk_json("groth16", "bls12381", None);
-		let proof = prepare_proof_json("groth16", "bls12381", None);
+		let vk = prepare_bn254_vk_json();
+		let proof = prepare_bn254_proof_json("bn128");
+		let inputs = prepare_bn254_public_inputs_json("33");
+		let mut nullifier = [0u8; 32];
+		nullifier[31] = 33;
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.as_bytes().into(),
+			Some(0)
+		));
+		assert_err_ignore_postinfo!(
+			ZKSnarks::submit_proof_unsigned(
+				RuntimeOrigin::none(),
+				CIRCUIT_ID,
+				proof.as_bytes().into(),
+				inputs.as_bytes().into()
+			),
+			Error::<Test>::UnsignedSubmissionNotAllowed
+		);
+		assert_ok!(ZKSnarks::set_unsigned_submission(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			Some(UnsignedSubmission { priority: 10, longevity: 64 })
+		));
 
-		assert_ok!(ZKSnarks::setup_verification(
+		assert_err_ignore_postinfo!(
+			ZKSnarks::submit_proof_unsigned(
+				RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
+				CIRCUIT_ID,
+				proof.as_bytes().into(),
+				inputs.as_bytes().into()
+			),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(ZKSnarks::submit_proof_unsigned(
 			RuntimeOrigin::none(),
-			prepare_correct_public_inputs_json().as_bytes().into(),
-			vk.as_bytes().into()
-		));
-		assert_ok!(ZKSnarks::verify(
-			RuntimeOrigin::signed(ALICE_ACCOUNT_ID),
-			proof.as_bytes().into()
+			CIRCUIT_ID,
+			proof.as_bytes().into(),
+			inputs.as_bytes().into()
 		));
+		assert!(NullifierStorage::<Test>::contains_key(CIRCUIT_ID, nullifier));
+		assert_eq!(verified_statements(), vec![(None, CIRCUIT_ID, vec![nullifier])]);
+		assert_eq!(VerificationRecords::<Test>::iter_prefix(CIRCUIT_ID).count(), 0);
+		assert_err_ignore_postinfo!(
+			ZKSnarks::submit_proof_unsigned(
+				RuntimeOrigin::none(),
+				CIRCUIT_ID,
+				proof.as_bytes().into(),
+				inputs.as_bytes().into()
+			),
+			Error::<Test>::NullifierAlreadyUsed
+		);
 
 		let events = zk_events();
 		assert_eq!(events.len(), 3);
-		assert_eq!(events[0], Event::<Test>::VerificationSetupCompleted);
-		assert_eq!(events[1], Event::<Test>::VerificationProofSet);
-		assert_eq!(events[2], Event::<Test>::VerificationSuccess { who: ALICE_ACCOUNT_ID });
+		assert_eq!(
+			events[2],
+			Event::<Test>::UnsignedProofVerified {
+				circuit_id: CIRCUIT_ID,
+				proof_hash: sp_io::hashing::blake2_256(proof.as_bytes()),
+				inputs_hash: inputs_hash(&inputs),
+			}
+		);
 	});
 }
 
 #[test]
-fn test_verification_failed() {
+fn test_dispatch_with_proof() {
 	new_test_ext().execute_with(|| {
-		let vk = prepare_vk_json("groth16", "bls12381", None);
-		let proof = prepare_proof_json("groth16", "bls12381", None);
-
-		assert_ok!(ZKSnarks::setup_verification(
-			RuntimeOrigin::none(),
-			prepare_incorrect_public_inputs_json().as_bytes().into(),
-			vk.as_bytes().into()
+		let vk = trapdoor_vk(2);
+		let mut nullifier = [0u8; 32];
+		nullifier[31] = 33;
+		let remark = RuntimeCall::System(frame_system::Call::remark { remark: vec![] });
+		let inputs = call_inputs(nullifier, &remark);
+		let proof = trapdoor_proof(&inputs);
+		let inputs = public_inputs_json(&inputs);
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			vk.clone(),
+			Some(0)
 		));
-		assert_ok!(ZKSnarks::verify(
-			RuntimeOrigin::signed(BOB_ACCOUNT_ID),
-			proof.as_bytes().into()
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			OTHER_CIRCUIT_ID,
+			vk.clone(),
+			None
 		));
 
+		let mut other_nullifier = nullifier;
+		other_nullifier[31] = 34;
+		let invalid_inputs = public_inputs_json(&call_inputs(other_nullifier, &remark));
+		for (circuit_id, inputs, error) in [
+			(OTHER_CIRCUIT_ID, inputs.as_str(), Error::<Test>::NoNullifier),
+			(CIRCUIT_ID, invalid_inputs.as_str(), Error::<Test>::InvalidProof),
+		] {
+			assert_err_ignore_postinfo!(
+				dispatch_with_proof(circuit_id, &proof, inputs, remark.clone()),
+				error
+			);
+		}
+		// A failing call reverts the whole extrinsic, so the nullifier is not used
+		let register = RuntimeCall::ZKSnarks(Call::register_circuit {
+			circuit_id: 3,
+			vec_vk: vk,
+			nullifier_index: None,
+		});
+		let register_inputs = call_inputs(nullifier, &register);
+		assert_err_ignore_postinfo!(
+			dispatch_with_proof(
+				CIRCUIT_ID,
+				&trapdoor_proof(&register_inputs),
+				&public_inputs_json(&register_inputs),
+				register
+			),
+			DispatchError::BadOrigin
+		);
+		assert!(!NullifierStorage::<Test>::contains_key(CIRCUIT_ID, nullifier));
+
+		assert_ok!(dispatch_with_proof(CIRCUIT_ID, &proof, &inputs, remark.clone()));
+		assert!(NullifierStorage::<Test>::contains_key(CIRCUIT_ID, nullifier));
+		assert_err_ignore_postinfo!(
+			dispatch_with_proof(CIRCUIT_ID, &proof, &inputs, remark),
+			Error::<Test>::NullifierAlreadyUsed
+		);
+		assert!(verified_statements().is_empty());
+
 		let events = zk_events();
 		assert_eq!(events.len(), 3);
-		assert_eq!(events[0], Event::<Test>::VerificationSetupCompleted);
-		assert_eq!(events[1], Event::<Test>::VerificationProofSet);
-		assert_eq!(events[2], Event::<Test>::VerificationFailed);
+		assert_eq!(
+			events[2],
+			Event::<Test>::ProofDispatched {
+				circuit_id: CIRCUIT_ID,
+				who: ALICE_ACCOUNT_ID,
+				proof_hash: sp_io::hashing::blake2_256(&proof),
+				inputs_hash: inputs_hash(&inputs),
+			}
+		);
 	});
 }
 
 #[test]
-fn test_could_not_create_proof() {
+fn test_dispatch_with_proof_of_other_call() {
 	new_test_ext().execute_with(|| {
-		let vk = prepare_vk_json("groth16", "bls12381", None);
-		let proof = prepare_proof_json("groth16", "bls12381", Some("12".to_owned()));
-
-		assert_ok!(ZKSnarks::setup_verification(
-			RuntimeOrigin::none(),
-			prepare_correct_public_inputs_json().as_bytes().into(),
-			vk.as_bytes().into()
+		let mut nullifier = [0u8; 32];
+		nullifier[31] = 33;
+		let remark = RuntimeCall::System(frame_system::Call::remark { remark: vec![1] });
+		let other_remark = RuntimeCall::System(frame_system::Call::remark { remark: vec![2] });
+		let inputs = call_inputs(nullifier, &remark);
+		let proof = trapdoor_proof(&inputs);
+		let inputs = public_inputs_json(&inputs);
+		assert_ok!(ZKSnarks::register_circuit(
+			RuntimeOrigin::root(),
+			CIRCUIT_ID,
+			trapdoor_vk(2),
+			Some(0)
 		));
-		assert_err!(
-			ZKSnarks::verify(RuntimeOrigin::none(), proof.as_bytes().into()),
-			Error::<Test>::ProofCreationError
+
+		// The proof is valid, but it was made for another call
+		assert_ok!(ZKSnarks::check_proof(CIRCUIT_ID, proof.clone(), inputs.as_bytes().into()));
+		assert_eq!(
+			ZKSnarks::dry_run_verify(CIRCUIT_ID, proof.clone(), inputs.as_bytes().into()),
+			Ok(true)
+		);
+		assert_err_ignore_postinfo!(
+			dispatch_with_proof(CIRCUIT_ID, &proof, &inputs, other_remark),
+			Error::<Test>::CallHashMismatch
 		);
+		assert!(!NullifierStorage::<Test>::contains_key(CIRCUIT_ID, nullifier));
 
-		let events = zk_events();
-		assert_eq!(events.len(), 1);
-		assert_eq!(events[0], Event::<Test>::VerificationSetupCompleted);
+		assert_ok!(dispatch_with_proof(CIRCUIT_ID, &proof, &inputs, remark));
+		assert!(NullifierStorage::<Test>::contains_key(CIRCUIT_ID, nullifier));
 	});
 }
 
 #[test]
-fn test_could_not_create_verification_key() {
-	new_test_ext().execute_with(|| {
-		let vk = prepare_vk_json("groth16", "bls12381", Some("12".to_owned()));
-		let proof = prepare_proof_json("groth16", "bls12381", None);
+fn test_call_hash_input() {
+	let remark = RuntimeCall::System(frame_system::Call::remark { remark: vec![] });
+	let hash = sp_io::hashing::blake2_256(&remark.encode());
 
-		assert_ok!(ZKSnarks::setup_verification(
-			RuntimeOrigin::none(),
-			prepare_correct_public_inputs_json().as_bytes().into(),
-			vk.as_bytes().into()
-		));
-		assert_err!(
-			ZKSnarks::verify(RuntimeOrigin::none(), proof.as_bytes().into()),
-			Error::<Test>::VerificationKeyCreationError
-		);
+	assert_eq!(
+		ZKSnarks::call_hash_input(SupportedCurve::Bn254, &remark),
+		Bn254::scalar_to_bytes(&Bn254::scalar_from_hash(hash))
+	);
+	assert_eq!(
+		ZKSnarks::call_hash_input(SupportedCurve::Bls12381, &remark),
+		Bls12_381::scalar_to_bytes(&Bls12_381::scalar_from_hash(hash))
+	);
+}
 
-		let events = zk_events();
-		assert_eq!(events.len(), 1);
-		assert_eq!(events[0], Event::<Test>::VerificationSetupCompleted);
+#[test]
+fn test_ensure_zk_proven() {
+	type EnsureProven = EnsureZkProven<ConstU32<CIRCUIT_ID>>;
+	let mut nullifier = [0u8; 32];
+	nullifier[31] = 33;
+	let proven =
+		|circuit_id| -> RuntimeOrigin { RawOrigin::ZkProven { circuit_id, nullifier }.into() };
+
+	assert_eq!(EnsureProven::try_origin(proven(CIRCUIT_ID)).ok(), Some(nullifier));
+	assert!(EnsureProven::try_origin(proven(OTHER_CIRCUIT_ID)).is_err());
+	assert!(EnsureProven::try_origin(RuntimeOrigin::root()).is_err());
+	assert!(EnsureProven::try_origin(RuntimeOrigin::signed(ALICE_ACCOUNT_ID)).is_err());
+}
+
+/// Dispatches `dispatch_with_proof` as an extrinsic, so a failing call reverts its changes.
+fn dispatch_with_proof(
+	circuit_id: CircuitId,
+	proof: &[u8],
+	public_inputs: &str,
+	call: RuntimeCall,
+) -> DispatchResultWithPostInfo {
+	RuntimeCall::ZKSnarks(Call::dispatch_with_proof {
+		circuit_id,
+		vec_proof: proof.into(),
+		pub_input: public_inputs.as_bytes().into(),
+		call: Box::new(call),
+	})
+	.dispatch(RuntimeOrigin::signed(ALICE_ACCOUNT_ID))
+}
+
+/// The nullifier and the hash of `call`, the public inputs of a proof dispatched with it.
+fn call_inputs(nullifier: PublicInput, call: &RuntimeCall) -> Vec<PublicInput> {
+	vec![nullifier, ZKSnarks::call_hash_input(SupportedCurve::Bn254, call)]
+}
+
+/// BN254 Groth16 key in the compact format, with the toxic waste known, so a valid proof of any
+/// statement can be computed without a prover: α = 3, ICᵢ = (13 + i) * G1 and all G2 points are
+/// the generator.
+fn trapdoor_vk(n_public: u64) -> Vec<u8> {
+	let g2 = trapdoor_g2();
+	CompactVKey::Bn254(CompressedVerificationKey {
+		alpha: trapdoor_g1(&Bn254::scalar_from_u64(3)),
+		beta: g2,
+		gamma: g2,
+		delta: g2,
+		ic: (0..=n_public).map(|i| trapdoor_g1(&Bn254::scalar_from_u64(13 + i))).collect(),
+	})
+	.encode()
+}
+
+/// Proof of `inputs` for `trapdoor_vk`: A = a * G1, B = G2 and C = c * G1, where
+/// a = α + Σ ICᵢ * inputᵢ + c.
+fn trapdoor_proof(inputs: &[PublicInput]) -> Vec<u8> {
+	let acc = inputs.iter().zip(14..).fold(Bn254::scalar_from_u64(13), |acc, (input, ic)| {
+		acc + Bn254::scalar_from_u64(ic) * Bn254::scalar_from_public_input(*input).unwrap()
 	});
+	let a = Bn254::scalar_from_u64(17);
+	let c = a - Bn254::scalar_from_u64(3) - acc;
+	CompactProof::Bn254(CompressedProof {
+		a: trapdoor_g1(&a),
+		b: trapdoor_g2(),
+		c: trapdoor_g1(&c),
+	})
+	.encode()
+}
+
+fn trapdoor_g1(scalar: &<Bn254 as Curve>::Scalar) -> Bn254G1 {
+	Bn254::g1_to_compressed(&Bn254::g1_mul(&Bn254::g1_generator(), scalar))
+		.try_into()
+		.unwrap()
+}
+
+fn trapdoor_g2() -> Bn254G2 {
+	Bn254::g2_to_compressed(&Bn254::g2_generator()).try_into().unwrap()
+}
+
+fn public_inputs_json(inputs: &[PublicInput]) -> String {
+	let inputs: Vec<String> = inputs
+		.iter()
+		.map(|input| format!("\"{}\"", U256::from_big_endian(input)))
+		.collect();
+	format!("[{}]", inputs.join(","))
+}
+
+fn verify_call(circuit_id: CircuitId, proof: &str, public_inputs: &str) -> RuntimeCall {
+	RuntimeCall::ZKSnarks(Call::verify {
+		circuit_id,
+		vec_proof: proof.as_bytes().into(),
+		pub_input: public_inputs.as_bytes().into(),
+	})
+}
+
+fn validate(call: RuntimeCall) -> TransactionValidity {
+	CheckZkProof::<Test>::new().validate(&ALICE_ACCOUNT_ID, &call, &DispatchInfo::default(), 0)
+}
+
+/// Transactions are rejected with the index of the pallet error.
+fn invalid_transaction(error: Error<Test>) -> TransactionValidityError {
+	match DispatchError::from(error) {
+		DispatchError::Module(ModuleError { error, .. }) =>
+			InvalidTransaction::Custom(error[0]).into(),
+		_ => unreachable!("pallet errors are module errors"),
+	}
+}
+
+fn unsigned_call(circuit_id: CircuitId, proof: &str, public_inputs: &str) -> Call<Test> {
+	Call::submit_proof_unsigned {
+		circuit_id,
+		vec_proof: proof.as_bytes().into(),
+		pub_input: public_inputs.as_bytes().into(),
+	}
+}
+
+fn validate_unsigned(call: &Call<Test>) -> TransactionValidity {
+	<ZKSnarks as ValidateUnsigned>::validate_unsigned(TransactionSource::External, call)
+}
+
+fn pre_dispatch(call: &Call<Test>) -> Result<(), TransactionValidityError> {
+	<ZKSnarks as ValidateUnsigned>::pre_dispatch(call)
+}
+
+fn setup_completed(circuit_id: CircuitId, vk: impl AsRef<[u8]>) -> Event<Test> {
+	let vk_hash = sp_io::hashing::blake2_256(vk.as_ref());
+	Event::<Test>::VerificationSetupCompleted { circuit_id, vk_hash }
+}
+
+fn proof_set(who: u64, proof: &[u8]) -> Event<Test> {
+	let proof_hash = sp_io::hashing::blake2_256(proof);
+	Event::<Test>::VerificationProofSet { circuit_id: CIRCUIT_ID, who, proof_hash }
+}
+
+fn inputs_hash(inputs: &str) -> InputsHash {
+	sp_io::hashing::blake2_256(&deserialize_public_inputs(inputs.as_bytes()).unwrap().concat())
+}
+
+fn verification_success(who: u64, proof: &[u8], inputs: &str) -> Event<Test> {
+	Event::<Test>::VerificationSuccess {
+		circuit_id: CIRCUIT_ID,
+		who,
+		proof_hash: sp_io::hashing::blake2_256(proof),
+		inputs_hash: inputs_hash(inputs),
+	}
+}
+
+fn verification_failed(who: u64, proof: &[u8], inputs: &str) -> Event<Test> {
+	Event::<Test>::VerificationFailed {
+		circuit_id: CIRCUIT_ID,
+		who,
+		proof_hash: sp_io::hashing::blake2_256(proof),
+		inputs_hash: inputs_hash(inputs),
+	}
+}
+
+fn batch_verification_completed(
+	who: u64,
+	proofs: &[(&[u8], &str)],
+	results: Vec<bool>,
+) -> Event<Test> {
+	let proofs = proofs
+		.iter()
+		.map(|(proof, inputs)| (sp_io::hashing::blake2_256(proof), inputs_hash(inputs)))
+		.collect();
+	Event::<Test>::BatchVerificationCompleted { circuit_id: CIRCUIT_ID, who, proofs, results }
+}
+
+fn compact_vk(json: &str) -> Vec<u8> {
+	CompactVKey::from_snarkjs(VKey::from_json_u8_slice(json.as_bytes()).unwrap())
+		.unwrap()
+		.encode()
+}
+
+fn compact_proof(json: &str) -> Vec<u8> {
+	CompactProof::from_snarkjs(Proof::from_json_u8_slice(json.as_bytes()).unwrap())
+		.unwrap()
+		.encode()
+}
+
+fn actual_weight(result: DispatchResultWithPostInfo) -> Option<Weight> {
+	match result {
+		Ok(post_info) => post_info.actual_weight,
+		Err(error) => error.post_info.actual_weight,
+	}
 }
 
 fn prepare_correct_public_inputs_json() -> String {
@@ -298,6 +2919,20 @@ fn prepare_incorrect_public_inputs_json() -> String {
 	.to_owned()
 }
 
+fn prepare_big_public_inputs_json() -> String {
+	r#"[
+ "0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000000"
+]"#
+	.to_owned()
+}
+
+fn prepare_out_of_field_public_inputs_json() -> String {
+	r#"[
+ "0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001"
+]"#
+	.to_owned()
+}
+
 fn prepare_empty_public_inputs_json() -> String {
 	r#"[
 ]"#
@@ -442,3 +3077,319 @@ fn prepare_proof_json(protocol: &str, curve: &str, pi_a_x: Option<String>) -> St
 		.replace("<curve>", curve)
 		.replace("<pi_a_x>", &pi_a_x)
 }
+
+fn prepare_bn254_public_inputs_json(input: &str) -> String {
+	format!("[\n \"{input}\"\n]")
+}
+
+fn prepare_bn254_vk_json() -> String {
+	r#"{
+"protocol": "groth16",
+"curve": "bn128",
+"nPublic": 1,
+"vk_alpha_1": [
+    "866095345221421711514180413743448151905772182708004637573355935301020034566",
+    "7221391810315363065250504614451527595399904277538141734100112624585369102786",
+    "1"
+],
+"vk_beta_2": [
+    [
+    "129782923071222172219222274433184956792132339028859579067463064678661755618",
+    "5799183513420936057741456559375310019513849279052488076883082562800333859274"
+    ],
+    [
+    "3036510262051021480576773093037947606842011407315450398741349831397258672651",
+    "7788190940393224787731613326796768244471912930413017978895603378360484911578"
+    ],
+    [
+    "1",
+    "0"
+    ]
+],
+"vk_gamma_2": [
+    [
+    "5990830084538208363307314339680834085561604791168915514548635466897816344270",
+    "8029705769976122274798785083342388760186164194754081135701136915002223344424"
+    ],
+    [
+    "16928854157997045097912724200193400372079759262604262014535591690839693907711",
+    "10062016626673020347994911112711835999441816093079367807412606575688452361020"
+    ],
+    [
+    "1",
+    "0"
+    ]
+],
+"vk_delta_2": [
+    [
+    "18182221911411233032090955183083484750381318839113869191762455872265023953053",
+    "4532716008912247041498858886168290022787263312119842298261689495345379468329"
+    ],
+    [
+    "2817430011812539585715424844376396694204510210323711625789139129715730343075",
+    "13159489736730190134669183937699368816206619946735782132507646973360959453541"
+    ],
+    [
+    "1",
+    "0"
+    ]
+],
+"IC": [
+    [
+        "19457571953900102113261406416722199904229182968440313615011891701937375523407",
+        "15616285690599967882747247340893844255466115442843311725523493735929078649876",
+        "1"
+    ],
+    [
+        "5070236491689709851553582362151187783154533049050495132105505049846834680730",
+        "12128836162002864285533101021881151758502217213754536414245453986296060464546",
+        "1"
+    ]
+]
+}"#
+	.to_owned()
+}
+
+fn prepare_bn254_proof_json(curve: &str) -> String {
+	let proof_template = r#"{
+"pi_a": [
+    "8407441580725980188856084962231235924707878671344545471241261411953209254851",
+    "8220749938458590961628004998375077645203738486400012973135184170547661229743",
+    "1"
+],
+"pi_b": [
+    [
+    "5817848408043394063999077998697460959884877750414136281598380853549698825081",
+    "8056041305764246727599908171879358720762163217000709315622453338868724401720"
+    ],
+    [
+    "2623946943410936151790385943274651226235732134018858389255343943011310926352",
+    "11996717810309561989026900382509107972554623931395104420925641685342210056291"
+    ],
+    [
+    "1",
+    "0"
+    ]
+],
+"pi_c": [
+    "13566245701818283649578978140042569312397974576184185491376415048557578470362",
+    "19110775143408751771206277603025975640399741366272013798382679012092160449187",
+    "1"
+],
+"protocol": "groth16",
+"curve": "<curve>"
+}"#;
+
+	proof_template.replace("<curve>", curve)
+}
+
+fn prepare_plonk_vk_json() -> String {
+	r#"{
+ "protocol": "plonk",
+ "curve": "bn128",
+ "nPublic": 1,
+ "power": 3,
+ "k1": "2",
+ "k2": "3",
+ "Qm": [
+  "21043728423416338430918951451064271033722560221385216624472295944389744135560",
+  "7531252060151573373164348699915826074436956742849317532716300618815277245067",
+  "1"
+ ],
+ "Ql": [
+  "821097604170088485852085622868773717728631991742632327825724171564969414940",
+  "2647172813390738520257209287350116007289741063967461296380833200236237911717",
+  "1"
+ ],
+ "Qr": [
+  "0",
+  "1",
+  "0"
+ ],
+ "Qo": [
+  "11516313973206610564043357888519182990698593340601211726714758763508389710900",
+  "8950598501716091183718760238186989290116217320938084142119593068167442558245",
+  "1"
+ ],
+ "Qc": [
+  "11845860552908422364377744600016924434155157704034720818873354144394207982499",
+  "5508321323441358493836342943307841778381969941790809858981303114645964991025",
+  "1"
+ ],
+ "S1": [
+  "2235607443531693859265916918065333834364394190336305762182641447802417372009",
+  "9848726838867374993783505086398926861491531068652461841459181843203432255664",
+  "1"
+ ],
+ "S2": [
+  "9788181228714848772856361516618486192036338766431315959511228871487574735959",
+  "11221562281131986188415627600663131038835616420522042998394813277851945751797",
+  "1"
+ ],
+ "S3": [
+  "14304519745318582339725285585244597307325201272194314673551577665092212705474",
+  "19228951283770524755079559375283576350128339072723271183594778255963373978321",
+  "1"
+ ],
+ "X_2": [
+  [
+   "15903878421108759439848478618074911574701566925570229139405616333111755535845",
+   "4291294403358638570039388526470878576987994586754394716672632188568865367486"
+  ],
+  [
+   "18531874304451210335666117718257049670818307824295916814921889502511919838294",
+   "2979484662042156423400334245990653844506029192010916415899010348289180103697"
+  ],
+  [
+   "1",
+   "0"
+  ]
+ ],
+ "w": "19540430494807482326159819597004422086093766032135589407132600596362845576832"
+}"#
+	.to_owned()
+}
+
+fn prepare_plonk_proof_json(curve: &str) -> String {
+	let proof_template = r#"{
+ "A": [
+  "8571288710236751879730997581741400792611069332973679042048973055709261243539",
+  "14505133663306351091444265640431853697465226870714079593501461575202456283605",
+  "1"
+ ],
+ "B": [
+  "8709602404358829963806292574900641750888950165640854482941286731183889057416",
+  "4064372348266938924003430859990616001411605888948457505726361949340793486731",
+  "1"
+ ],
+ "C": [
+  "974258032619639082307086666508405829638732027132683257675071685470820259617",
+  "9947979352472998407693157448368152775659671397610754925546869613987363000286",
+  "1"
+ ],
+ "Z": [
+  "21582975335397802135236442183473720597280437435395155820134943519900190476791",
+  "20117906821286077619527251149860529546974198958438333756274932492267806607715",
+  "1"
+ ],
+ "T1": [
+  "3545047308376181817457078032537497780767037728542205684418983080568224901623",
+  "20423766024501605378925265690246980657758181274119290968639476903797673392576",
+  "1"
+ ],
+ "T2": [
+  "21412735937442269165543243942908813316679278517749759534329703631962993706878",
+  "11991608116089446212999640219388187810476941106794902828194145597922404854191",
+  "1"
+ ],
+ "T3": [
+  "17137014371243091548441524707310565370586455640374122777978460290174411171371",
+  "19223419751530258366450704352728745316790095985433903042435104847840594671030",
+  "1"
+ ],
+ "Wxi": [
+  "20598467983392346143310491132720614691629395457401862373602901392244159413474",
+  "8493872317884825048181903459975198654783028584403315108405093230577797471934",
+  "1"
+ ],
+ "Wxiw": [
+  "3301513922664847757831644666475169063379780282647379546677097932558048563340",
+  "1685002504133676457031889893955555229564947216091760582208208537050562706470",
+  "1"
+ ],
+ "eval_a": "16223272930278254588247201010062725648761034075168364130363741966231738021709",
+ "eval_b": "2152978088049985579898263018241760104122429006545931490938632122890502494750",
+ "eval_c": "8136359670427995329697337395228596914522316680441619537643323534835157314322",
+ "eval_s1": "21049033008309520438571113921643403326422991470175443198166049146187094037046",
+ "eval_s2": "1957731797245194343016497149135681201032756824191774452262203751882632276210",
+ "eval_zw": "11509309785962252428353253848745032447564617327566520412965007707469704422600",
+ "protocol": "plonk",
+ "curve": "<curve>"
+}"#;
+
+	proof_template.replace("<curve>", curve)
+}
+
+fn prepare_fflonk_vk_json() -> String {
+	r#"{
+ "protocol": "fflonk",
+ "curve": "bn128",
+ "nPublic": 1,
+ "power": 3,
+ "k1": "2",
+ "k2": "3",
+ "w": "19540430494807482326159819597004422086093766032135589407132600596362845576832",
+ "wr": "13274704216607947843011480449124596415239537050559949017414504948711435969894",
+ "w3": "4407920970296243842393367215006156084916469457145843978461",
+ "w4": "21888242871839275217838484774961031246007050428528088939761107053157389710902",
+ "w8": "19540430494807482326159819597004422086093766032135589407132600596362845576832",
+ "X_2": [
+  [
+   "15903878421108759439848478618074911574701566925570229139405616333111755535845",
+   "4291294403358638570039388526470878576987994586754394716672632188568865367486"
+  ],
+  [
+   "18531874304451210335666117718257049670818307824295916814921889502511919838294",
+   "2979484662042156423400334245990653844506029192010916415899010348289180103697"
+  ],
+  [
+   "1",
+   "0"
+  ]
+ ],
+ "C0": [
+  "15430478022952747567281363769135958239699498064319376976856365673890527175523",
+  "14355169710197482966037336888063443614101929220282507648686726841697796256294",
+  "1"
+ ]
+}"#
+	.to_owned()
+}
+
+fn prepare_fflonk_proof_json(curve: &str) -> String {
+	let proof_template = r#"{
+ "polynomials": {
+  "C1": [
+   "6056851803853156526422026485589820674611636223475926784301179222414323137764",
+   "7072713591611864728130413511231546499851317092634744758857192410732036302583",
+   "1"
+  ],
+  "C2": [
+   "10138582921131393296871211261513073434842624002233417638173111496965207167305",
+   "15417743445269105065814266879891425710017914093418656378456680353028994955160",
+   "1"
+  ],
+  "W1": [
+   "8424003839246399592275061594687777201491777936530514040432369178932011919109",
+   "11048609772151598725219814359873939662058780018435353492835870006048718454489",
+   "1"
+  ],
+  "W2": [
+   "12410450569706012324819204875160791796933326608730268682085971310132808981683",
+   "13094661934285929561613766285132491483176878867455530586515698940252304966520",
+   "1"
+  ]
+ },
+ "evaluations": {
+  "ql": "13193859200870671640172085365437828020278616074331492992085587967179507346382",
+  "qr": "0",
+  "qm": "20384287399079250017330340823480099809091108165603403258056756745994831637528",
+  "qo": "12700870399927656298210586125721461841428067695267551143523333465519590275526",
+  "qc": "10185740962175657164609247878681690491184294419467308513750750298335776738923",
+  "s1": "9969822935792325902298324746648758154373190610033968351665462040152469807418",
+  "s2": "66115539258269445511521773361088335265545103184557754850001340835232153373",
+  "s3": "15518909339936644069099489320891493774305233844767999721194093506356646278002",
+  "a": "14496738519376518814156278225574046034165288695098044102020538566224520001642",
+  "b": "5263125896663929424976851135556719821095173004145797340522622518416871988092",
+  "c": "13486966956790162789797445748393905156262952321707961386340216767670398273386",
+  "z": "11581421146568986481597713236324377101479121116140516488867670053438216876326",
+  "zw": "12271767543796158703760030954039183864088952967517860635247167443500189839730",
+  "t1w": "19202546427701517470082958969123001153374970711625310247160987427839503702403",
+  "t2w": "3273223232739549770309504070173848084995396500525172608330596352614595597457"
+ },
+ "protocol": "fflonk",
+ "curve": "<curve>"
+}"#;
+
+	proof_template.replace("<curve>", curve)
+}
@@ -26,14 +26,360 @@
 // IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
 // DEALINGS IN THE SOFTWARE.
 
-use crate::verify::VerificationError::InvalidVerificationKey;
-use bls12_381::{Bls12, G1Affine, G2Affine, Scalar};
-use group::{prime::PrimeCurveAffine, Curve};
-use pairing::{Engine, MultiMillerLoop};
-use sp_std::{ops::AddAssign, prelude::*};
+use crate::{
+	deserialization::{Number, PublicInput},
+	verify::VerificationError::InvalidVerificationKey,
+};
+use bls12_381::{multi_miller_loop, pairing, G1Affine, G2Affine, G2Prepared, Gt, Scalar};
+use codec::{Decode, Encode};
+use core::ops::{Add, Mul, Neg, Sub};
+use group::{prime::PrimeCurveAffine, Curve as _};
+use scale_info::TypeInfo;
+use sp_io::hashing::keccak_256;
+use sp_std::prelude::*;
+
+/// Proving systems supported by the verifier
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SupportedProtocol {
+	Groth16,
+	Plonk,
+	Fflonk,
+}
+
+impl SupportedProtocol {
+	/// Matches the protocol name used by snarkjs
+	pub fn from_name(name: &[u8]) -> Option<Self> {
+		match name {
+			b"groth16" => Some(SupportedProtocol::Groth16),
+			b"plonk" => Some(SupportedProtocol::Plonk),
+			b"fflonk" => Some(SupportedProtocol::Fflonk),
+			_ => None,
+		}
+	}
+}
+
+/// Curves supported by the verifier
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum SupportedCurve {
+	Bls12381,
+	Bn254,
+}
+
+impl SupportedCurve {
+	/// Matches the curve name used by snarkjs
+	pub fn from_name(name: &[u8]) -> Option<Self> {
+		match name {
+			b"bls12381" => Some(SupportedCurve::Bls12381),
+			b"bn128" => Some(SupportedCurve::Bn254),
+			_ => None,
+		}
+	}
+
+	/// Curve name used by snarkjs
+	pub fn name(&self) -> &'static [u8] {
+		match self {
+			SupportedCurve::Bls12381 => b"bls12381",
+			SupportedCurve::Bn254 => b"bn128",
+		}
+	}
+}
+
+/// Reason for rejecting a curve point
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PointError {
+	/// Coordinate is not smaller than the base field modulus, or the point is not encoded the
+	/// canonical way
+	NonCanonicalCoordinate,
+	/// Coordinates do not satisfy the curve equation
+	NotOnCurve,
+	/// Point lies on the curve, but not in its prime order subgroup
+	NotInSubgroup,
+	/// Point at infinity where only a finite point is allowed
+	PointAtInfinity,
+}
+
+/// Pairing friendly curve which can be used for the proof verification
+pub trait Curve {
+	type G1: Copy;
+	type G2: Copy;
+	type Gt: Copy;
+	type Scalar: Copy
+		+ PartialEq
+		+ Add<Output = Self::Scalar>
+		+ Sub<Output = Self::Scalar>
+		+ Mul<Output = Self::Scalar>
+		+ Neg<Output = Self::Scalar>;
+
+	/// Big-endian base field modulus
+	const BASE_FIELD_MODULUS: Number;
+
+	/// Creates G1 point from big-endian affine coordinates
+	fn g1_from_coordinates(x: Number, y: Number) -> Result<Self::G1, PointError>;
+
+	/// Creates G2 point from big-endian affine coordinates
+	fn g2_from_coordinates(
+		x_c0: Number,
+		x_c1: Number,
+		y_c0: Number,
+		y_c1: Number,
+	) -> Result<Self::G2, PointError>;
+
+	/// Creates scalar from big-endian integer, `None` if it is not smaller than the modulus
+	fn scalar_from_public_input(input: PublicInput) -> Option<Self::Scalar>;
+
+	/// Creates scalar from a small integer
+	fn scalar_from_u64(value: u64) -> Self::Scalar;
+
+	/// Reduces big-endian 256 bits hash modulo the scalar field modulus
+	fn scalar_from_hash(hash: [u8; 32]) -> Self::Scalar;
+
+	/// Turns scalar into big-endian integer
+	fn scalar_to_bytes(scalar: &Self::Scalar) -> PublicInput;
+
+	fn scalar_inverse(scalar: &Self::Scalar) -> Option<Self::Scalar>;
+
+	fn g1_generator() -> Self::G1;
+
+	fn g2_generator() -> Self::G2;
+
+	fn g1_identity() -> Self::G1;
+
+	fn g2_identity() -> Self::G2;
+
+	fn g1_is_identity(point: &Self::G1) -> bool;
+
+	fn g2_is_identity(point: &Self::G2) -> bool;
+
+	/// Turns G1 point into big-endian affine coordinates `x || y`, the way snarkjs hashes it
+	fn g1_to_bytes(point: &Self::G1) -> Vec<u8>;
+
+	/// Turns G2 point into its uncompressed big-endian representation
+	fn g2_to_bytes(point: &Self::G2) -> Vec<u8>;
+
+	/// Restores G1 point from the output of `g1_to_bytes`. Subgroup membership is not checked, so
+	/// it may be used only for points which were validated before they were turned into bytes
+	fn g1_from_trusted_bytes(bytes: &[u8]) -> Option<Self::G1>;
+
+	/// Restores G2 point from the output of `g2_to_bytes`. Subgroup membership is not checked, so
+	/// it may be used only for points which were validated before they were turned into bytes
+	fn g2_from_trusted_bytes(bytes: &[u8]) -> Option<Self::G2>;
+
+	/// Creates G1 point from its compressed representation, which may be the point at infinity
+	fn g1_from_compressed(bytes: &[u8]) -> Result<Self::G1, PointError>;
+
+	/// Creates G2 point from its compressed representation, which may be the point at infinity
+	fn g2_from_compressed(bytes: &[u8]) -> Result<Self::G2, PointError>;
+
+	/// Turns G1 point into its compressed representation: `x` and the flags telling which `y`
+	fn g1_to_compressed(point: &Self::G1) -> Vec<u8>;
+
+	/// Turns G2 point into its compressed representation: `x` and the flags telling which `y`
+	fn g2_to_compressed(point: &Self::G2) -> Vec<u8>;
+
+	/// Computes `point * scalar`
+	fn g1_mul(point: &Self::G1, scalar: &Self::Scalar) -> Self::G1;
+
+	/// Computes `base + Σ pointsᵢ * scalarsᵢ`
+	fn g1_multi_exp(base: &Self::G1, points: &[Self::G1], scalars: &[Self::Scalar]) -> Self::G1;
+
+	fn g1_neg(point: &Self::G1) -> Self::G1;
+
+	/// Checks whether product of pairings of given points is equal to the identity
+	fn pairing_product_is_identity(pairs: &[(Self::G1, Self::G2)]) -> bool;
+
+	/// Checks whether product of pairings of given points and `factor` is equal to the identity,
+	/// so a pairing computed in advance saves its Miller loop
+	fn pairing_product_with_is_identity(pairs: &[(Self::G1, Self::G2)], factor: &Self::Gt) -> bool;
+
+	/// Computes `e(g1, g2)`
+	fn pairing(g1: &Self::G1, g2: &Self::G2) -> Self::Gt;
+
+	/// Turns `Gt` element into the bytes of its in-memory representation, neither curve library
+	/// offers an encoding of it
+	fn gt_to_bytes(value: &Self::Gt) -> Vec<u8>;
+
+	/// Restores `Gt` element from the output of `gt_to_bytes`. Any bytes of the right length are
+	/// accepted, so it may be used only for the bytes of an element computed before
+	fn gt_from_trusted_bytes(bytes: &[u8]) -> Option<Self::Gt>;
+}
+
+/// Rejects the point at infinity, which makes a proof or a verification key degenerate
+pub fn finite_g1<C: Curve>(point: C::G1) -> Result<C::G1, PointError> {
+	if C::g1_is_identity(&point) {
+		return Err(PointError::PointAtInfinity)
+	}
+	Ok(point)
+}
+
+/// Rejects the point at infinity, which makes a proof or a verification key degenerate
+pub fn finite_g2<C: Curve>(point: C::G2) -> Result<C::G2, PointError> {
+	if C::g2_is_identity(&point) {
+		return Err(PointError::PointAtInfinity)
+	}
+	Ok(point)
+}
+
+/// BLS12-381 curve
+pub struct Bls12_381;
+
+impl Curve for Bls12_381 {
+	type G1 = G1Affine;
+	type G2 = G2Affine;
+	type Gt = Gt;
+	type Scalar = Scalar;
+
+	const BASE_FIELD_MODULUS: Number = BLS12_381_BASE_FIELD_MODULUS;
+
+	fn g1_from_coordinates(x: Number, y: Number) -> Result<Self::G1, PointError> {
+		(&G1UncompressedBytes::new(x, y)).try_into()
+	}
+
+	fn g2_from_coordinates(
+		x_c0: Number,
+		x_c1: Number,
+		y_c0: Number,
+		y_c1: Number,
+	) -> Result<Self::G2, PointError> {
+		(&G2UncompressedBytes::new(x_c0, x_c1, y_c0, y_c1)).try_into()
+	}
+
+	fn scalar_from_public_input(mut input: PublicInput) -> Option<Self::Scalar> {
+		input.reverse();
+		Scalar::from_bytes(&input).into()
+	}
+
+	fn scalar_from_u64(value: u64) -> Self::Scalar {
+		Scalar::from(value)
+	}
+
+	fn scalar_from_hash(hash: [u8; 32]) -> Self::Scalar {
+		let mut wide = [0u8; 64];
+		wide[..32].copy_from_slice(&hash);
+		wide[..32].reverse();
+		Scalar::from_bytes_wide(&wide)
+	}
+
+	fn scalar_to_bytes(scalar: &Self::Scalar) -> PublicInput {
+		let mut bytes = scalar.to_bytes();
+		bytes.reverse();
+		bytes
+	}
+
+	fn scalar_inverse(scalar: &Self::Scalar) -> Option<Self::Scalar> {
+		scalar.invert().into()
+	}
+
+	fn g1_generator() -> Self::G1 {
+		G1Affine::generator()
+	}
+
+	fn g2_generator() -> Self::G2 {
+		G2Affine::generator()
+	}
+
+	fn g1_identity() -> Self::G1 {
+		G1Affine::identity()
+	}
+
+	fn g2_identity() -> Self::G2 {
+		G2Affine::identity()
+	}
+
+	fn g1_is_identity(point: &Self::G1) -> bool {
+		point.is_identity().into()
+	}
+
+	fn g2_is_identity(point: &Self::G2) -> bool {
+		point.is_identity().into()
+	}
+
+	fn g1_to_bytes(point: &Self::G1) -> Vec<u8> {
+		point.to_uncompressed().to_vec()
+	}
+
+	fn g2_to_bytes(point: &Self::G2) -> Vec<u8> {
+		point.to_uncompressed().to_vec()
+	}
+
+	fn g1_from_trusted_bytes(bytes: &[u8]) -> Option<Self::G1> {
+		G1Affine::from_uncompressed_unchecked(bytes.try_into().ok()?).into()
+	}
+
+	fn g2_from_trusted_bytes(bytes: &[u8]) -> Option<Self::G2> {
+		G2Affine::from_uncompressed_unchecked(bytes.try_into().ok()?).into()
+	}
+
+	fn g1_from_compressed(bytes: &[u8]) -> Result<Self::G1, PointError> {
+		let bytes: &[u8; 48] = bytes.try_into().map_err(|_| PointError::NonCanonicalCoordinate)?;
+		let point: Option<G1Affine> = G1Affine::from_compressed_unchecked(bytes).into();
+		validate_g1(point.ok_or_else(|| compressed_point_error(bytes))?)
+	}
 
-pub const SUPPORTED_CURVE: &str = "bls12381";
-pub const SUPPORTED_PROTOCOL: &str = "groth16";
+	fn g2_from_compressed(bytes: &[u8]) -> Result<Self::G2, PointError> {
+		let bytes: &[u8; 96] = bytes.try_into().map_err(|_| PointError::NonCanonicalCoordinate)?;
+		let point: Option<G2Affine> = G2Affine::from_compressed_unchecked(bytes).into();
+		validate_g2(point.ok_or_else(|| compressed_point_error(bytes))?)
+	}
+
+	fn g1_to_compressed(point: &Self::G1) -> Vec<u8> {
+		point.to_compressed().to_vec()
+	}
+
+	fn g2_to_compressed(point: &Self::G2) -> Vec<u8> {
+		point.to_compressed().to_vec()
+	}
+
+	fn g1_mul(point: &Self::G1, scalar: &Self::Scalar) -> Self::G1 {
+		(point * scalar).to_affine()
+	}
+
+	fn g1_multi_exp(base: &Self::G1, points: &[Self::G1], scalars: &[Self::Scalar]) -> Self::G1 {
+		let mut acc = base.to_curve();
+		for (point, scalar) in points.iter().zip(scalars) {
+			acc += point * scalar;
+		}
+		acc.to_affine()
+	}
+
+	fn g1_neg(point: &Self::G1) -> Self::G1 {
+		-point
+	}
+
+	fn pairing_product_is_identity(pairs: &[(Self::G1, Self::G2)]) -> bool {
+		bls12_381_pairing_product(pairs) == Gt::identity()
+	}
+
+	fn pairing_product_with_is_identity(pairs: &[(Self::G1, Self::G2)], factor: &Self::Gt) -> bool {
+		bls12_381_pairing_product(pairs) + factor == Gt::identity()
+	}
+
+	fn pairing(g1: &Self::G1, g2: &Self::G2) -> Self::Gt {
+		pairing(g1, g2)
+	}
+
+	fn gt_to_bytes(value: &Self::Gt) -> Vec<u8> {
+		// SAFETY: `Gt` is twelve base field elements of six `u64` limbs each, so it has no padding
+		// and `transmute` fails to compile if its size is not 576 bytes
+		unsafe { core::mem::transmute::<Gt, [u8; BLS12_381_GT_LEN]>(*value) }.to_vec()
+	}
+
+	fn gt_from_trusted_bytes(bytes: &[u8]) -> Option<Self::Gt> {
+		let bytes: [u8; BLS12_381_GT_LEN] = bytes.try_into().ok()?;
+		// SAFETY: every bit pattern is a valid `u64` limb
+		Some(unsafe { core::mem::transmute::<[u8; BLS12_381_GT_LEN], Gt>(bytes) })
+	}
+}
+
+/// Size of BLS12-381 `Gt` element: twelve base field elements
+const BLS12_381_GT_LEN: usize = 576;
+
+/// Computes product of pairings with a single final exponentiation
+fn bls12_381_pairing_product(pairs: &[(G1Affine, G2Affine)]) -> Gt {
+	let prepared: Vec<(G1Affine, G2Prepared)> =
+		pairs.iter().map(|(g1, g2)| (*g1, G2Prepared::from(*g2))).collect();
+	let terms: Vec<(&G1Affine, &G2Prepared)> = prepared.iter().map(|(g1, g2)| (g1, g2)).collect();
+	multi_miller_loop(&terms).final_exponentiation()
+}
 
 /// Stores G1 field points (x, y) in an array.
 /// positions `[0,48]` contains x
@@ -79,46 +425,86 @@ impl G2UncompressedBytes {
 }
 
 impl TryFrom<&G1UncompressedBytes> for G1Affine {
-	type Error = ();
+	type Error = PointError;
 
 	fn try_from(value: &G1UncompressedBytes) -> Result<Self, Self::Error> {
-		let g1 = G1Affine::from_uncompressed(&value.inner);
-		if g1.is_none().into() {
-			Err(())
-		} else {
-			Ok(g1.unwrap())
-		}
+		let g1: Option<G1Affine> = G1Affine::from_uncompressed_unchecked(&value.inner).into();
+		validate_g1(g1.ok_or(PointError::NonCanonicalCoordinate)?)
 	}
 }
 
 impl TryFrom<&G2UncompressedBytes> for G2Affine {
-	type Error = ();
+	type Error = PointError;
 
 	fn try_from(value: &G2UncompressedBytes) -> Result<Self, Self::Error> {
-		let g2 = G2Affine::from_uncompressed(&value.inner);
-		if g2.is_none().into() {
-			Err(())
-		} else {
-			Ok(g2.unwrap())
-		}
+		let g2: Option<G2Affine> = G2Affine::from_uncompressed_unchecked(&value.inner).into();
+		validate_g2(g2.ok_or(PointError::NonCanonicalCoordinate)?)
 	}
 }
 
+/// Runs the checks which `bls12_381` skips when it creates points without validation
+fn validate_g1(point: G1Affine) -> Result<G1Affine, PointError> {
+	if !bool::from(point.is_on_curve()) {
+		return Err(PointError::NotOnCurve)
+	}
+	if !bool::from(point.is_torsion_free()) {
+		return Err(PointError::NotInSubgroup)
+	}
+	Ok(point)
+}
+
+/// Runs the checks which `bls12_381` skips when it creates points without validation
+fn validate_g2(point: G2Affine) -> Result<G2Affine, PointError> {
+	if !bool::from(point.is_on_curve()) {
+		return Err(PointError::NotOnCurve)
+	}
+	if !bool::from(point.is_torsion_free()) {
+		return Err(PointError::NotInSubgroup)
+	}
+	Ok(point)
+}
+
+/// BLS12-381 base field modulus
+const BLS12_381_BASE_FIELD_MODULUS: Number = [
+	0x1a, 0x01, 0x11, 0xea, 0x39, 0x7f, 0xe6, 0x9a, 0x4b, 0x1b, 0xa7, 0xb6, 0x43, 0x4b, 0xac, 0xd7,
+	0x64, 0x77, 0x4b, 0x84, 0xf3, 0x85, 0x12, 0xbf, 0x67, 0x30, 0xd2, 0xa0, 0xf6, 0xb0, 0xf6, 0x24,
+	0x1e, 0xab, 0xff, 0xfe, 0xb1, 0x53, 0xff, 0xff, 0xb9, 0xfe, 0xff, 0xff, 0xff, 0xff, 0xaa, 0xab,
+];
+
+/// Tells why `bls12_381` rejected a compressed point. Its three most significant bits are the
+/// compression, infinity and sort flags, the rest are the coordinates of `x`.
+fn compressed_point_error(bytes: &[u8]) -> PointError {
+	let flags = bytes[0] >> 5;
+	if flags & 0b100 == 0 || flags & 0b010 != 0 {
+		// Not compressed, or the point at infinity with non-zero bytes
+		return PointError::NonCanonicalCoordinate
+	}
+	let mut x = bytes.to_vec();
+	x[0] &= 0b0001_1111;
+	if x.chunks(48).any(|coordinate| coordinate >= &BLS12_381_BASE_FIELD_MODULUS[..]) {
+		return PointError::NonCanonicalCoordinate
+	}
+	// There is no `y` for such `x`
+	PointError::NotOnCurve
+}
+
 /// Represents Groth16 verification key
-pub struct VerificationKey {
-	pub alpha: G1Affine,
-	pub beta: G2Affine,
-	pub gamma: G2Affine,
-	pub delta: G2Affine,
-	pub ic: Vec<G1Affine>,
+pub struct VerificationKey<C: Curve> {
+	pub alpha: C::G1,
+	pub beta: C::G2,
+	pub gamma: C::G2,
+	pub delta: C::G2,
+	pub ic: Vec<C::G1>,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum VerificationKeyCreationError {
-	PointCreationError,
+	PointCreationError(PointError),
+	ScalarCreationError,
+	NotSupportedCurve,
 }
 
-impl VerificationKey {
+impl VerificationKey<Bls12_381> {
 	pub fn from_uncompressed(
 		alpha: &G1UncompressedBytes,
 		beta: &G2UncompressedBytes,
@@ -126,20 +512,18 @@ impl VerificationKey {
 		delta: &G2UncompressedBytes,
 		ic: &Vec<G1UncompressedBytes>,
 	) -> Result<Self, VerificationKeyCreationError> {
-		let alpha =
-			alpha.try_into().map_err(|_| VerificationKeyCreationError::PointCreationError)?;
+		let alpha = alpha.try_into().map_err(VerificationKeyCreationError::PointCreationError)?;
 		let beta: G2Affine =
-			beta.try_into().map_err(|_| VerificationKeyCreationError::PointCreationError)?;
+			beta.try_into().map_err(VerificationKeyCreationError::PointCreationError)?;
 		let gamma: G2Affine =
-			gamma.try_into().map_err(|_| VerificationKeyCreationError::PointCreationError)?;
+			gamma.try_into().map_err(VerificationKeyCreationError::PointCreationError)?;
 		let delta: G2Affine =
-			delta.try_into().map_err(|_| VerificationKeyCreationError::PointCreationError)?;
+			delta.try_into().map_err(VerificationKeyCreationError::PointCreationError)?;
 		let mut ic_2: Vec<G1Affine> = Vec::with_capacity(ic.len());
 
 		for i in ic {
 			ic_2.push(
-				G1Affine::try_from(i)
-					.map_err(|_| VerificationKeyCreationError::PointCreationError)?,
+				G1Affine::try_from(i).map_err(VerificationKeyCreationError::PointCreationError)?,
 			);
 		}
 
@@ -147,27 +531,158 @@ impl VerificationKey {
 	}
 }
 
+/// Groth16 verification key with `e(alpha, beta)` computed in advance, which saves one of the
+/// four Miller loops of every verification
+pub struct PreparedVerificationKey<C: Curve> {
+	pub vk: VerificationKey<C>,
+	pub alpha_beta: C::Gt,
+}
+
+impl<C: Curve> From<VerificationKey<C>> for PreparedVerificationKey<C> {
+	fn from(vk: VerificationKey<C>) -> Self {
+		let alpha_beta = C::pairing(&vk.alpha, &vk.beta);
+		PreparedVerificationKey { vk, alpha_beta }
+	}
+}
+
+pub type Bls12381UncompressedG1 = [u8; 96];
+pub type Bls12381UncompressedG2 = [u8; 192];
+pub type Bls12381Gt = [u8; 576];
+pub type Bn254UncompressedG1 = [u8; 64];
+pub type Bn254UncompressedG2 = [u8; 128];
+pub type Bn254Gt = [u8; 384];
+
+/// Groth16 verification key stored as the output of `Curve::g1_to_bytes`, `Curve::g2_to_bytes`
+/// and `Curve::gt_to_bytes`
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct UncompressedVerificationKey<G1, G2, Gt> {
+	pub alpha: G1,
+	pub beta: G2,
+	pub gamma: G2,
+	pub delta: G2,
+	pub ic: Vec<G1>,
+	/// `e(alpha, beta)`
+	pub alpha_beta: Gt,
+}
+
+/// Groth16 verification key which was already validated, with `e(alpha, beta)` computed in
+/// advance.
+///
+/// Restoring it needs neither JSON parsing, subgroup checks nor the pairing of `alpha` and `beta`.
+/// `e(alpha, beta)` is kept as the in-memory representation of the `Gt` element, since neither
+/// `bls12_381` nor `substrate-bn` encodes it. `substrate-bn` declares that layout `repr(C)`,
+/// `bls12_381` does not, so changing its version needs a migration decoding the keys again. The
+/// `G2Prepared` line coefficients of `gamma` and `delta` are not stored: `bls12_381` keeps them
+/// private and `substrate-bn` does not expose its prepared form, so they are computed during every
+/// verification.
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum DecodedVerificationKey {
+	Bls12381(
+		UncompressedVerificationKey<Bls12381UncompressedG1, Bls12381UncompressedG2, Bls12381Gt>,
+	),
+	Bn254(UncompressedVerificationKey<Bn254UncompressedG1, Bn254UncompressedG2, Bn254Gt>),
+}
+
+/// Turns point or `Gt` bytes into an array, their size is fixed by the curve
+fn fixed<const N: usize>(bytes: Vec<u8>) -> [u8; N] {
+	bytes.try_into().expect("uncompressed value has the size of the curve")
+}
+
+impl<const G1: usize, const G2: usize, const GT: usize>
+	UncompressedVerificationKey<[u8; G1], [u8; G2], [u8; GT]>
+{
+	fn new<C: Curve>(vk: &PreparedVerificationKey<C>) -> Self {
+		UncompressedVerificationKey {
+			alpha: fixed(C::g1_to_bytes(&vk.vk.alpha)),
+			beta: fixed(C::g2_to_bytes(&vk.vk.beta)),
+			gamma: fixed(C::g2_to_bytes(&vk.vk.gamma)),
+			delta: fixed(C::g2_to_bytes(&vk.vk.delta)),
+			ic: vk.vk.ic.iter().map(|point| fixed(C::g1_to_bytes(point))).collect(),
+			alpha_beta: fixed(C::gt_to_bytes(&vk.alpha_beta)),
+		}
+	}
+
+	fn restore<C: Curve>(
+		&self,
+	) -> Result<PreparedVerificationKey<C>, VerificationKeyCreationError> {
+		let corrupted =
+			VerificationKeyCreationError::PointCreationError(PointError::NonCanonicalCoordinate);
+		let g1 = |bytes: &[u8; G1]| C::g1_from_trusted_bytes(bytes).ok_or(corrupted);
+		let g2 = |bytes: &[u8; G2]| C::g2_from_trusted_bytes(bytes).ok_or(corrupted);
+		Ok(PreparedVerificationKey {
+			vk: VerificationKey {
+				alpha: g1(&self.alpha)?,
+				beta: g2(&self.beta)?,
+				gamma: g2(&self.gamma)?,
+				delta: g2(&self.delta)?,
+				ic: self.ic.iter().map(g1).collect::<Result<_, _>>()?,
+			},
+			alpha_beta: C::gt_from_trusted_bytes(&self.alpha_beta).ok_or(corrupted)?,
+		})
+	}
+}
+
+impl DecodedVerificationKey {
+	/// Computes `e(alpha, beta)`, `C` has to be the curve given
+	pub fn new<C: Curve>(curve: SupportedCurve, vk: VerificationKey<C>) -> Self {
+		let vk = PreparedVerificationKey::from(vk);
+		match curve {
+			SupportedCurve::Bls12381 =>
+				DecodedVerificationKey::Bls12381(UncompressedVerificationKey::new(&vk)),
+			SupportedCurve::Bn254 =>
+				DecodedVerificationKey::Bn254(UncompressedVerificationKey::new(&vk)),
+		}
+	}
+
+	pub fn curve(&self) -> SupportedCurve {
+		match self {
+			DecodedVerificationKey::Bls12381(_) => SupportedCurve::Bls12381,
+			DecodedVerificationKey::Bn254(_) => SupportedCurve::Bn254,
+		}
+	}
+
+	pub fn public_inputs_len(&self) -> usize {
+		match self {
+			DecodedVerificationKey::Bls12381(vk) => vk.ic.len().saturating_sub(1),
+			DecodedVerificationKey::Bn254(vk) => vk.ic.len().saturating_sub(1),
+		}
+	}
+
+	/// Restores the points and `e(alpha, beta)`, `C` has to be the curve of the key
+	pub fn restore<C: Curve>(
+		&self,
+	) -> Result<PreparedVerificationKey<C>, VerificationKeyCreationError> {
+		match self {
+			DecodedVerificationKey::Bls12381(vk) => vk.restore(),
+			DecodedVerificationKey::Bn254(vk) => vk.restore(),
+		}
+	}
+}
+
 /// Represents Groth16 proof
-pub struct GProof {
-	pub a: G1Affine,
-	pub b: G2Affine,
-	pub c: G1Affine,
+pub struct GProof<C: Curve> {
+	pub a: C::G1,
+	pub b: C::G2,
+	pub c: C::G1,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum GProofCreationError {
-	PointCreationError,
+	PointCreationError(PointError),
+	ScalarCreationError,
+	NotSupportedCurve,
 }
 
-impl GProof {
+impl GProof<Bls12_381> {
 	pub fn from_uncompressed(
 		a: &G1UncompressedBytes,
 		b: &G2UncompressedBytes,
 		c: &G1UncompressedBytes,
 	) -> Result<Self, GProofCreationError> {
-		let a = a.try_into().map_err(|_| GProofCreationError::PointCreationError)?;
-		let b = b.try_into().map_err(|_| GProofCreationError::PointCreationError)?;
-		let c = c.try_into().map_err(|_| GProofCreationError::PointCreationError)?;
+		let a = a.try_into().map_err(GProofCreationError::PointCreationError)?;
+		let b = b.try_into().map_err(GProofCreationError::PointCreationError)?;
+		let c = c.try_into().map_err(GProofCreationError::PointCreationError)?;
 
 		Ok(GProof { a, b, c })
 	}
@@ -180,54 +695,146 @@ pub enum VerificationError {
 
 pub type VerificationResult = Result<bool, VerificationError>;
 
-pub type PublicInputs = Vec<Scalar>;
+pub type PublicInputs<C> = Vec<<C as Curve>::Scalar>;
 
-/// Turns `u64` values into `Scalar` representation
-pub fn prepare_public_inputs(inputs: Vec<u64>) -> Vec<Scalar> {
-	inputs.into_iter().map(Scalar::from).collect()
+#[derive(Debug, PartialEq)]
+pub enum PublicInputsCreationError {
+	/// The value is not smaller than the scalar field modulus
+	ScalarCreationError,
 }
 
-/// Verifies given proof with given verification key and public inputs
-pub fn verify(vk: VerificationKey, proof: GProof, inputs: PublicInputs) -> VerificationResult {
-	let public_inputs: &[<Bls12 as Engine>::Fr] = &inputs;
+/// Turns big-endian integers into `Scalar` representation
+pub fn prepare_public_inputs<C: Curve>(
+	inputs: Vec<PublicInput>,
+) -> Result<PublicInputs<C>, PublicInputsCreationError> {
+	inputs
+		.into_iter()
+		.map(|input| {
+			C::scalar_from_public_input(input).ok_or(PublicInputsCreationError::ScalarCreationError)
+		})
+		.collect()
+}
 
-	if (public_inputs.len() + 1) != vk.ic.len() {
+/// Verifies given proof with given verification key and public inputs. A key which is not
+/// prepared gets `e(alpha, beta)` computed first.
+pub fn verify<C: Curve>(
+	vk: impl Into<PreparedVerificationKey<C>>,
+	proof: GProof<C>,
+	inputs: PublicInputs<C>,
+) -> VerificationResult {
+	let vk = vk.into();
+	if (inputs.len() + 1) != vk.vk.ic.len() {
 		return Err(InvalidVerificationKey)
 	}
 
+	Ok(check(&vk, &proof, &inputs))
+}
+
+fn check<C: Curve>(
+	prepared: &PreparedVerificationKey<C>,
+	proof: &GProof<C>,
+	inputs: &[C::Scalar],
+) -> bool {
+	let vk = &prepared.vk;
 	// ic contains Lᵢ(τ)/δ
 	// Lᵢ(x) = β * Aᵢ(x) + α * Bᵢ(x) + Cᵢ(x)
 	// public variables [33]
 	// w = [1, 33, ...private variables]
 	// acc contains sum of Lᵢ(x) * wᵢ
-	let mut acc = vk.ic[0].to_curve();
-	for (i, b) in public_inputs.iter().zip(vk.ic.iter().skip(1)) {
-		AddAssign::<&<Bls12 as Engine>::G1>::add_assign(&mut acc, &(*b * i));
+	let acc = C::g1_multi_exp(&vk.ic[0], &vk.ic[1..], inputs);
+
+	// Aₚ*Bₚ = αβ + (L_input(τ)/γ)γ + Cₚδ
+	// checked as -Aₚ*Bₚ + αβ + (L_input(τ)/γ)γ + Cₚδ = 0, with e(α, β) computed in advance
+	C::pairing_product_with_is_identity(
+		&[(C::g1_neg(&proof.a), proof.b), (acc, vk.gamma), (proof.c, vk.delta)],
+		&prepared.alpha_beta,
+	)
+}
+
+/// Tells for every proof of the batch whether it is valid
+pub type BatchVerificationResult = Result<Vec<bool>, VerificationError>;
+
+/// Verifies many proofs against the same verification key with a single pairing product.
+///
+/// Equation of every proof is multiplied by a random coefficient rᵢ and all of them are summed up,
+/// so `n` proofs cost `n + 3` Miller loops sharing one final exponentiation instead of `3n` Miller
+/// loops and `n` final exponentiations.
+/// Only when the batch does not hold, the proofs are verified one by one to find the invalid ones.
+pub fn verify_batch<C: Curve>(
+	vk: impl Into<PreparedVerificationKey<C>>,
+	proofs: Vec<(GProof<C>, PublicInputs<C>)>,
+) -> BatchVerificationResult {
+	let prepared = vk.into();
+	let vk = &prepared.vk;
+	if proofs.iter().any(|(_, inputs)| inputs.len() + 1 != vk.ic.len()) {
+		return Err(InvalidVerificationKey)
+	}
+	if proofs.is_empty() {
+		return Ok(Vec::new())
+	}
+
+	let coefficients = batch_coefficients(&proofs);
+	let zero = C::scalar_from_u64(0);
+	let mut coefficients_sum = zero;
+	let mut inputs_combination = vec![zero; vk.ic.len() - 1];
+	let mut pairs = Vec::with_capacity(proofs.len() + 3);
+	for ((proof, inputs), r) in proofs.iter().zip(&coefficients) {
+		coefficients_sum = coefficients_sum + *r;
+		for (combination, input) in inputs_combination.iter_mut().zip(inputs) {
+			*combination = *combination + *r * *input;
+		}
+		pairs.push((C::g1_mul(&C::g1_neg(&proof.a), r), proof.b));
 	}
 
-	//lhs
-	// Aₚ*Bₚ
-	let a_b_pairing = Bls12::pairing(&proof.a, &proof.b);
+	// Σ rᵢ * (-Aᵢ*Bᵢ + αβ + accᵢγ + Cᵢδ) = 0 checked as
+	// Π e(-rᵢ * Aᵢ, Bᵢ) * e(Σ rᵢ * α, β) * e(Σ rᵢ * accᵢ, γ) * e(Σ rᵢ * Cᵢ, δ) = 1
+	let c: Vec<C::G1> = proofs.iter().map(|(proof, _)| proof.c).collect();
+	pairs.push((C::g1_mul(&vk.alpha, &coefficients_sum), vk.beta));
+	pairs.push((
+		C::g1_multi_exp(&C::g1_mul(&vk.ic[0], &coefficients_sum), &vk.ic[1..], &inputs_combination),
+		vk.gamma,
+	));
+	pairs.push((
+		C::g1_multi_exp(&C::g1_mul(&c[0], &coefficients[0]), &c[1..], &coefficients[1..]),
+		vk.delta,
+	));
+	if C::pairing_product_is_identity(&pairs) {
+		return Ok(vec![true; proofs.len()])
+	}
 
-	//rhs
-	// αβ + (L_input(τ)/γ)γ + Cₚδ
-	let final_result = Bls12::multi_miller_loop(&[
-		(&vk.alpha, &vk.beta.into()),
-		(&acc.to_affine(), &vk.gamma.into()),
-		(&proof.c, &vk.delta.into()),
-	])
-	.final_exponentiation();
+	Ok(proofs.iter().map(|(proof, inputs)| check(&prepared, proof, inputs)).collect())
+}
 
-	Ok(a_b_pairing == final_result)
+/// Derives batching coefficients from all proofs and public inputs of the batch, so they can not
+/// be known before the proofs are fixed
+fn batch_coefficients<C: Curve>(proofs: &[(GProof<C>, PublicInputs<C>)]) -> Vec<C::Scalar> {
+	let mut transcript = Vec::new();
+	for (proof, inputs) in proofs {
+		transcript.extend(C::g1_to_bytes(&proof.a));
+		transcript.extend(C::g2_to_bytes(&proof.b));
+		transcript.extend(C::g1_to_bytes(&proof.c));
+		for input in inputs {
+			transcript.extend(C::scalar_to_bytes(input));
+		}
+	}
+	let seed = keccak_256(&transcript);
+	(0..proofs.len() as u64)
+		.map(|index| {
+			let mut preimage = seed.to_vec();
+			preimage.extend(index.to_be_bytes());
+			C::scalar_from_hash(keccak_256(&preimage))
+		})
+		.collect()
 }
 
 #[cfg(test)]
 mod tests {
 	use crate::verify::{
-		verify, G1UncompressedBytes, G2UncompressedBytes, GProof, VerificationError,
-		VerificationKey,
+		finite_g1, prepare_public_inputs, verify, verify_batch, Bls12_381, Curve,
+		DecodedVerificationKey, G1UncompressedBytes, G2UncompressedBytes, GProof, PointError,
+		PublicInputsCreationError, SupportedCurve, VerificationError, VerificationKey,
 	};
-	use bls12_381::{G1Affine, G2Affine};
+	use bls12_381::{G1Affine, G2Affine, Gt, Scalar};
 
 	const ALPHA_X: &str = "2417420058161902631695569321985275527817337553240735969068630412919230058600548397578577183742111992841943587142680";
 	const ALPHA_Y: &str = "2683193963041639430431668252069589353703764749562535314981925385889474793061455502785968498855669710056680025802535";
@@ -386,8 +993,8 @@ mod tests {
 
 		//----------VERIFICATION---------------//
 		assert!(verify(
-			VerificationKey { alpha, beta, gamma, delta, ic: vec![ic_1, ic_2] },
-			GProof { a: pi_a, b: pi_b, c: pi_c },
+			VerificationKey::<Bls12_381> { alpha, beta, gamma, delta, ic: vec![ic_1, ic_2] },
+			GProof::<Bls12_381> { a: pi_a, b: pi_b, c: pi_c },
 			// blog/data/public.json
 			[12.into()].into(),
 		)
@@ -463,8 +1070,8 @@ mod tests {
 
 		//----------VERIFICATION---------------//
 		assert!(!verify(
-			VerificationKey { alpha, beta, gamma, delta, ic: vec![ic_1, ic_2] },
-			GProof { a: pi_a, b: pi_b, c: pi_c },
+			VerificationKey::<Bls12_381> { alpha, beta, gamma, delta, ic: vec![ic_1, ic_2] },
+			GProof::<Bls12_381> { a: pi_a, b: pi_b, c: pi_c },
 			[33.into()].into(),
 		)
 		.unwrap())
@@ -536,8 +1143,8 @@ mod tests {
 		//----------VERIFICATION---------------//
 		assert_eq!(
 			verify(
-				VerificationKey { alpha, beta, gamma, delta, ic: vec![ic_1] },
-				GProof { a: pi_a, b: pi_b, c: pi_c },
+				VerificationKey::<Bls12_381> { alpha, beta, gamma, delta, ic: vec![ic_1] },
+				GProof::<Bls12_381> { a: pi_a, b: pi_b, c: pi_c },
 				[33.into()].into(),
 			)
 			.err()
@@ -547,6 +1154,244 @@ mod tests {
 		//--------END OF VERIFICATION---------//
 	}
 
+	fn prepare_vk() -> VerificationKey<Bls12_381> {
+		VerificationKey::from_uncompressed(
+			&G1UncompressedBytes::new(from_dec_string(ALPHA_X), from_dec_string(ALPHA_Y)),
+			&G2UncompressedBytes::new(
+				from_dec_string(BETA_X_C0),
+				from_dec_string(BETA_X_C1),
+				from_dec_string(BETA_Y_C0),
+				from_dec_string(BETA_Y_C1),
+			),
+			&G2UncompressedBytes::new(
+				from_dec_string(GAMMA_X_C0),
+				from_dec_string(GAMMA_X_C1),
+				from_dec_string(GAMMA_Y_C0),
+				from_dec_string(GAMMA_Y_C1),
+			),
+			&G2UncompressedBytes::new(
+				from_dec_string(DELTA_X_C0),
+				from_dec_string(DELTA_X_C1),
+				from_dec_string(DELTA_Y_C0),
+				from_dec_string(DELTA_Y_C1),
+			),
+			&vec![
+				G1UncompressedBytes::new(from_dec_string(IC_1_X), from_dec_string(IC_1_Y)),
+				G1UncompressedBytes::new(from_dec_string(IC_2_X), from_dec_string(IC_2_Y)),
+			],
+		)
+		.unwrap()
+	}
+
+	fn prepare_proof() -> GProof<Bls12_381> {
+		GProof::from_uncompressed(
+			&G1UncompressedBytes::new(from_dec_string(PI_A_X), from_dec_string(PI_A_Y)),
+			&G2UncompressedBytes::new(
+				from_dec_string(PI_B_X_C0),
+				from_dec_string(PI_B_X_C1),
+				from_dec_string(PI_B_Y_C0),
+				from_dec_string(PI_B_Y_C1),
+			),
+			&G1UncompressedBytes::new(from_dec_string(PI_C_X), from_dec_string(PI_C_Y)),
+		)
+		.unwrap()
+	}
+
+	#[test]
+	fn verify_with_decoded_verification_key() {
+		let decoded = DecodedVerificationKey::new(SupportedCurve::Bls12381, prepare_vk());
+		let vk = decoded.restore::<Bls12_381>().unwrap();
+
+		assert_eq!(decoded.curve(), SupportedCurve::Bls12381);
+		assert_eq!(decoded.public_inputs_len(), 1);
+		assert!(verify(vk, prepare_proof(), [12.into()].into()).unwrap());
+	}
+
+	#[test]
+	fn decoded_verification_key_keeps_alpha_beta() {
+		let vk = prepare_vk();
+		let alpha_beta = Bls12_381::pairing(&vk.alpha, &vk.beta);
+		let restored = DecodedVerificationKey::new(SupportedCurve::Bls12381, vk)
+			.restore::<Bls12_381>()
+			.unwrap();
+
+		assert_eq!(restored.alpha_beta, alpha_beta);
+	}
+
+	#[test]
+	fn verify_with_wrong_alpha_beta() {
+		let mut decoded = DecodedVerificationKey::new(SupportedCurve::Bls12381, prepare_vk());
+		if let DecodedVerificationKey::Bls12381(vk) = &mut decoded {
+			vk.alpha_beta = Bls12_381::gt_to_bytes(&Gt::identity()).try_into().unwrap();
+		}
+		let vk = decoded.restore::<Bls12_381>().unwrap();
+
+		assert!(!verify(vk, prepare_proof(), [12.into()].into()).unwrap());
+	}
+
+	#[test]
+	fn decoded_verification_key_with_corrupted_point() {
+		let mut decoded = DecodedVerificationKey::new(SupportedCurve::Bls12381, prepare_vk());
+		if let DecodedVerificationKey::Bls12381(vk) = &mut decoded {
+			// Compression flag, which uncompressed points must not have
+			vk.ic[1][0] |= 0x80;
+		}
+
+		assert!(decoded.restore::<Bls12_381>().is_err());
+	}
+
+	#[test]
+	fn verify_batch_of_correct_proofs() {
+		let proofs =
+			vec![(prepare_proof(), [12.into()].into()), (prepare_proof(), [12.into()].into())];
+
+		assert_eq!(verify_batch(prepare_vk(), proofs).unwrap(), vec![true, true])
+	}
+
+	#[test]
+	fn verify_batch_with_incorrect_proof() {
+		let proofs = vec![
+			(prepare_proof(), [12.into()].into()),
+			(prepare_proof(), [33.into()].into()),
+			(prepare_proof(), [12.into()].into()),
+		];
+
+		assert_eq!(verify_batch(prepare_vk(), proofs).unwrap(), vec![true, false, true])
+	}
+
+	#[test]
+	fn verify_empty_batch() {
+		assert_eq!(verify_batch(prepare_vk(), Vec::new()).unwrap(), Vec::<bool>::new())
+	}
+
+	#[test]
+	fn verify_batch_with_incorrect_public_inputs_len() {
+		let proofs = vec![
+			(prepare_proof(), [12.into()].into()),
+			(prepare_proof(), [12.into(), 12.into()].into()),
+		];
+
+		assert_eq!(
+			verify_batch(prepare_vk(), proofs).err().unwrap(),
+			VerificationError::InvalidVerificationKey
+		)
+	}
+
+	#[test]
+	fn public_inputs_from_scalar_field_are_ok() {
+		// r - 1
+		let mut max_scalar = [0; 32];
+		max_scalar.copy_from_slice(
+			&from_dec_string(
+				"52435875175126190479447740508185965837690552500527637822603658699938581184512",
+			)[16..],
+		);
+		let mut small_scalar = [0; 32];
+		small_scalar.copy_from_slice(&from_dec_string("33")[16..]);
+
+		let inputs = prepare_public_inputs::<Bls12_381>(vec![max_scalar, small_scalar]).unwrap();
+		assert_eq!(inputs, vec![-Scalar::one(), Scalar::from(33)]);
+	}
+
+	#[test]
+	fn public_inputs_out_of_scalar_field_are_rejected() {
+		// r
+		let mut modulus = [0; 32];
+		modulus.copy_from_slice(
+			&from_dec_string(
+				"52435875175126190479447740508185965837690552500527637822603658699938581184513",
+			)[16..],
+		);
+
+		assert_eq!(
+			prepare_public_inputs::<Bls12_381>(vec![modulus]).err().unwrap(),
+			PublicInputsCreationError::ScalarCreationError
+		);
+		assert_eq!(
+			prepare_public_inputs::<Bls12_381>(vec![[0xff; 32]]).err().unwrap(),
+			PublicInputsCreationError::ScalarCreationError
+		);
+	}
+
+	#[test]
+	fn scalar_from_hash_is_reduced() {
+		// r + 1
+		let mut hash = [0; 32];
+		hash.copy_from_slice(
+			&from_dec_string(
+				"52435875175126190479447740508185965837690552500527637822603658699938581184514",
+			)[16..],
+		);
+
+		assert_eq!(Bls12_381::scalar_from_hash(hash), Scalar::one());
+	}
+
+	#[test]
+	fn scalar_to_bytes_is_big_endian() {
+		let mut bytes = [0; 32];
+		bytes[30] = 1;
+		bytes[31] = 2;
+		let scalar = Bls12_381::scalar_from_public_input(bytes).unwrap();
+
+		assert_eq!(scalar, Bls12_381::scalar_from_u64(258));
+		assert_eq!(Bls12_381::scalar_to_bytes(&scalar), bytes);
+	}
+
+	#[test]
+	fn point_not_on_curve() {
+		let mut y = from_dec_string(PI_A_Y);
+		y[47] ^= 1;
+
+		assert_eq!(
+			Bls12_381::g1_from_coordinates(from_dec_string(PI_A_X), y).err(),
+			Some(PointError::NotOnCurve)
+		);
+	}
+
+	#[test]
+	fn point_not_in_subgroup() {
+		// y² = x³ + 4, but the point is not in the subgroup of order r
+		let y = "1630892974828014537729259858097113969650871260980656934049590190201941782487224876496582135785777461178964897591404";
+
+		assert_eq!(
+			Bls12_381::g1_from_coordinates(from_dec_string("4"), from_dec_string(y)).err(),
+			Some(PointError::NotInSubgroup)
+		);
+	}
+
+	#[test]
+	fn coordinate_out_of_base_field() {
+		let p = "4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559787";
+
+		assert_eq!(
+			Bls12_381::g1_from_coordinates(from_dec_string(p), from_dec_string(PI_A_Y)).err(),
+			Some(PointError::NonCanonicalCoordinate)
+		);
+	}
+
+	#[test]
+	fn compressed_points() {
+		let mut bytes = [0u8; 48];
+		// Compressed point at infinity
+		bytes[0] = 0xc0;
+		let infinity = Bls12_381::g1_from_compressed(&bytes).unwrap();
+		assert_eq!(finite_g1::<Bls12_381>(infinity).err(), Some(PointError::PointAtInfinity));
+
+		bytes[47] = 1;
+		assert_eq!(
+			Bls12_381::g1_from_compressed(&bytes).err(),
+			Some(PointError::NonCanonicalCoordinate)
+		);
+		// x³ + 4 is not a square for x = 1
+		bytes[0] = 0x80;
+		assert_eq!(Bls12_381::g1_from_compressed(&bytes).err(), Some(PointError::NotOnCurve));
+		bytes[0] = 0x00;
+		assert_eq!(
+			Bls12_381::g1_from_compressed(&bytes).err(),
+			Some(PointError::NonCanonicalCoordinate)
+		);
+	}
+
 	fn from_dec_string(number: &str) -> [u8; 48] {
 		let mut bytes: [u8; 48] = [0; 48];
 		U256::from_dec_str(number).unwrap().to_big_endian(bytes.as_mut_slice());
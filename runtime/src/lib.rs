@@ -103,10 +103,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	//   `spec_version`, and `authoring_version` are the same between Wasm and native.
 	// This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
 	//   the compatible custom types.
-	spec_version: 100,
+	spec_version: 101,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 1,
+	transaction_version: 2,
 	state_version: 1,
 };
 
@@ -275,11 +275,17 @@ impl pallet_sudo::Config for Runtime {
 
 /// Configure the pallet-template in pallets/zk-snarks.
 impl pallet_zk_snarks::Config for Runtime {
-	type MaxPublicInputsLength = ConstU32<9>;
-	type MaxProofLength = ConstU32<1115>;
-	type MaxVerificationKeyLength = ConstU32<4079>;
+	type MaxPublicInputsLength = ConstU32<1024>;
+	type MaxProofLength = ConstU32<4096>;
+	type MaxVerificationKeyLength = ConstU32<8192>;
+	type MaxBatchSize = ConstU32<16>;
+	type VerificationRecordLifetime = ConstU32<{ 7 * DAYS }>;
 	type RuntimeEvent = RuntimeEvent;
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
 	type WeightInfo = pallet_zk_snarks::weights::SubstrateWeight<Runtime>;
+	type SetupOrigin = frame_system::EnsureRoot<AccountId>;
+	type OnProofVerified = ();
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -318,6 +324,7 @@ pub type SignedExtra = (
 	frame_system::CheckNonce<Runtime>,
 	frame_system::CheckWeight<Runtime>,
 	pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+	pallet_zk_snarks::CheckZkProof<Runtime>,
 );
 
 /// Unchecked extrinsic type as expected by this runtime.
@@ -480,6 +487,20 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_zk_snarks_runtime_api::ZkSnarksApi<Block> for Runtime {
+		fn verify_proof(
+			circuit_id: pallet_zk_snarks::CircuitId,
+			proof: Vec<u8>,
+			public_inputs: Vec<u8>,
+		) -> Result<bool, sp_runtime::DispatchError> {
+			ZKSnarks::dry_run_verify(circuit_id, proof, public_inputs)
+		}
+
+		fn get_verification_key(circuit_id: pallet_zk_snarks::CircuitId) -> Option<Vec<u8>> {
+			ZKSnarks::verification_key(circuit_id)
+		}
+	}
+
 	impl pallet_transaction_payment_rpc_runtime_api::TransactionPaymentCallApi<Block, Balance, RuntimeCall>
 		for Runtime
 	{